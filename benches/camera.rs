@@ -21,10 +21,10 @@ fn bench_camera(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     Camera::parse(stream(test_tokens)).expect("Benchmark input should be valid Camera");
 
-    let camera = tokenize(input_str);
+    let camera = tokenize(input_str).unwrap();
 
     c.bench_function("parse camera", |b| {
         b.iter_batched(
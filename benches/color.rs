@@ -6,7 +6,7 @@ use mnk_vmf::{
 };
 
 fn bench_color(c: &mut Criterion) {
-    let color = tokenize(r#""color" "10 100 250""#);
+    let color = tokenize(r#""color" "10 100 250""#).unwrap();
 
     c.bench_function("parse color", |b| {
         b.iter_batched(
@@ -14,10 +14,10 @@ fn bench_cordon(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     Cordon::parse(stream(test_tokens)).expect("Benchmark input should be valid Cordon");
 
-    let cordon = tokenize(input_str);
+    let cordon = tokenize(input_str).unwrap();
 
     c.bench_function("parse cordon", |b| {
         b.iter_batched(
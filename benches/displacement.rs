@@ -52,10 +52,10 @@ fn bench_dispinfo(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     DispInfo::parse(stream(test_tokens)).expect("Benchmark input should be valid DispInfo");
 
-    let dispinfo = tokenize(input_str);
+    let dispinfo = tokenize(input_str).unwrap();
 
     c.bench_function("parse dispinfo", |b| {
         b.iter_batched(
@@ -16,10 +16,10 @@ fn bench_editor(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     EditorData::parse(stream(test_tokens)).expect("Benchmark input should be valid EditorData");
 
-    let editor = tokenize(input_str);
+    let editor = tokenize(input_str).unwrap();
 
     c.bench_function("parse editor", |b| {
         b.iter_batched(
@@ -25,10 +25,10 @@ fn bench_entity(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     Entity::parse(stream(test_tokens)).expect("Benchmark input should be valid Entity");
 
-    let entity = tokenize(input_str);
+    let entity = tokenize(input_str).unwrap();
 
     c.bench_function("parse entity", |b| {
         b.iter_batched(
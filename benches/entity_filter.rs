@@ -0,0 +1,111 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mnk_vmf::{ParseOptions, VMFValue, VMF};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator wrapper that keeps track of the peak number of live bytes.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Builds a map with `props` prop_static entities and `triggers` trigger_multiple entities.
+fn prop_heavy_map(props: usize, triggers: usize) -> String {
+    let mut src = String::from("world\n{\n\t\"id\" \"1\"\n\t\"classname\" \"worldspawn\"\n}\n");
+    for i in 0..props + triggers {
+        let classname = if i < props {
+            "prop_static"
+        } else {
+            "trigger_multiple"
+        };
+        src.push_str(&format!(
+            "entity\n{{\n\t\"id\" \"{id}\"\n\t\"classname\" \"{classname}\"\n\t\"model\" \"models/props/crate{id}.mdl\"\n\t\"angles\" \"0 90 0\"\n\t\"skin\" \"0\"\n\t\"solid\" \"6\"\n\t\"origin\" \"{id} 0 0\"\n\teditor\n\t{{\n\t\t\"color\" \"255 255 0\"\n\t\t\"visgroupshown\" \"1\"\n\t\t\"visgroupautoshown\" \"1\"\n\t\t\"logicalpos\" \"[0 0]\"\n\t}}\n}}\n",
+            id = i + 2,
+        ));
+    }
+    src
+}
+
+fn triggers_only() -> ParseOptions {
+    ParseOptions {
+        entity_filter: Some(Box::new(|classname| classname.starts_with("trigger_"))),
+        ..Default::default()
+    }
+}
+
+/// Returns the peak heap usage (in bytes) while parsing, on top of what was live before.
+fn peak_parse_memory(vmf: &VMF, options: &ParseOptions) -> (usize, usize) {
+    let baseline = ALLOC.current.load(Ordering::Relaxed);
+    ALLOC.peak.store(baseline, Ordering::Relaxed);
+
+    let data = vmf.parse_with(options).expect("Failed to parse VMF");
+    let entities = data
+        .iter()
+        .filter(|value| matches!(value, VMFValue::Entity(_)))
+        .count();
+    let peak = ALLOC.peak.load(Ordering::Relaxed) - baseline;
+    drop(data);
+
+    (entities, peak)
+}
+
+fn bench_entity_filter(c: &mut Criterion) {
+    let path = std::env::temp_dir().join("mnk_vmf_prop_heavy.vmf");
+    std::fs::write(&path, prop_heavy_map(4000, 10)).expect("Failed to write bench map");
+    let vmf = VMF::open(Path::new(&path)).expect("Failed to open VMF");
+
+    let filtered = triggers_only();
+    for (name, options) in [("all", &ParseOptions::default()), ("triggers", &filtered)] {
+        let (entities, peak) = peak_parse_memory(&vmf, options);
+        eprintln!(
+            "entity_filter/{}: {} entities kept, peak heap {} KiB",
+            name,
+            entities,
+            peak / 1024
+        );
+    }
+
+    let mut group = c.benchmark_group("entity_filter");
+
+    group.bench_with_input(BenchmarkId::new("parse", "all"), &vmf, |b, vmf| {
+        b.iter(|| {
+            let data = vmf.parse_with(&ParseOptions::default()).unwrap();
+            black_box(data);
+        });
+    });
+
+    group.bench_with_input(BenchmarkId::new("parse", "triggers"), &vmf, |b, vmf| {
+        b.iter(|| {
+            let data = vmf.parse_with(&filtered).unwrap();
+            black_box(data);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_entity_filter);
+criterion_main!(benches);
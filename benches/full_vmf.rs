@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use mnk_vmf::vmf::VMF;
+use mnk_vmf::VMF;
 use std::path::Path;
 
 fn bench_full_vmf_parsing(c: &mut Criterion) {
@@ -58,7 +58,7 @@ fn bench_full_vmf_parsing(c: &mut Criterion) {
                 let vmf = VMF::open(Path::new(filename)).expect("Failed to open VMF");
                 let src = vmf.as_str();
                 b.iter(|| {
-                    let tokens = mnk_vmf::util::tokenize(black_box(src));
+                    let tokens = mnk_vmf::util::tokenize(black_box(src)).unwrap();
                     black_box(tokens);
                 });
             },
@@ -0,0 +1,35 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mnk_vmf::parse_vmf;
+
+/// Builds a logic-heavy map: an empty world and `count` light entities.
+fn light_heavy_map(count: usize) -> String {
+    let mut src = String::from("world\n{\n\t\"id\" \"1\"\n\t\"classname\" \"worldspawn\"\n}\n");
+    for i in 0..count {
+        src.push_str(&format!(
+            "entity\n{{\n\t\"id\" \"{id}\"\n\t\"classname\" \"light\"\n\t\"targetname\" \"light_{i}\"\n\t\"_light\" \"255 255 255 400\"\n\t\"_lightHDR\" \"-1 -1 -1 1\"\n\t\"_lightscaleHDR\" \"1\"\n\t\"_quadratic_attn\" \"1\"\n\t\"spawnflags\" \"0\"\n\t\"style\" \"0\"\n\t\"origin\" \"{x} {y} 128\"\n\teditor\n\t{{\n\t\t\"color\" \"220 30 220\"\n\t\t\"visgroupshown\" \"1\"\n\t\t\"visgroupautoshown\" \"1\"\n\t\t\"logicalpos\" \"[0 {i}]\"\n\t}}\n}}\n",
+            id = i + 2,
+            x = (i % 100) * 64,
+            y = (i / 100) * 64,
+        ));
+    }
+    src
+}
+
+fn bench_many_entities(c: &mut Criterion) {
+    let src = light_heavy_map(5000);
+    let blocks = parse_vmf(&src).expect("Generated map should parse");
+    assert_eq!(blocks.len(), 5001);
+
+    let mut group = c.benchmark_group("many_entities");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.bench_function("parse 5000 lights", |b| {
+        b.iter(|| {
+            let data = parse_vmf(black_box(&src)).unwrap();
+            black_box(data);
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_many_entities);
+criterion_main!(benches);
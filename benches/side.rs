@@ -18,7 +18,8 @@ fn bench_side(c: &mut Criterion) {
             "vaxis" "[1 0 0 20] 0.125"
             "rotation" "90"
         }"#,
-    );
+    )
+    .unwrap();
 
     c.bench_function("parse side", |b| {
         b.iter_batched(
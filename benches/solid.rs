@@ -51,10 +51,10 @@ fn bench_solid(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     Solid::parse(stream(test_tokens)).expect("Benchmark input should be valid Solid");
 
-    let solid = tokenize(input_str);
+    let solid = tokenize(input_str).unwrap();
 
     c.bench_function("parse solid", |b| {
         b.iter_batched(
@@ -15,7 +15,8 @@ fn bench_versioninfo(c: &mut Criterion) {
                   "formatversion" "100"
                   "prefab" "0"
                 }"#,
-    );
+    )
+    .unwrap();
 
     c.bench_function("parse versioninfo", |b| {
         b.iter_batched(
@@ -25,7 +25,8 @@ fn bench_versioninfo(c: &mut Criterion) {
             "bShowLightingPreview" "0"
             "bShowWireframe" "1"
         }"#,
-    );
+    )
+    .unwrap();
 
     c.bench_function("parse viewsettings", |b| {
         b.iter_batched(
@@ -30,7 +30,7 @@ fn bench_visgroups(c: &mut Criterion) {
         }
     "#;
 
-    let tokens = tokenize(input);
+    let tokens = tokenize(input).unwrap();
 
     c.bench_function("parse visgroups", |b| {
         b.iter_batched(
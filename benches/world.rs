@@ -82,10 +82,10 @@ fn bench_world(c: &mut Criterion) {
         }"#;
 
     // Validate the input parses correctly before benchmarking
-    let test_tokens = tokenize(input_str);
+    let test_tokens = tokenize(input_str).unwrap();
     World::parse(stream(test_tokens)).expect("Benchmark input should be valid World");
 
-    let world = tokenize(input_str);
+    let world = tokenize(input_str).unwrap();
 
     c.bench_function("parse world", |b| {
         b.iter_batched(
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::ParsedVmf;
+use crate::types::{geometry::polygon_area, Side, Solid};
+
+/// How often one material is used on brush faces, see [`ParsedVmf::material_report`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MaterialUsage {
+    /// Number of sides using the material.
+    pub faces: usize,
+    /// Ids of the solids owning those sides, in document order and without duplicates.
+    pub solids: Vec<u32>,
+}
+
+/// Brush material usage of a whole map, sorted by material.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MaterialReport<'a> {
+    pub materials: BTreeMap<&'a str, MaterialUsage>,
+}
+
+impl MaterialReport<'_> {
+    /// Returns the usage of `material`, matched exactly.
+    pub fn get(&self, material: &str) -> Option<&MaterialUsage> {
+        self.materials.get(material)
+    }
+}
+
+impl<'src> ParsedVmf<'src> {
+    /// Returns every model path used by entities, sorted and without duplicates.
+    ///
+    /// This covers the `model` key of every entity, except brush entity references like
+    /// `*3`, and any other keyvalue holding a `.mdl` path (e.g. `gibmodel`).
+    pub fn models(&self) -> BTreeSet<&str> {
+        let mut models = BTreeSet::new();
+        for entity in self.entities() {
+            if let Some(model) = entity.model_path() {
+                models.insert(model);
+            }
+            models.extend(
+                entity
+                    .properties
+                    .values()
+                    .map(|value| value.as_ref())
+                    .filter(|value| is_model_path(value)),
+            );
+        }
+        models.remove("");
+        models
+    }
+
+    /// Counts the brush sides using each material and lists the solids they belong to.
+    ///
+    /// Covers world brushes and brush entities.
+    pub fn material_report(&self) -> MaterialReport<'_> {
+        let mut report = MaterialReport::default();
+        for (solid, side) in self.sides() {
+            let usage = report.materials.entry(side.material.as_ref()).or_default();
+            usage.faces += 1;
+            if usage.solids.last() != Some(&solid.id) {
+                usage.solids.push(solid.id);
+            }
+        }
+        report
+    }
+
+    /// Iterates over every brush side using a `tools/` material, see
+    /// [`Side::is_tool_texture`](crate::types::Side::is_tool_texture).
+    ///
+    /// Covers world brushes and brush entities, in the same order as [`sides`](Self::sides).
+    pub fn tool_texture_faces(&self) -> impl Iterator<Item = (&Solid<'src>, &Side<'src>)> {
+        self.sides().filter(|(_, side)| side.is_tool_texture())
+    }
+
+    /// Sums the face area of every brush side by material, in square units.
+    ///
+    /// Covers world brushes and brush entities. Sides that don't form a face add nothing,
+    /// displacements are counted with the area of their base face.
+    pub fn material_areas(&self) -> HashMap<&str, f32> {
+        let mut areas = HashMap::new();
+        for solid in self.solids() {
+            for (side, polygon) in solid.sides.iter().zip(solid.face_polygons()) {
+                *areas.entry(side.material.as_ref()).or_insert(0.0) += polygon_area(&polygon);
+            }
+        }
+        areas
+    }
+}
+
+fn is_model_path(value: &str) -> bool {
+    value
+        .get(value.len().saturating_sub(4)..)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(".mdl"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::ParsedVmf;
+    use crate::VMF;
+
+    #[test]
+    fn test_models() {
+        let vmf: VMF = r#"
+        entity
+        {
+            "id" "1"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+        }
+        entity
+        {
+            "id" "2"
+            "classname" "prop_physics"
+            "model" "models/props_junk/wood_crate001a.mdl"
+            "gibmodel" "models/props_junk/wood_crate001a_chunk01.mdl"
+        }
+        entity
+        {
+            "id" "3"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+        }
+        entity
+        {
+            "id" "4"
+            "classname" "func_brush"
+            "model" "*1"
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        assert_eq!(
+            doc.models().into_iter().collect::<Vec<_>>(),
+            vec![
+                "models/props_c17/oildrum001.mdl",
+                "models/props_junk/wood_crate001a.mdl",
+                "models/props_junk/wood_crate001a_chunk01.mdl",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_material_areas() {
+        let vmf: VMF = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side { "id" "3" "plane" "(0 64 64) (64 64 64) (64 0 64)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+                side { "id" "4" "plane" "(0 0 0) (64 0 0) (64 64 0)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+                side { "id" "5" "plane" "(0 64 64) (0 0 64) (0 0 0)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+                side { "id" "6" "plane" "(64 64 0) (64 0 0) (64 0 64)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+                side { "id" "7" "plane" "(64 64 64) (0 64 64) (0 64 0)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+                side { "id" "8" "plane" "(64 0 0) (0 0 0) (0 0 64)" "material" "DEV/DEV_MEASUREGENERIC01B" }
+            }
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let areas = doc.material_areas();
+        assert_eq!(areas.len(), 1);
+        let area = areas["DEV/DEV_MEASUREGENERIC01B"];
+        assert!((area - 6.0 * 64.0 * 64.0).abs() < 0.5, "area was {}", area);
+        assert_eq!(ParsedVmf::default().material_areas(), HashMap::new());
+    }
+
+    #[test]
+    fn test_material_report() {
+        let vmf: VMF = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side { "id" "3" "material" "BRICK/BRICKWALL001A" }
+                side { "id" "4" "material" "BRICK/BRICKWALL001A" }
+                side { "id" "5" "material" "BRICK/BRICKWALL001A" }
+                side { "id" "6" "material" "BRICK/BRICKWALL001A" }
+                side { "id" "7" "material" "BRICK/BRICKWALL001A" }
+                side { "id" "8" "material" "BRICK/BRICKWALL001A" }
+            }
+            solid
+            {
+                "id" "9"
+                side { "id" "10" "material" "TOOLS/TOOLSNODRAW" }
+                side { "id" "11" "material" "TOOLS/TOOLSNODRAW" }
+            }
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let report = doc.material_report();
+        assert_eq!(report.materials.len(), 2);
+        let brick = report.get("BRICK/BRICKWALL001A").unwrap();
+        assert_eq!(brick.faces, 6);
+        assert_eq!(brick.solids, [2]);
+        let nodraw = report.get("TOOLS/TOOLSNODRAW").unwrap();
+        assert_eq!(nodraw.faces, 2);
+        assert_eq!(nodraw.solids, [9]);
+
+        let tool_sides: Vec<_> = doc.tool_texture_faces().map(|(_, side)| side.id).collect();
+        assert_eq!(tool_sides, [10, 11]);
+    }
+}
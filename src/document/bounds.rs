@@ -0,0 +1,226 @@
+use super::ParsedVmf;
+use crate::types::{
+    geometry::{bounds_of, ON_EPSILON},
+    Entity, Point3D,
+};
+
+impl ParsedVmf<'_> {
+    /// Axis aligned bounding box `(mins, maxs)` of the whole map.
+    ///
+    /// Covers world brushes, brush entity solids and the origins of point entities.
+    /// Returns `None` if the document has no geometry and no positioned entities.
+    pub fn world_bounds(&self) -> Option<(Point3D, Point3D)> {
+        let solid_bounds = self
+            .solids()
+            .filter_map(|solid| solid.bounds())
+            .flat_map(|(mins, maxs)| [mins, maxs]);
+        let origins = self
+            .entities()
+            .filter(|entity| entity.solids.is_empty())
+            .filter_map(|entity| entity.origin);
+
+        bounds_of(solid_bounds.chain(origins))
+    }
+
+    /// Returns the entities, hidden ones included, inside the box from `mins` to `maxs`.
+    ///
+    /// Brush entities are matched when the bounding box of their solids touches the box,
+    /// point entities when their origin lies inside it. Entities with neither solids nor
+    /// an origin are never matched.
+    pub fn entities_in_box(&self, mins: Point3D, maxs: Point3D) -> Vec<&Entity<'_>> {
+        let inside = |point: Point3D| point.max(mins) == point && point.min(maxs) == point;
+
+        self.entities()
+            .filter(|entity| match brush_bounds(entity) {
+                Some((entity_mins, entity_maxs)) => {
+                    entity_mins.min(maxs) == entity_mins && entity_maxs.max(mins) == entity_maxs
+                }
+                None => entity.origin.is_some_and(inside),
+            })
+            .collect()
+    }
+
+    /// Returns pairs of world brush ids whose bounding boxes overlap.
+    ///
+    /// This is a cheap first pass, two brushes with overlapping boxes don't have to
+    /// intersect. Brushes that only touch, like two blocks sharing a face, are not reported.
+    /// Each pair is listed once as `(lower id, higher id)`, sorted.
+    pub fn overlapping_solids(&self) -> Vec<(u32, u32)> {
+        let mut boxes: Vec<_> = self
+            .worlds()
+            .flat_map(|world| world.solids.iter())
+            .filter_map(|solid| solid.bounds().map(|(mins, maxs)| (solid.id, mins, maxs)))
+            .collect();
+        // Sweep along x, only boxes starting before the current one ends can overlap it
+        boxes.sort_by(|a, b| a.1.x.total_cmp(&b.1.x));
+
+        let mut pairs = Vec::new();
+        for (i, &(id, mins, maxs)) in boxes.iter().enumerate() {
+            for &(other, other_mins, other_maxs) in boxes[i + 1..]
+                .iter()
+                .take_while(|(_, other_mins, _)| other_mins.x < maxs.x - ON_EPSILON)
+            {
+                let overlaps = other_mins.y < maxs.y - ON_EPSILON
+                    && mins.y < other_maxs.y - ON_EPSILON
+                    && other_mins.z < maxs.z - ON_EPSILON
+                    && mins.z < other_maxs.z - ON_EPSILON;
+                if overlaps {
+                    pairs.push((id.min(other), id.max(other)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// Bounding box `(mins, maxs)` of all solids of a brush entity.
+pub(super) fn brush_bounds(entity: &Entity) -> Option<(Point3D, Point3D)> {
+    bounds_of(
+        entity
+            .solids
+            .iter()
+            .filter_map(|solid| solid.bounds())
+            .flat_map(|(mins, maxs)| [mins, maxs]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::World, util::lex, Parser, VMFValue, VMF};
+
+    #[test]
+    fn test_world_bounds_of_test_vmf() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let (mins, maxs) = doc.world_bounds().unwrap();
+        assert!(mins.x < maxs.x && mins.y < maxs.y && mins.z < maxs.z);
+        for solid in doc.solids() {
+            let (solid_mins, solid_maxs) = solid.bounds().unwrap();
+            assert_eq!(solid_mins.min(mins), mins);
+            assert_eq!(solid_maxs.max(maxs), maxs);
+        }
+        for origin in doc.entities().filter_map(|e| e.origin) {
+            assert_eq!(origin.min(mins), mins);
+            assert_eq!(origin.max(maxs), maxs);
+        }
+    }
+
+    #[test]
+    fn test_world_bounds_point_entities_only() {
+        let entity = |x| {
+            VMFValue::Entity(Box::new(Entity {
+                origin: Some(Point3D::new(x, 0.0, 16.0)),
+                ..Default::default()
+            }))
+        };
+        assert_eq!(ParsedVmf::default().world_bounds(), None);
+
+        let doc = ParsedVmf::new(vec![entity(-32.0), entity(64.0)]);
+        assert_eq!(
+            doc.world_bounds(),
+            Some((
+                Point3D::new(-32.0, 0.0, 16.0),
+                Point3D::new(64.0, 0.0, 16.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_entities_in_box() {
+        let light = Entity::parse(lex(r#"
+        entity { "id" "1" "classname" "light" "origin" "-192 192 128" }
+        "#))
+        .unwrap();
+        let door = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "2"
+            "classname" "func_door"
+            solid
+            {
+                "id" "3"
+                side { "id" "4" "plane" "(256 0 64) (320 0 64) (320 -16 64)" }
+                side { "id" "5" "plane" "(256 -16 0) (320 -16 0) (320 0 0)" }
+                side { "id" "6" "plane" "(256 0 64) (256 -16 64) (256 -16 0)" }
+                side { "id" "7" "plane" "(320 0 0) (320 -16 0) (320 -16 64)" }
+                side { "id" "8" "plane" "(320 0 64) (256 0 64) (256 0 0)" }
+                side { "id" "9" "plane" "(320 -16 0) (256 -16 0) (256 -16 64)" }
+            }
+        }
+        "#))
+        .unwrap();
+        let doc = ParsedVmf::new(vec![
+            VMFValue::Entity(Box::new(light)),
+            VMFValue::Entity(Box::new(door)),
+        ]);
+
+        let ids = |mins, maxs| -> Vec<u32> {
+            doc.entities_in_box(mins, maxs)
+                .iter()
+                .map(|e| e.id)
+                .collect()
+        };
+        // The box only overlaps the door, it doesn't have to contain it
+        assert_eq!(
+            ids(
+                Point3D::new(300.0, -64.0, 32.0),
+                Point3D::new(512.0, 64.0, 512.0)
+            ),
+            vec![2]
+        );
+        assert_eq!(
+            ids(
+                Point3D::new(-256.0, 0.0, 0.0),
+                Point3D::new(0.0, 256.0, 256.0)
+            ),
+            vec![1]
+        );
+        assert!(ids(Point3D::new(0.0, 0.0, 0.0), Point3D::new(64.0, 64.0, 64.0)).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_solids() {
+        let cube = |id: u32, min: [i32; 3], size: i32| {
+            let [x, y, z] = min;
+            let [x2, y2, z2] = [x + size, y + size, z + size];
+            format!(
+                r#"
+                solid
+                {{
+                    "id" "{id}"
+                    side {{ "id" "{s1}" "plane" "({x} {y2} {z2}) ({x2} {y2} {z2}) ({x2} {y} {z2})" }}
+                    side {{ "id" "{s2}" "plane" "({x} {y} {z}) ({x2} {y} {z}) ({x2} {y2} {z})" }}
+                    side {{ "id" "{s3}" "plane" "({x} {y2} {z2}) ({x} {y} {z2}) ({x} {y} {z})" }}
+                    side {{ "id" "{s4}" "plane" "({x2} {y2} {z}) ({x2} {y} {z}) ({x2} {y} {z2})" }}
+                    side {{ "id" "{s5}" "plane" "({x2} {y2} {z2}) ({x} {y2} {z2}) ({x} {y2} {z})" }}
+                    side {{ "id" "{s6}" "plane" "({x2} {y} {z}) ({x} {y} {z}) ({x} {y} {z2})" }}
+                }}
+                "#,
+                s1 = id * 10 + 1,
+                s2 = id * 10 + 2,
+                s3 = id * 10 + 3,
+                s4 = id * 10 + 4,
+                s5 = id * 10 + 5,
+                s6 = id * 10 + 6,
+            )
+        };
+        let source = format!(
+            r#"world {{ "id" "1" "classname" "worldspawn" {} {} {} {} }}"#,
+            cube(4, [0, 0, 0], 64),
+            cube(2, [32, 32, 32], 64),
+            // Shares a face with solid 4, touching isn't overlapping
+            cube(3, [-64, 0, 0], 64),
+            cube(5, [512, 0, 0], 64),
+        );
+        let world = World::parse(lex(&source)).unwrap();
+        let doc = ParsedVmf::new(vec![VMFValue::World(Box::new(world))]);
+
+        for solid in doc.solids() {
+            assert!(solid.bounds().is_some(), "solid {} has no faces", solid.id);
+        }
+        assert_eq!(doc.overlapping_solids(), vec![(2, 4)]);
+    }
+}
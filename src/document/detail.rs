@@ -0,0 +1,153 @@
+use super::ParsedVmf;
+use crate::{types::Entity, VMFValue};
+
+impl ParsedVmf<'_> {
+    /// Moves the solids of every `func_detail` entity into the world and removes the
+    /// entities.
+    ///
+    /// Useful when exporting geometry, where detail brushes should be treated like any
+    /// other world brush. The solids of top-level entities are appended to the first `world`
+    /// block, those of entities in a world's `hidden` blocks to that world, like the parser
+    /// does with hidden world solids. Does nothing if the document has no world.
+    pub fn merge_detail_into_world(&mut self) {
+        if self.worlds().next().is_none() {
+            return;
+        }
+
+        let is_detail = |entity: &Entity| entity.classname == "func_detail";
+        let is_detail_block =
+            |block: &VMFValue| matches!(block, VMFValue::Entity(entity) if is_detail(entity));
+        let top_level_detail = self.blocks.iter().any(is_detail_block);
+        if top_level_detail && self.sources.len() == self.blocks.len() {
+            let mut removed = self.blocks.iter().map(is_detail_block);
+            self.sources.retain(|_| !removed.next().unwrap_or(false));
+        }
+
+        let mut detail_solids = Vec::new();
+        self.blocks.retain_mut(|block| match block {
+            VMFValue::Entity(entity) if is_detail(entity) => {
+                detail_solids.append(&mut entity.solids);
+                false
+            }
+            _ => true,
+        });
+
+        for index in 0..self.blocks.len() {
+            let hides_detail = matches!(
+                &self.blocks[index],
+                VMFValue::World(world) if world.hidden_entities.iter().any(is_detail)
+            );
+            if !hides_detail {
+                continue;
+            }
+            if let Some(VMFValue::World(world)) = self.edit(index) {
+                let (detail, hidden): (Vec<_>, Vec<_>) = std::mem::take(&mut world.hidden_entities)
+                    .into_iter()
+                    .partition(|entity| is_detail(entity));
+                world.hidden_entities = hidden;
+                world.solids.extend(detail.into_iter().flat_map(|entity| entity.solids));
+            }
+        }
+
+        if !top_level_detail {
+            return;
+        }
+        let world = self
+            .blocks
+            .iter()
+            .position(|block| matches!(block, VMFValue::World(_)));
+        if let Some(VMFValue::World(world)) = world.and_then(|index| self.edit(index)) {
+            world.solids.extend(detail_solids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::{Entity, World},
+        util::lex,
+        Parser,
+    };
+
+    #[test]
+    fn test_merge_detail_into_world() {
+        let world = World::parse(lex(r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid { "id" "2" side { "id" "3" "material" "TOOLS/TOOLSNODRAW" } }
+        }
+        "#))
+        .unwrap();
+        let detail = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "4"
+            "classname" "func_detail"
+            solid { "id" "5" side { "id" "6" "material" "BRICK/BRICKWALL001A" } }
+            solid { "id" "7" side { "id" "8" "material" "BRICK/BRICKWALL001A" } }
+        }
+        "#))
+        .unwrap();
+        let light = Entity::parse(lex(r#"entity { "id" "9" "classname" "light" }"#)).unwrap();
+
+        let mut doc = ParsedVmf::new(vec![
+            VMFValue::World(Box::new(world)),
+            VMFValue::Entity(Box::new(detail)),
+            VMFValue::Entity(Box::new(light)),
+        ]);
+        doc.merge_detail_into_world();
+
+        let classnames: Vec<_> = doc.entities().map(|e| e.classname.as_ref()).collect();
+        assert_eq!(classnames, vec!["light"]);
+        let world = doc.worlds().next().unwrap();
+        let ids: Vec<_> = world.solids.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![2, 5, 7]);
+    }
+
+    #[test]
+    fn test_merge_hidden_detail_into_world() {
+        let world = World::parse(lex(r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid { "id" "2" side { "id" "3" } }
+            hidden
+            {
+                entity
+                {
+                    "id" "4"
+                    "classname" "func_detail"
+                    solid { "id" "5" side { "id" "6" } }
+                }
+                entity { "id" "7" "classname" "func_brush" }
+            }
+        }
+        "#))
+        .unwrap();
+
+        let mut doc = ParsedVmf::new(vec![VMFValue::World(Box::new(world))]);
+        doc.merge_detail_into_world();
+
+        let world = doc.world().unwrap();
+        let ids: Vec<_> = world.solids.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![2, 5]);
+        let classnames: Vec<_> = doc.entities().map(|e| e.classname.as_ref()).collect();
+        assert_eq!(classnames, vec!["func_brush"]);
+    }
+
+    #[test]
+    fn test_merge_detail_without_world() {
+        let detail = Entity {
+            classname: "func_detail".into(),
+            ..Default::default()
+        };
+        let mut doc = ParsedVmf::new(vec![VMFValue::Entity(Box::new(detail))]);
+        doc.merge_detail_into_world();
+        assert_eq!(doc.entities().count(), 1);
+    }
+}
@@ -0,0 +1,350 @@
+use std::borrow::Cow;
+
+use super::ParsedVmf;
+use crate::types::{
+    Color, DispInfo, Entity, EntityOutput, Point3D, Properties, Side, Solid, TextureAxis, World,
+};
+use crate::VMFValue;
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed, so hashes can be stored
+/// and compared across builds.
+///
+/// Values are fed in as explicit bytes (little-endian integers, length-prefixed strings)
+/// instead of through `std::hash::Hash`, whose byte layout depends on the platform and
+/// isn't promised to stay the same between Rust versions.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+/// Feeds the fields that affect the compiled map into a hasher.
+/// Editor-only data (editor blocks, visgroups, cameras, cordons, ..) is left out.
+trait ContentHash {
+    fn content_hash(&self, state: &mut Fnv1a);
+}
+
+impl ContentHash for f32 {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        // `-0` and `0` compare equal, so they have to hash equal too
+        let value = if *self == 0.0 { 0.0f32 } else { *self };
+        value.to_bits().content_hash(state);
+    }
+}
+
+impl ContentHash for u8 {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        state.write(&[*self]);
+    }
+}
+
+impl ContentHash for bool {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        u8::from(*self).content_hash(state);
+    }
+}
+
+impl ContentHash for u32 {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        state.write(&self.to_le_bytes());
+    }
+}
+
+impl ContentHash for i32 {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        state.write(&self.to_le_bytes());
+    }
+}
+
+impl ContentHash for u64 {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        state.write(&self.to_le_bytes());
+    }
+}
+
+impl ContentHash for str {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        // The length keeps ("ab", "c") and ("a", "bc") apart
+        (self.len() as u64).content_hash(state);
+        state.write(self.as_bytes());
+    }
+}
+
+impl ContentHash for Cow<'_, str> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.as_ref().content_hash(state);
+    }
+}
+
+impl ContentHash for Color {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        state.write(&[self.r, self.g, self.b]);
+    }
+}
+
+impl ContentHash for Point3D {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.x.content_hash(state);
+        self.y.content_hash(state);
+        self.z.content_hash(state);
+    }
+}
+
+impl<T: ContentHash> ContentHash for Option<T> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        match self {
+            Some(value) => {
+                1u8.content_hash(state);
+                value.content_hash(state);
+            }
+            None => 0u8.content_hash(state),
+        }
+    }
+}
+
+impl<T: ContentHash> ContentHash for [T] {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        (self.len() as u64).content_hash(state);
+        for item in self {
+            item.content_hash(state);
+        }
+    }
+}
+
+impl ContentHash for Properties<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        // Equal maps can list their keys in a different order
+        let mut pairs: Vec<_> = self.iter().collect();
+        pairs.sort();
+        (pairs.len() as u64).content_hash(state);
+        for (key, value) in pairs {
+            key.content_hash(state);
+            value.content_hash(state);
+        }
+    }
+}
+
+impl ContentHash for TextureAxis {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        for value in [self.x, self.y, self.z, self.shift, self.scale] {
+            value.content_hash(state);
+        }
+    }
+}
+
+impl ContentHash for DispInfo {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.power.content_hash(state);
+        self.start_position.content_hash(state);
+        self.elevation.content_hash(state);
+        self.subdiv.content_hash(state);
+        self.normals.content_hash(state);
+        self.distances.content_hash(state);
+        self.offsets.content_hash(state);
+        self.offset_normals.content_hash(state);
+        self.alphas.content_hash(state);
+        self.triangle_tags.content_hash(state);
+        self.allowed_verts.content_hash(state);
+        self.flags.content_hash(state);
+    }
+}
+
+impl ContentHash for Side<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.id.content_hash(state);
+        let (p1, p2, p3) = self.plane;
+        [p1, p2, p3].content_hash(state);
+        self.material.content_hash(state);
+        self.uaxis.content_hash(state);
+        self.vaxis.content_hash(state);
+        self.rotation.content_hash(state);
+        self.lightmapscale.content_hash(state);
+        self.smoothing_groups.content_hash(state);
+        self.dispinfo.content_hash(state);
+    }
+}
+
+impl ContentHash for Solid<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.id.content_hash(state);
+        self.sides.content_hash(state);
+    }
+}
+
+impl ContentHash for EntityOutput<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.output_name.content_hash(state);
+        self.target.content_hash(state);
+        self.input.content_hash(state);
+        self.parameter.content_hash(state);
+        self.delay.content_hash(state);
+        self.times_to_fire.content_hash(state);
+    }
+}
+
+impl ContentHash for World<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.id.content_hash(state);
+        self.classname.content_hash(state);
+        self.detailmaterial.content_hash(state);
+        self.detailvbsp.content_hash(state);
+        self.maxpropscreenwidth.content_hash(state);
+        self.skyname.content_hash(state);
+        self.sounds.content_hash(state);
+        self.maxrange.content_hash(state);
+        self.maxoccludeearea.content_hash(state);
+        self.minoccluderarea.content_hash(state);
+        self.maxoccludeearea_csgo.content_hash(state);
+        self.minoccluderarea_csgo.content_hash(state);
+        self.difficulty_level.content_hash(state);
+        self.hdr_level.content_hash(state);
+        self.solids.content_hash(state);
+        self.hidden_entities.content_hash(state);
+        self.targetname.content_hash(state);
+        self.target.content_hash(state);
+        self.properties.content_hash(state);
+    }
+}
+
+impl ContentHash for Entity<'_> {
+    fn content_hash(&self, state: &mut Fnv1a) {
+        self.id.content_hash(state);
+        self.classname.content_hash(state);
+        self.origin.content_hash(state);
+        self.angles.content_hash(state);
+        self.targetname.content_hash(state);
+        self.parentname.content_hash(state);
+        self.target.content_hash(state);
+        self.model.content_hash(state);
+        self.skin.content_hash(state);
+        self.spawnflags.content_hash(state);
+        self.rendermode.content_hash(state);
+        self.renderamt.content_hash(state);
+        self.rendercolor.content_hash(state);
+        self.renderfx.content_hash(state);
+        self.disablex360.content_hash(state);
+        self.disableshadows.content_hash(state);
+        self.disablereceiveshadows.content_hash(state);
+        self.startdisabled.content_hash(state);
+        self.fademindist.content_hash(state);
+        self.fademaxdist.content_hash(state);
+        self.fadescale.content_hash(state);
+        self.outputs.content_hash(state);
+        self.properties.content_hash(state);
+        self.solids.content_hash(state);
+    }
+}
+
+impl ParsedVmf<'_> {
+    /// Returns a stable hash of the map content: world settings, brushes, entities and
+    /// their connections.
+    ///
+    /// Editor metadata is ignored (editor blocks, visgroups, view settings, cameras,
+    /// cordons, version info), as is the formatting of the source. Two maps with the same
+    /// hash compile the same, so a build pipeline can use it to skip unchanged maps.
+    pub fn content_hash(&self) -> u64 {
+        let mut state = Fnv1a::default();
+        for block in &self.blocks {
+            match block {
+                VMFValue::World(world) => {
+                    0u8.content_hash(&mut state);
+                    world.content_hash(&mut state);
+                }
+                VMFValue::Entity(entity) => {
+                    1u8.content_hash(&mut state);
+                    entity.content_hash(&mut state);
+                }
+                _ => {}
+            }
+        }
+        state.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VMF;
+
+    fn content_hash(src: &str) -> u64 {
+        let vmf: VMF = src.parse().unwrap();
+        vmf.parse_document().unwrap().content_hash()
+    }
+
+    #[test]
+    fn test_content_hash_ignores_formatting() {
+        let compact = r#"versioninfo { "editorversion" "400" "editorbuild" "6157" "mapversion" "1" "formatversion" "100" "prefab" "0" }
+world { "id" "1" "classname" "worldspawn" "skyname" "sky_day01_01" "detailvbsp" "detail.vbsp"
+solid { "id" "2" side { "id" "3" "plane" "(0 0 0) (0 64 0) (64 64 0)" "material" "DEV/DEV_MEASUREGENERIC01B" } editor { "color" "0 255 0" } } }
+entity { "id" "4" "classname" "light" "origin" "0 0 64" "_light" "255 255 255 200" "style" "0" }"#;
+
+        let formatted = r#"versioninfo
+{
+	"editorversion" "400"
+	"editorbuild" "6157"
+	"mapversion" "57"
+	"formatversion" "100"
+	"prefab" "0"
+}
+world
+{
+	"id" "1"
+	"classname" "worldspawn"
+	"detailvbsp" "detail.vbsp"
+	"skyname" "sky_day01_01"
+	solid
+	{
+		"id" "2"
+		side
+		{
+			"id" "3"
+			"plane" "(0 0 0) (0 64 0) (64 64 0)"
+			"material" "DEV/DEV_MEASUREGENERIC01B"
+		}
+		editor
+		{
+			"color" "220 30 220"
+		}
+	}
+}
+entity
+{
+	"id" "4"
+	"classname" "light"
+	"style" "0"
+	"_light" "255 255 255 200"
+	"origin" "0.0 0.0 64.0"
+}
+"#;
+
+        assert_eq!(content_hash(compact), content_hash(formatted));
+        assert_ne!(
+            content_hash(compact),
+            content_hash(&compact.replace("\"0 0 64\"", "\"0 0 65\""))
+        );
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        let src = include_str!("../../test.vmf");
+        assert_eq!(content_hash(src), content_hash(src));
+        assert_ne!(content_hash(src), content_hash(""));
+    }
+
+    #[test]
+    fn test_content_hash_value_is_fixed() {
+        // Stored hashes stay valid only as long as this value doesn't change
+        let src = r#"entity { "id" "1" "classname" "info_target" "origin" "0 0 64" }"#;
+        assert_eq!(content_hash(src), 0x8336_e33f_67f9_146f);
+    }
+}
@@ -0,0 +1,82 @@
+use super::ParsedVmf;
+use crate::types::Entity;
+
+/// The entity name in a `parentname` value, without the `,attachment` suffix.
+fn parent_name<'a>(entity: &'a Entity) -> Option<&'a str> {
+    let parentname = entity.parentname.as_deref()?;
+    let name = parentname.split(',').next().unwrap_or(parentname).trim();
+    (!name.is_empty()).then_some(name)
+}
+
+fn has_targetname(entity: &Entity, name: &str) -> bool {
+    entity
+        .targetname
+        .as_deref()
+        .is_some_and(|targetname| targetname.eq_ignore_ascii_case(name))
+}
+
+impl<'src> ParsedVmf<'src> {
+    /// Returns the entity `entity` is parented to with its `parentname` key.
+    ///
+    /// Names are matched case-insensitively, and an attachment in the value
+    /// (`"parentname" "train,wheel"`) is ignored. If several entities share the parent's
+    /// targetname, the first one in the document is returned.
+    pub fn parent_of(&self, entity: &Entity) -> Option<&Entity<'src>> {
+        let name = parent_name(entity)?;
+        self.entities().find(|parent| has_targetname(parent, name))
+    }
+
+    /// Returns every entity whose `parentname` points to the targetname of `entity`, in
+    /// document order.
+    ///
+    /// A targetname can be shared by several entities, so this also includes the children
+    /// of the others with the same name. Returns nothing for an entity without a name.
+    pub fn children_of(&self, entity: &Entity) -> Vec<&Entity<'src>> {
+        let Some(name) = entity.targetname.as_deref() else {
+            return Vec::new();
+        };
+        self.entities()
+            .filter(|child| {
+                parent_name(child).is_some_and(|parent| parent.eq_ignore_ascii_case(name))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{types::Entity, VMF};
+
+    #[test]
+    fn test_parent_chain() {
+        let vmf: VMF = r#"
+        entity { "id" "1" "classname" "func_tracktrain" "targetname" "train" }
+        entity { "id" "2" "classname" "prop_dynamic" "targetname" "cart" "parentname" "train" }
+        entity { "id" "3" "classname" "prop_dynamic" "targetname" "Cart" "parentname" "train,a" }
+        entity { "id" "4" "classname" "light_dynamic" "targetname" "lamp" "parentname" "cart" }
+        entity { "id" "5" "classname" "info_target" "parentname" "missing" }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+        let entity = |id: u32| doc.entities().find(|entity| entity.id == id).unwrap();
+        let ids = |entities: Vec<&Entity>| -> Vec<u32> {
+            entities.iter().map(|entity| entity.id).collect()
+        };
+
+        // lamp -> cart -> train
+        let cart = doc.parent_of(entity(4)).unwrap();
+        assert_eq!(cart.id, 2);
+        assert_eq!(doc.parent_of(cart).unwrap().id, 1);
+        assert_eq!(doc.parent_of(entity(3)).unwrap().id, 1);
+        assert!(doc.parent_of(entity(1)).is_none());
+        assert!(doc.parent_of(entity(5)).is_none());
+
+        assert_eq!(ids(doc.children_of(entity(1))), [2, 3]);
+        // Both carts share the name, so both see the lamp as their child
+        assert_eq!(ids(doc.children_of(entity(2))), [4]);
+        assert_eq!(ids(doc.children_of(entity(3))), [4]);
+        assert!(doc.children_of(entity(4)).is_empty());
+        assert!(doc.children_of(entity(5)).is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use super::{lint::SIDE_REFERENCE_KEYS, ParsedVmf};
+use crate::{
+    types::{EditorData, Entity, Group, Solid},
+    VMFValue,
+};
+
+/// Hands out fresh object ids for generated worlds, entities, solids and sides.
+///
+/// Hammer expects ids to be unique, so when new objects are added to a parsed map the
+/// allocator should be seeded from that map with [`IdAllocator::from_document`].
+///
+/// Ids are `u32`, so an allocator that has handed out `u32::MAX` is exhausted. From then on
+/// [`next`](IdAllocator::next) panics and [`next_id`](IdAllocator::next_id) returns `None`.
+///
+/// usage:
+/// ```ignore
+/// let mut ids = IdAllocator::from_document(&doc);
+/// let solid_id = ids.next();
+/// let side_id = ids.next_id().ok_or("out of ids")?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdAllocator {
+    /// `None` once every id has been handed out.
+    next: Option<u32>,
+}
+
+impl IdAllocator {
+    /// Creates an allocator whose first id is `first`.
+    pub fn new(first: u32) -> Self {
+        Self { next: Some(first) }
+    }
+
+    /// Creates an allocator that starts right after the highest id used in `doc`.
+    ///
+    /// A document that already uses `u32::MAX` gets an exhausted allocator.
+    pub fn from_document(doc: &ParsedVmf) -> Self {
+        Self {
+            next: doc.max_id().checked_add(1),
+        }
+    }
+
+    /// Returns the next free id and advances the allocator.
+    ///
+    /// # Panics
+    /// Panics if the ids ran out, see [`IdAllocator::next_id`] for a version that doesn't.
+    // Not an `Iterator`, the allocator always has an id to hand out until it panics
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u32 {
+        self.next_id().expect("IdAllocator ran out of ids")
+    }
+
+    /// Returns the next free id and advances the allocator, or `None` if the ids ran out.
+    pub fn next_id(&mut self) -> Option<u32> {
+        let id = self.next?;
+        self.next = id.checked_add(1);
+        Some(id)
+    }
+
+    /// Returns the id that the next call to [`IdAllocator::next_id`] will hand out.
+    pub fn peek(&self) -> Option<u32> {
+        self.next
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Returns the highest object id used in `block`, see [`ParsedVmf::max_id`].
+pub(crate) fn block_max_id(block: &VMFValue) -> u32 {
+    fn group_max(group: &Group) -> u32 {
+        group.groups.iter().map(group_max).fold(group.id, u32::max)
+    }
+    fn solids_max(solids: &[Solid]) -> u32 {
+        solids
+            .iter()
+            .flat_map(|solid| {
+                std::iter::once(solid.id).chain(solid.sides.iter().map(|side| side.id))
+            })
+            .fold(0, u32::max)
+    }
+    fn entity_max(entity: &Entity) -> u32 {
+        entity.id.max(solids_max(&entity.solids))
+    }
+
+    match block {
+        VMFValue::World(world) => world
+            .group
+            .iter()
+            .map(group_max)
+            .chain(world.hidden_entities.iter().map(entity_max))
+            .fold(world.id.max(solids_max(&world.solids)), u32::max),
+        VMFValue::Entity(entity) => entity_max(entity),
+        VMFValue::Cameras(cameras) => cameras.cameras.iter().map(|c| c.id).fold(0, u32::max),
+        _ => 0,
+    }
+}
+
+impl ParsedVmf<'_> {
+    /// Gives every world, entity, solid, side, group and camera a fresh id, counting up
+    /// from 1 in document order.
+    ///
+    /// All objects draw from one sequence, so side ids never collide with solid or entity
+    /// ids. References are rewritten to match: the `groupid` of editor data and side lists
+    /// such as the `sides` key of overlays. Visgroup ids are a separate namespace and are
+    /// left alone.
+    pub fn renumber_ids(&mut self) {
+        // Counting from 1, a document can't hold enough objects to run out of ids
+        let mut ids = IdAllocator::default();
+        let mut side_ids = HashMap::new();
+        let mut group_ids = HashMap::new();
+        let mut edited = Vec::new();
+
+        for (index, block) in self.blocks.iter_mut().enumerate() {
+            match block {
+                VMFValue::World(world) => {
+                    world.id = ids.next();
+                    for solid in &mut world.solids {
+                        renumber_solid(solid, &mut ids, &mut side_ids);
+                    }
+                    if let Some(group) = &mut world.group {
+                        renumber_group(group, &mut ids, &mut group_ids);
+                    }
+                    for entity in &mut world.hidden_entities {
+                        renumber_entity(entity, &mut ids, &mut side_ids);
+                    }
+                }
+                VMFValue::Entity(entity) => renumber_entity(entity, &mut ids, &mut side_ids),
+                VMFValue::Cameras(cameras) => {
+                    for camera in &mut cameras.cameras {
+                        camera.id = ids.next();
+                    }
+                }
+                _ => continue,
+            }
+            edited.push(index);
+        }
+
+        for block in &mut self.blocks {
+            match block {
+                VMFValue::World(world) => {
+                    remap_group_id(&mut world.editor, &group_ids);
+                    for solid in &mut world.solids {
+                        remap_group_id(&mut solid.editor, &group_ids);
+                    }
+                    if let Some(group) = &mut world.group {
+                        remap_nested_group_ids(group, &group_ids);
+                    }
+                    for entity in &mut world.hidden_entities {
+                        remap_entity_references(entity, &side_ids, &group_ids);
+                    }
+                }
+                VMFValue::Entity(entity) => {
+                    remap_entity_references(entity, &side_ids, &group_ids)
+                }
+                _ => {}
+            }
+        }
+
+        for index in edited {
+            self.mark_edited(index);
+        }
+    }
+}
+
+fn renumber_entity(entity: &mut Entity, ids: &mut IdAllocator, side_ids: &mut HashMap<u32, u32>) {
+    entity.id = ids.next();
+    for solid in &mut entity.solids {
+        renumber_solid(solid, ids, side_ids);
+    }
+}
+
+fn renumber_solid(solid: &mut Solid, ids: &mut IdAllocator, side_ids: &mut HashMap<u32, u32>) {
+    solid.id = ids.next();
+    for side in &mut solid.sides {
+        let id = ids.next();
+        // A duplicated old id keeps pointing at its first side
+        side_ids.entry(side.id).or_insert(id);
+        side.id = id;
+    }
+}
+
+fn renumber_group(group: &mut Group, ids: &mut IdAllocator, group_ids: &mut HashMap<u32, u32>) {
+    let id = ids.next();
+    group_ids.entry(group.id).or_insert(id);
+    group.id = id;
+    for child in &mut group.groups {
+        renumber_group(child, ids, group_ids);
+    }
+}
+
+fn remap_group_id(editor: &mut Option<EditorData>, group_ids: &HashMap<u32, u32>) {
+    if let Some(groupid) = editor.as_mut().and_then(|editor| editor.groupid.as_mut())
+        && let Some(&id) = group_ids.get(groupid)
+    {
+        *groupid = id;
+    }
+}
+
+fn remap_nested_group_ids(group: &mut Group, group_ids: &HashMap<u32, u32>) {
+    remap_group_id(&mut group.editor, group_ids);
+    for child in &mut group.groups {
+        remap_nested_group_ids(child, group_ids);
+    }
+}
+
+fn remap_entity_references(
+    entity: &mut Entity,
+    side_ids: &HashMap<u32, u32>,
+    group_ids: &HashMap<u32, u32>,
+) {
+    remap_group_id(&mut entity.editor, group_ids);
+    for solid in &mut entity.solids {
+        remap_group_id(&mut solid.editor, group_ids);
+    }
+
+    for key in SIDE_REFERENCE_KEYS {
+        let Some(value) = entity.properties.get_mut(key) else {
+            continue;
+        };
+        // Ids that don't name a side are kept as they are, the linter reports them
+        let remapped: Vec<String> = value
+            .split_whitespace()
+            .map(|id| match id.parse().ok().and_then(|id| side_ids.get(&id)) {
+                Some(new_id) => new_id.to_string(),
+                None => id.to_string(),
+            })
+            .collect();
+        *value = Cow::Owned(remapped.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::{Entity, PropertiesExt, World},
+        util::lex,
+        Parser, VMFValue, VMF,
+    };
+
+    #[test]
+    fn test_allocator_seeded_from_document() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "100"
+            "classname" "info_target"
+        }
+        "#))
+        .unwrap();
+        let doc = ParsedVmf::new(vec![VMFValue::Entity(Box::new(entity))]);
+
+        let mut ids = IdAllocator::from_document(&doc);
+        assert_eq!(ids.next(), 101);
+        assert_eq!(ids.next_id(), Some(102));
+        assert_eq!(ids.peek(), Some(103));
+    }
+
+    #[test]
+    fn test_allocator_runs_out_of_ids() {
+        let doc = ParsedVmf::new(vec![VMFValue::World(Box::new(World {
+            id: u32::MAX,
+            ..Default::default()
+        }))]);
+        let mut ids = IdAllocator::from_document(&doc);
+        assert_eq!(ids.peek(), None);
+        assert_eq!(ids.next_id(), None);
+
+        let mut ids = IdAllocator::new(u32::MAX - 1);
+        assert_eq!(ids.next_id(), Some(u32::MAX - 1));
+        assert_eq!(ids.next_id(), Some(u32::MAX));
+        assert_eq!(ids.next_id(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ran out of ids")]
+    fn test_allocator_next_panics_when_exhausted() {
+        let mut ids = IdAllocator::new(u32::MAX);
+        assert_eq!(ids.next(), u32::MAX);
+        ids.next();
+    }
+
+    #[test]
+    fn test_allocator_accounts_for_side_ids() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "5"
+            "classname" "func_detail"
+            solid
+            {
+                "id" "6"
+                side
+                {
+                    "id" "40"
+                }
+            }
+        }
+        "#))
+        .unwrap();
+        let doc = ParsedVmf::new(vec![VMFValue::Entity(Box::new(entity))]);
+
+        assert_eq!(IdAllocator::from_document(&doc).next_id(), Some(41));
+    }
+
+    #[test]
+    fn test_default_allocator_starts_at_one() {
+        assert_eq!(IdAllocator::default().next_id(), Some(1));
+    }
+
+    #[test]
+    fn test_renumber_ids_sequential() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let mut doc = vmf.parse_document().unwrap();
+        doc.renumber_ids();
+
+        fn group_ids(group: &Group, out: &mut Vec<u32>) {
+            out.push(group.id);
+            group.groups.iter().for_each(|child| group_ids(child, out));
+        }
+
+        let mut ids = Vec::new();
+        for block in &doc.blocks {
+            match block {
+                VMFValue::World(world) => {
+                    ids.push(world.id);
+                    world.group.iter().for_each(|group| group_ids(group, &mut ids));
+                }
+                VMFValue::Entity(entity) => ids.push(entity.id),
+                VMFValue::Cameras(cameras) => ids.extend(cameras.cameras.iter().map(|c| c.id)),
+                _ => {}
+            }
+        }
+        for solid in doc.solids() {
+            ids.push(solid.id);
+            ids.extend(solid.sides.iter().map(|side| side.id));
+        }
+        ids.sort_unstable();
+
+        assert!(ids.len() > 1);
+        assert_eq!(ids, (1..=ids.len() as u32).collect::<Vec<_>>());
+        assert_eq!(doc.max_id(), ids.len() as u32);
+    }
+
+    #[test]
+    fn test_renumber_ids_updates_side_references() {
+        let src = r#"
+        entity
+        {
+            "id" "50"
+            "classname" "func_detail"
+            solid
+            {
+                "id" "60"
+                side
+                {
+                    "id" "70"
+                }
+                side
+                {
+                    "id" "71"
+                }
+            }
+        }
+        entity
+        {
+            "id" "80"
+            "classname" "info_overlay"
+            "sides" "71 70 999"
+        }
+        "#;
+        let vmf: VMF = src.parse().unwrap();
+        let mut doc = vmf.parse_document().unwrap();
+        doc.renumber_ids();
+
+        let overlay = doc.entities().nth(1).unwrap();
+        assert_eq!(overlay.id, 5);
+        assert_eq!(overlay.properties.get_str("sides"), Some("4 3 999"));
+        assert!(doc.block_source(1).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use super::ParsedVmf;
+
+/// Maps entity classnames to small integer symbols.
+///
+/// Every distinct classname gets its own symbol, counting up from 0 in the order the names
+/// were first seen. Comparing symbols is cheaper than comparing the strings, which adds up
+/// when grouping or filtering thousands of entities.
+///
+/// usage:
+/// ```ignore
+/// let classnames = doc.classnames().unwrap();
+/// let light = classnames.symbol("light");
+/// let lights = doc
+///     .entities()
+///     .filter(|entity| classnames.symbol(&entity.classname) == light)
+///     .count();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClassnameInterner {
+    symbols: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl ClassnameInterner {
+    /// Returns the symbol of `classname`, adding it if it wasn't seen before.
+    pub fn intern(&mut self, classname: &str) -> u32 {
+        if let Some(&symbol) = self.symbols.get(classname) {
+            return symbol;
+        }
+        let symbol = self.names.len() as u32;
+        self.symbols.insert(classname.to_string(), symbol);
+        self.names.push(classname.to_string());
+        symbol
+    }
+
+    /// Returns the symbol of `classname`, or `None` if it was never interned.
+    pub fn symbol(&self, classname: &str) -> Option<u32> {
+        self.symbols.get(classname).copied()
+    }
+
+    /// Returns the classname behind `symbol`.
+    pub fn resolve(&self, symbol: u32) -> Option<&str> {
+        self.names.get(symbol as usize).map(String::as_str)
+    }
+
+    /// Number of distinct classnames.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if no classname was interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl ParsedVmf<'_> {
+    /// Interns the classname of every entity, including hidden ones and worldspawn.
+    ///
+    /// [`VMF::parse_document_with`](crate::VMF::parse_document_with) does this when
+    /// [`ParseOptions::intern_classnames`](crate::ParseOptions::intern_classnames) is set.
+    /// Call it again after adding entities with new classnames.
+    pub fn intern_classnames(&mut self) {
+        let mut interner = self.classnames.take().unwrap_or_default();
+        for world in self.worlds() {
+            interner.intern(&world.classname);
+        }
+        for entity in self.entities() {
+            interner.intern(&entity.classname);
+        }
+        self.classnames = Some(interner);
+    }
+
+    /// Returns the classname symbols, if they were built with
+    /// [`intern_classnames`](Self::intern_classnames).
+    pub fn classnames(&self) -> Option<&ClassnameInterner> {
+        self.classnames.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseOptions, VMF};
+
+    #[test]
+    fn test_shared_classname_symbol() {
+        let vmf: VMF = r#"
+        entity { "id" "1" "classname" "light" }
+        entity { "id" "2" "classname" "info_player_start" }
+        entity { "id" "3" "classname" "light" }
+        "#
+        .parse()
+        .unwrap();
+
+        assert!(vmf.parse_document().unwrap().classnames().is_none());
+
+        let options = ParseOptions {
+            intern_classnames: true,
+            ..Default::default()
+        };
+        let doc = vmf.parse_document_with(&options).unwrap();
+        let classnames = doc.classnames().unwrap();
+
+        let symbols: Vec<_> = doc
+            .entities()
+            .map(|entity| classnames.symbol(&entity.classname).unwrap())
+            .collect();
+        assert_eq!(symbols[0], symbols[2]);
+        assert_ne!(symbols[0], symbols[1]);
+        assert_eq!(classnames.len(), 2);
+        assert_eq!(classnames.resolve(symbols[1]), Some("info_player_start"));
+        assert_eq!(classnames.symbol("func_detail"), None);
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ParsedVmf;
+use crate::types::{Entity, Point3D};
+
+/// Largest coordinate (in either direction) that the Source engine can represent.
+pub const MAX_COORD: f32 = 16384.0;
+
+/// Kind of problem reported by [`ParsedVmf::lint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintCategory {
+    /// Two objects share an id.
+    DuplicateId,
+    /// An output targets a name that no entity in the map uses.
+    DanglingOutput,
+    /// A point or brush lies outside the `±MAX_COORD` playable space.
+    OutOfBounds,
+    /// A displacement's data doesn't match its power.
+    InvalidDisplacement,
+    /// An entity references a side id (e.g. the `sides` key of overlays) that doesn't exist.
+    BrokenSideReference,
+    /// An entity has no `classname`, usually a sign of a truncated or hand-edited file.
+    MissingClassname,
+    /// The document has more than one `world` block, usually left behind by merging files.
+    DuplicateWorld,
+    /// A side's plane points are collinear or coincident, so the plane has no normal.
+    DegeneratePlane,
+}
+
+/// A single problem found by [`ParsedVmf::lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub category: LintCategory,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(category: LintCategory, message: String) -> Self {
+        Self { category, message }
+    }
+}
+
+/// Entity keys that hold space separated side ids.
+pub(super) const SIDE_REFERENCE_KEYS: [&str; 2] = ["sides", "sides2"];
+
+impl ParsedVmf<'_> {
+    /// Runs every structural check over the document and returns all problems found.
+    ///
+    /// An empty result means the map passed all checks.
+    pub fn lint(&self) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        self.lint_duplicate_ids(&mut issues);
+        self.lint_dangling_outputs(&mut issues);
+        self.lint_out_of_bounds(&mut issues);
+        self.lint_displacements(&mut issues);
+        self.lint_side_references(&mut issues);
+        self.lint_missing_classnames(&mut issues);
+        self.lint_duplicate_worlds(&mut issues);
+        self.lint_degenerate_planes(&mut issues);
+        issues
+    }
+
+    /// Worlds, entities, solids and sides share one id space, the same one
+    /// [`max_id`](ParsedVmf::max_id) and [`renumber_ids`](ParsedVmf::renumber_ids) use.
+    fn lint_duplicate_ids(&self, issues: &mut Vec<LintIssue>) {
+        let mut objects: HashMap<u32, &str> = HashMap::new();
+
+        let mut check_object = |id: u32, kind: &'static str, issues: &mut Vec<LintIssue>| {
+            if let Some(previous) = objects.insert(id, kind) {
+                issues.push(LintIssue::new(
+                    LintCategory::DuplicateId,
+                    format!("{} id {} is already used by a {}", kind, id, previous),
+                ));
+            }
+        };
+
+        for world in self.worlds() {
+            check_object(world.id, "world", issues);
+        }
+        for entity in self.entities() {
+            check_object(entity.id, "entity", issues);
+        }
+        for solid in self.solids() {
+            check_object(solid.id, "solid", issues);
+            for side in &solid.sides {
+                check_object(side.id, "side", issues);
+            }
+        }
+    }
+
+    fn lint_dangling_outputs(&self, issues: &mut Vec<LintIssue>) {
+        let names: HashSet<&str> = self
+            .entities()
+            .flat_map(|entity| {
+                entity
+                    .targetname
+                    .as_deref()
+                    .into_iter()
+                    .chain([&*entity.classname])
+            })
+            .chain(
+                self.worlds()
+                    .filter_map(|world| world.targetname.as_deref()),
+            )
+            .collect();
+
+        let resolves = |target: &str| {
+            if target.starts_with('!') {
+                // !self, !activator, !player, ... are resolved at runtime
+                return true;
+            }
+            match target.strip_suffix('*') {
+                Some(prefix) => names.iter().any(|name| name.starts_with(prefix)),
+                None => names.contains(target),
+            }
+        };
+
+        for entity in self.entities() {
+            for output in &entity.outputs {
+                if !resolves(&output.target) {
+                    issues.push(LintIssue::new(
+                        LintCategory::DanglingOutput,
+                        format!(
+                            "entity {} output {} targets unknown entity '{}'",
+                            entity.id, output.output_name, output.target
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn lint_out_of_bounds(&self, issues: &mut Vec<LintIssue>) {
+        let outside =
+            |p: &Point3D| p.x.abs() > MAX_COORD || p.y.abs() > MAX_COORD || p.z.abs() > MAX_COORD;
+
+        for entity in self.entities() {
+            if let Some(origin) = entity.origin.as_ref().filter(|p| outside(p)) {
+                issues.push(LintIssue::new(
+                    LintCategory::OutOfBounds,
+                    format!("entity {} origin {:?} is out of bounds", entity.id, origin),
+                ));
+            }
+        }
+
+        for solid in self.solids() {
+            let out = solid.sides.iter().any(|side| {
+                let (p1, p2, p3) = &side.plane;
+                outside(p1) || outside(p2) || outside(p3)
+            });
+            if out {
+                issues.push(LintIssue::new(
+                    LintCategory::OutOfBounds,
+                    format!("solid {} extends out of bounds", solid.id),
+                ));
+            }
+        }
+    }
+
+    fn lint_displacements(&self, issues: &mut Vec<LintIssue>) {
+        for solid in self.solids() {
+            for side in &solid.sides {
+                let Some(dispinfo) = &side.dispinfo else {
+                    continue;
+                };
+                if let Err(err) = dispinfo.validate() {
+                    issues.push(LintIssue::new(
+                        LintCategory::InvalidDisplacement,
+                        format!("side {} of solid {}: {}", side.id, solid.id, err),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn lint_degenerate_planes(&self, issues: &mut Vec<LintIssue>) {
+        for (solid, side) in self.sides().filter(|(_, side)| side.is_degenerate()) {
+            issues.push(LintIssue::new(
+                LintCategory::DegeneratePlane,
+                format!(
+                    "side {} of solid {} has a degenerate plane {:?}",
+                    side.id, solid.id, side.plane
+                ),
+            ));
+        }
+    }
+
+    fn lint_side_references(&self, issues: &mut Vec<LintIssue>) {
+        let sides: HashSet<u32> = self
+            .solids()
+            .flat_map(|solid| solid.sides.iter().map(|side| side.id))
+            .collect();
+
+        for entity in self.entities() {
+            for (key, id) in side_references(entity) {
+                let exists = id.parse::<u32>().is_ok_and(|id| sides.contains(&id));
+                if !exists {
+                    issues.push(LintIssue::new(
+                        LintCategory::BrokenSideReference,
+                        format!(
+                            "entity {} key '{}' references missing side '{}'",
+                            entity.id, key, id
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn lint_missing_classnames(&self, issues: &mut Vec<LintIssue>) {
+        let worlds = self
+            .worlds()
+            .map(|world| ("world", world.id, &world.classname));
+        let entities = self
+            .entities()
+            .map(|entity| ("entity", entity.id, &entity.classname));
+
+        for (kind, id, classname) in worlds.chain(entities) {
+            if classname.trim().is_empty() {
+                issues.push(LintIssue::new(
+                    LintCategory::MissingClassname,
+                    format!("{} {} has no classname", kind, id),
+                ));
+            }
+        }
+    }
+
+    fn lint_duplicate_worlds(&self, issues: &mut Vec<LintIssue>) {
+        let count = self.worlds().count();
+        if count > 1 {
+            issues.push(LintIssue::new(
+                LintCategory::DuplicateWorld,
+                format!("document has {} world blocks, expected one", count),
+            ));
+        }
+    }
+}
+
+/// Iterates over every (key, side id) pair referenced by `entity`.
+fn side_references<'a>(entity: &'a Entity) -> impl Iterator<Item = (&'a str, &'a str)> {
+    SIDE_REFERENCE_KEYS.iter().flat_map(move |&key| {
+        entity
+            .properties
+            .get(key)
+            .into_iter()
+            .flat_map(|value| value.split_whitespace())
+            .map(move |id| (key, id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::World, util::lex, Parser, VMFValue};
+
+    fn entity(src: &str) -> VMFValue<'_> {
+        VMFValue::Entity(Box::new(Entity::parse(lex(src)).unwrap()))
+    }
+
+    fn categories(doc: &ParsedVmf) -> Vec<LintCategory> {
+        doc.lint().into_iter().map(|issue| issue.category).collect()
+    }
+
+    #[test]
+    fn test_lint_duplicate_id() {
+        let doc = ParsedVmf::new(vec![
+            entity(r#"entity { "id" "5" "classname" "info_target" }"#),
+            entity(r#"entity { "id" "5" "classname" "info_target" }"#),
+        ]);
+
+        assert_eq!(categories(&doc), vec![LintCategory::DuplicateId]);
+
+        // Sides draw from the same ids as entities and solids
+        let doc = ParsedVmf::new(vec![
+            entity(r#"entity { "id" "5" "classname" "info_target" }"#),
+            entity(
+                r#"entity {
+                    "id" "6"
+                    "classname" "func_brush"
+                    solid { "id" "7" side { "id" "5" } }
+                }"#,
+            ),
+        ]);
+        let issues: Vec<_> = doc
+            .lint()
+            .into_iter()
+            .filter(|issue| issue.category == LintCategory::DuplicateId)
+            .collect();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "side id 5 is already used by a entity");
+    }
+
+    #[test]
+    fn test_lint_clean_document() {
+        let doc = ParsedVmf::new(vec![
+            entity(
+                r#"entity {
+                    "id" "1"
+                    "classname" "func_button"
+                    connections { "OnPressed" "door,Open,,0,-1" }
+                }"#,
+            ),
+            entity(r#"entity { "id" "2" "classname" "func_door" "targetname" "door" }"#),
+        ]);
+
+        assert!(doc.lint().is_empty(), "{:?}", doc.lint());
+    }
+
+    #[test]
+    fn test_lint_dangling_output() {
+        let doc = ParsedVmf::new(vec![entity(
+            r#"entity {
+                "id" "1"
+                "classname" "func_button"
+                connections
+                {
+                    "OnPressed" "missing,Open,,0,-1"
+                    "OnPressed" "!activator,Kill,,0,-1"
+                }
+            }"#,
+        )]);
+
+        assert_eq!(categories(&doc), vec![LintCategory::DanglingOutput]);
+    }
+
+    #[test]
+    fn test_lint_out_of_bounds() {
+        let doc = ParsedVmf::new(vec![entity(
+            r#"entity { "id" "1" "classname" "info_target" "origin" "0 20000 0" }"#,
+        )]);
+
+        assert_eq!(categories(&doc), vec![LintCategory::OutOfBounds]);
+    }
+
+    #[test]
+    fn test_lint_invalid_displacement() {
+        let doc = ParsedVmf::new(vec![entity(
+            r#"entity {
+                "id" "1"
+                "classname" "func_detail"
+                solid
+                {
+                    "id" "2"
+                    side
+                    {
+                        "id" "3"
+                        "plane" "(0 0 0) (0 128 0) (128 128 0)"
+                        dispinfo
+                        {
+                            "power" "2"
+                            alphas { "row0" "0 0 0" }
+                        }
+                    }
+                }
+            }"#,
+        )]);
+
+        assert_eq!(categories(&doc), vec![LintCategory::InvalidDisplacement]);
+    }
+
+    #[test]
+    fn test_lint_missing_classname() {
+        let doc = ParsedVmf::new(vec![
+            entity(r#"entity { "id" "1" "origin" "0 0 0" }"#),
+            entity(r#"entity { "id" "2" "classname" "" }"#),
+            entity(r#"entity { "id" "3" "classname" "info_target" }"#),
+        ]);
+
+        let issues = doc.lint();
+        assert_eq!(
+            categories(&doc),
+            vec![
+                LintCategory::MissingClassname,
+                LintCategory::MissingClassname
+            ]
+        );
+        assert_eq!(issues[0].message, "entity 1 has no classname");
+    }
+
+    #[test]
+    fn test_lint_broken_side_reference() {
+        let doc = ParsedVmf::new(vec![entity(
+            r#"entity {
+                "id" "1"
+                "classname" "info_overlay"
+                "sides" "42"
+            }"#,
+        )]);
+
+        assert_eq!(categories(&doc), vec![LintCategory::BrokenSideReference]);
+    }
+
+    #[test]
+    fn test_lint_duplicate_world() {
+        let world = |src| VMFValue::World(Box::new(World::parse(lex(src)).unwrap()));
+        let doc = ParsedVmf::new(vec![
+            world(r#"world { "id" "1" "classname" "worldspawn" "skyname" "sky_day01_01" }"#),
+            world(r#"world { "id" "2" "classname" "worldspawn" }"#),
+        ]);
+
+        let issues = doc.lint();
+        assert_eq!(categories(&doc), vec![LintCategory::DuplicateWorld]);
+        assert_eq!(issues[0].message, "document has 2 world blocks, expected one");
+        assert_eq!(doc.world().map(|world| world.id), Some(1));
+        assert_eq!(ParsedVmf::default().world(), None);
+    }
+
+    #[test]
+    fn test_lint_degenerate_plane() {
+        let doc = ParsedVmf::new(vec![entity(
+            r#"entity {
+                "id" "1"
+                "classname" "func_detail"
+                solid
+                {
+                    "id" "2"
+                    side { "id" "3" "plane" "(0 0 0) (0 128 0) (128 128 0)" }
+                    side { "id" "4" "plane" "(0 0 64) (64 0 64) (128 0 64)" }
+                }
+            }"#,
+        )]);
+
+        let issues = doc.lint();
+        assert_eq!(categories(&doc), vec![LintCategory::DegeneratePlane]);
+        assert!(issues[0].message.starts_with("side 4 of solid 2"));
+    }
+}
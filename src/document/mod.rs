@@ -0,0 +1,301 @@
+mod assets;
+mod bounds;
+mod detail;
+mod hash;
+mod hierarchy;
+mod ids;
+mod interner;
+mod lint;
+mod order;
+mod source;
+mod spawns;
+mod triggers;
+mod visgroups;
+
+pub use assets::*;
+pub use ids::*;
+pub use interner::*;
+pub use lint::*;
+pub use triggers::*;
+pub(crate) use ids::block_max_id;
+pub(crate) use order::canonical_rank;
+pub(crate) use source::block_sources;
+use source::BlockSource;
+
+use crate::types::{DispInfo, Entity, Point3D, Side, Solid, VersionInfo, World};
+use crate::VMFValue;
+
+/// A displacement together with the side and solid that own it.
+#[derive(Debug, Clone, Copy)]
+pub struct DispRef<'a> {
+    pub solid_id: u32,
+    pub side_id: u32,
+    pub dispinfo: &'a DispInfo,
+    /// Plane of the owning side, the displacement is built on this face.
+    pub plane: (Point3D, Point3D, Point3D),
+}
+
+/// A parsed VMF document.
+///
+/// Wraps the top-level blocks returned by [`VMF::parse`](crate::VMF::parse) and provides
+/// queries that need to look across the whole map.
+///
+/// usage:
+/// ```ignore
+/// let vmf = VMF::open("test.vmf")?;
+/// let doc = vmf.parse_document()?;
+/// println!("highest id: {}", doc.max_id());
+/// ```
+#[derive(Debug, Default)]
+pub struct ParsedVmf<'src> {
+    pub blocks: Vec<VMFValue<'src>>,
+    /// Original source and leading comments of each block. Empty if the document wasn't
+    /// parsed from source, see [`block_source`](Self::block_source).
+    sources: Vec<BlockSource<'src>>,
+    classnames: Option<ClassnameInterner>,
+}
+
+impl<'src> ParsedVmf<'src> {
+    /// Creates a new [`ParsedVmf`] from already parsed top-level blocks.
+    pub fn new(blocks: Vec<VMFValue<'src>>) -> Self {
+        Self {
+            blocks,
+            sources: Vec::new(),
+            classnames: None,
+        }
+    }
+
+    /// Returns the `versioninfo` header block.
+    ///
+    /// Generated or stripped maps can leave the header out, in which case this is `None`.
+    pub fn version_info(&self) -> Option<&VersionInfo> {
+        self.blocks.iter().find_map(|block| match block {
+            VMFValue::VersionInfo(version_info) => Some(version_info),
+            _ => None,
+        })
+    }
+
+    /// Iterates over all `world` blocks in the document.
+    pub fn worlds(&self) -> impl Iterator<Item = &World<'src>> {
+        self.blocks.iter().filter_map(|block| match block {
+            VMFValue::World(world) => Some(world.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Returns the first `world` block in the document.
+    ///
+    /// A map should have exactly one, [`lint`](Self::lint) reports documents with more.
+    pub fn world(&self) -> Option<&World<'src>> {
+        self.worlds().next()
+    }
+
+    /// Iterates over every entity in the document: the top-level `entity` blocks and the
+    /// hidden entities of each world, in document order.
+    pub fn entities(&self) -> impl Iterator<Item = &Entity<'src>> {
+        self.blocks.iter().flat_map(|block| {
+            let (hidden, entity) = match block {
+                VMFValue::World(world) => (world.hidden_entities.as_slice(), None),
+                VMFValue::Entity(entity) => (&[][..], Some(entity.as_ref())),
+                _ => (&[][..], None),
+            };
+            hidden.iter().chain(entity)
+        })
+    }
+
+    /// Iterates over every solid in the document, world brushes first, then brush entities.
+    pub fn solids(&self) -> impl Iterator<Item = &Solid<'src>> {
+        self.worlds()
+            .flat_map(|world| world.solids.iter())
+            .chain(self.entities().flat_map(|entity| entity.solids.iter()))
+    }
+
+    /// Iterates over every side in the document together with the solid that owns it.
+    pub fn sides(&self) -> impl Iterator<Item = (&Solid<'src>, &Side<'src>)> {
+        self.solids()
+            .flat_map(|solid| solid.sides.iter().map(move |side| (solid, side)))
+    }
+
+    /// Iterates over every displacement in the document, in the same order as [`sides`](Self::sides).
+    pub fn displacements(&self) -> impl Iterator<Item = DispRef<'_>> {
+        self.sides().filter_map(|(solid, side)| {
+            side.dispinfo.as_ref().map(|dispinfo| DispRef {
+                solid_id: solid.id,
+                side_id: side.id,
+                dispinfo,
+                plane: side.plane,
+            })
+        })
+    }
+
+    /// Returns the highest object id used in the document.
+    ///
+    /// This covers worlds, entities, solids, sides, groups and cameras. Visgroup ids live
+    /// in their own namespace and are not included.
+    pub fn max_id(&self) -> u32 {
+        self.blocks.iter().map(block_max_id).fold(0, u32::max)
+    }
+}
+
+impl<'src> From<Vec<VMFValue<'src>>> for ParsedVmf<'src> {
+    fn from(blocks: Vec<VMFValue<'src>>) -> Self {
+        Self::new(blocks)
+    }
+}
+
+impl<'a, 'src> IntoIterator for &'a ParsedVmf<'src> {
+    type Item = &'a VMFValue<'src>;
+    type IntoIter = std::slice::Iter<'a, VMFValue<'src>>;
+
+    /// Iterates over the top-level blocks, so a document can be used as `for block in &doc`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, ParseOptions, Parser, VMF};
+
+    #[test]
+    fn test_document_from_test_vmf() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().expect("Failed to parse VMF");
+
+        assert_eq!(doc.worlds().count(), 1);
+        assert!(doc.entities().count() > 0);
+        assert!(doc.solids().count() > 0);
+        assert!(doc.max_id() > 0);
+    }
+
+    #[test]
+    fn test_version_info() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+        assert!(doc.version_info().is_some());
+
+        let headerless: VMF = r#"
+        world { "id" "1" "classname" "worldspawn" }
+        entity { "id" "2" "classname" "info_player_start" "origin" "0 0 0" }
+        "#
+        .parse()
+        .unwrap();
+        let doc = headerless.parse_document().unwrap();
+        assert_eq!(doc.blocks.len(), 2);
+        assert!(doc.version_info().is_none());
+    }
+
+    #[test]
+    fn test_iterate_document() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let (mut worlds, mut entities, mut others) = (0, 0, 0);
+        for block in &doc {
+            match block {
+                VMFValue::World(_) => worlds += 1,
+                VMFValue::Entity(_) => entities += 1,
+                _ => others += 1,
+            }
+        }
+        assert_eq!(worlds, 1);
+        assert_eq!(entities, doc.entities().count());
+        assert_eq!(worlds + entities + others, doc.blocks.len());
+    }
+
+    #[test]
+    fn test_hidden_entities() {
+        let vmf: VMF = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            hidden
+            {
+                entity
+                {
+                    "id" "5"
+                    "classname" "func_brush"
+                    "targetname" "door"
+                    solid { "id" "6" side { "id" "7" "material" "TOOLS/NODRAW" } }
+                }
+            }
+        }
+        entity { "id" "2" "classname" "prop_dynamic" "parentname" "door" }
+        "#
+        .parse()
+        .unwrap();
+        let options = ParseOptions {
+            normalize_materials: true,
+            ..Default::default()
+        };
+        let doc = vmf.parse_document_with(&options).unwrap();
+
+        let ids: Vec<_> = doc.entities().map(|entity| entity.id).collect();
+        assert_eq!(ids, [5, 2]);
+        assert_eq!(doc.solids().map(|solid| solid.id).collect::<Vec<_>>(), [6]);
+        assert_eq!(doc.sides().next().unwrap().1.material, "tools/nodraw");
+        let child = doc.entities().nth(1).unwrap();
+        assert_eq!(doc.parent_of(child).unwrap().id, 5);
+        assert_eq!(doc.max_id(), 7);
+    }
+
+    #[test]
+    fn test_displacements() {
+        let world = World::parse(lex(r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side
+                {
+                    "id" "3"
+                    "plane" "(0 0 0) (0 128 0) (128 128 0)"
+                    "material" "NATURE/BLENDGRASSGRAVEL001A"
+                    dispinfo { "power" "2" "startposition" "[0 0 0]" }
+                }
+                side { "id" "4" "material" "TOOLS/TOOLSNODRAW" }
+            }
+        }
+        "#))
+        .unwrap();
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "5"
+            "classname" "func_detail"
+            solid
+            {
+                "id" "6"
+                side { "id" "7" "material" "TOOLS/TOOLSNODRAW" dispinfo { "power" "3" } }
+            }
+        }
+        "#))
+        .unwrap();
+        let doc = ParsedVmf::new(vec![
+            VMFValue::World(Box::new(world)),
+            VMFValue::Entity(Box::new(entity)),
+        ]);
+
+        assert_eq!(doc.sides().count(), 3);
+
+        let disps: Vec<_> = doc.displacements().collect();
+        assert_eq!(disps.len(), 2);
+        assert_eq!((disps[0].solid_id, disps[0].side_id), (2, 3));
+        assert_eq!(disps[0].dispinfo.power, 2);
+        assert_eq!(disps[0].plane.1, Point3D::new(0.0, 128.0, 0.0));
+        assert_eq!((disps[1].solid_id, disps[1].side_id), (6, 7));
+        assert_eq!(disps[1].dispinfo.power, 3);
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let doc = ParsedVmf::default();
+        assert_eq!(doc.max_id(), 0);
+        assert_eq!(doc.solids().count(), 0);
+    }
+}
@@ -0,0 +1,92 @@
+use super::ParsedVmf;
+use crate::VMFValue;
+
+/// Position of a block in the conventional VMF layout written by Hammer:
+/// versioninfo, visgroups, viewsettings, world, entities, cameras, cordon.
+pub(crate) fn canonical_rank(block: &VMFValue) -> u8 {
+    match block {
+        VMFValue::VersionInfo(_) => 0,
+        VMFValue::VisGroups(_) => 1,
+        VMFValue::ViewSettings(_) => 2,
+        VMFValue::World(_) => 3,
+        VMFValue::Entity(_) => 4,
+        VMFValue::Cameras(_) => 5,
+        VMFValue::Cordon(_) => 6,
+    }
+}
+
+impl ParsedVmf<'_> {
+    /// Returns `true` if the top-level blocks follow the conventional Hammer order.
+    ///
+    /// Some importers reject files whose blocks are out of order, see
+    /// [`reorder_canonical`](Self::reorder_canonical).
+    pub fn blocks_in_canonical_order(&self) -> bool {
+        self.blocks
+            .windows(2)
+            .all(|pair| canonical_rank(&pair[0]) <= canonical_rank(&pair[1]))
+    }
+
+    /// Sorts the top-level blocks into the conventional Hammer order.
+    ///
+    /// The sort is stable, so entities keep their relative order.
+    pub fn reorder_canonical(&mut self) {
+        if self.sources.len() != self.blocks.len() {
+            self.blocks.sort_by_key(canonical_rank);
+            return;
+        }
+
+        // Keep every block paired with its source
+        let mut pairs: Vec<_> = self.blocks.drain(..).zip(self.sources.drain(..)).collect();
+        pairs.sort_by_key(|(block, _)| canonical_rank(block));
+        (self.blocks, self.sources) = pairs.into_iter().unzip();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::{Cameras, Entity, VersionInfo},
+        util::lex,
+        Parser,
+    };
+
+    #[test]
+    fn test_reorder_shuffled_document() {
+        let entity = |id| {
+            VMFValue::Entity(Box::new(Entity {
+                id,
+                ..Default::default()
+            }))
+        };
+        let cameras = Cameras::parse(lex(r#"cameras { "activecamera" "-1" }"#)).unwrap();
+        let version = VersionInfo::parse(lex(
+            r#"versioninfo { "editorversion" "400" "editorbuild" "6157" "mapversion" "1" "formatversion" "100" "prefab" "0" }"#,
+        ))
+        .unwrap();
+
+        let mut doc = ParsedVmf::new(vec![
+            entity(2),
+            VMFValue::Cameras(Box::new(cameras)),
+            VMFValue::World(Box::default()),
+            entity(3),
+            VMFValue::VersionInfo(version),
+        ]);
+        assert!(!doc.blocks_in_canonical_order());
+
+        doc.reorder_canonical();
+        assert!(doc.blocks_in_canonical_order());
+
+        let ranks: Vec<_> = doc.blocks.iter().map(canonical_rank).collect();
+        assert_eq!(ranks, vec![0, 3, 4, 4, 5]);
+        let ids: Vec<_> = doc.entities().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_test_vmf_is_canonical() {
+        let vmf: crate::VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().expect("Failed to parse VMF");
+        assert!(doc.blocks_in_canonical_order());
+    }
+}
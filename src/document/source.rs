@@ -0,0 +1,207 @@
+use super::ParsedVmf;
+use crate::VMFValue;
+
+/// What a [`ParsedVmf`] remembers about the source of one block.
+#[derive(Debug, Clone)]
+pub(crate) struct BlockSource<'src> {
+    /// Source text of the block, `None` once the block was edited.
+    text: Option<&'src str>,
+    /// `//` comment lines in front of the block.
+    comments: Vec<&'src str>,
+}
+
+impl<'src> ParsedVmf<'src> {
+    /// Creates a document that remembers the source text and leading comments of every block.
+    ///
+    /// `sources` must hold one slice per block, otherwise no sources are kept. `comments`
+    /// lines up with `sources`, missing entries mean no comments.
+    pub(crate) fn with_sources(
+        blocks: Vec<VMFValue<'src>>,
+        sources: Vec<&'src str>,
+        comments: Vec<Vec<&'src str>>,
+    ) -> Self {
+        let sources = if sources.len() == blocks.len() {
+            let comments = comments.into_iter().chain(std::iter::repeat_with(Vec::new));
+            sources
+                .into_iter()
+                .zip(comments)
+                .map(|(text, comments)| BlockSource {
+                    text: Some(text),
+                    comments,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            sources,
+            ..Self::new(blocks)
+        }
+    }
+
+    /// Returns the original source text of the block at `index`.
+    ///
+    /// Only documents from [`VMF::parse_document`](crate::VMF::parse_document) keep their
+    /// source. Returns `None` once the block was changed through [`edit`](Self::edit), or
+    /// for every block if `blocks` was resized directly.
+    pub fn block_source(&self, index: usize) -> Option<&'src str> {
+        if self.sources.len() != self.blocks.len() {
+            return None;
+        }
+        self.sources.get(index).and_then(|source| source.text)
+    }
+
+    /// Returns the `//` comment lines written in front of the block at `index`, each one
+    /// as in the source, including the `//`.
+    ///
+    /// Comments are only kept when parsing with
+    /// [`ParseOptions::keep_comments`](crate::ParseOptions::keep_comments). They survive
+    /// [`edit`](Self::edit) and are written out again in front of the block.
+    pub fn leading_comments(&self, index: usize) -> &[&'src str] {
+        if self.sources.len() != self.blocks.len() {
+            return &[];
+        }
+        self.sources
+            .get(index)
+            .map_or(&[], |source| source.comments.as_slice())
+    }
+
+    /// Returns the block at `index` for editing and forgets its original source.
+    ///
+    /// When writing with [`SerializeOptions::preserve_source`](crate::writer::SerializeOptions),
+    /// only edited blocks are rendered again, every other block is copied from the source
+    /// as is. Changes made through `blocks` directly are not tracked.
+    pub fn edit(&mut self, index: usize) -> Option<&mut VMFValue<'src>> {
+        self.mark_edited(index);
+        self.blocks.get_mut(index)
+    }
+
+    pub(crate) fn mark_edited(&mut self, index: usize) {
+        if let Some(source) = self.sources.get_mut(index) {
+            source.text = None;
+        }
+    }
+}
+
+/// Splits `src` into the source text of each known top-level block.
+///
+/// Every slice runs up to the start of the next block, so the trailing whitespace and any
+/// skipped unknown blocks stay with the block before them, and the slices put together
+/// give back `src`. A block starts at its leading comments, if `starts` includes them.
+pub(crate) fn block_sources<'src>(src: &'src str, starts: &[usize]) -> Vec<&'src str> {
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let start = if i == 0 { 0 } else { start };
+            let end = starts.get(i + 1).copied().unwrap_or(src.len());
+            &src[start..end]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        writer::{SerializeOptions, ToVmf},
+        ParseOptions, VMFValue, VMF,
+    };
+
+    fn preserving() -> SerializeOptions {
+        SerializeOptions {
+            preserve_source: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unedited_document_is_written_verbatim() {
+        let src = include_str!("../../test.vmf");
+        let vmf: VMF = src.parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        assert_eq!(doc.to_vmf_with(&preserving()), src);
+        assert_ne!(doc.to_vmf(), src);
+    }
+
+    #[test]
+    fn test_edit_only_rewrites_edited_block() {
+        let src = include_str!("../../test.vmf");
+        let vmf: VMF = src.parse().unwrap();
+        let mut doc = vmf.parse_document().unwrap();
+
+        let index = doc
+            .blocks
+            .iter()
+            .position(|block| {
+                matches!(block, VMFValue::Entity(e) if e.classname == "info_player_start")
+            })
+            .unwrap();
+        let original = doc.block_source(index).unwrap();
+        let start = src.find(original).unwrap();
+        let end = start + original.len();
+
+        if let Some(VMFValue::Entity(entity)) = doc.edit(index) {
+            entity.targetname = Some("spawn_a".into());
+        }
+        assert_eq!(doc.block_source(index), None);
+
+        let written = doc.to_vmf_with(&preserving());
+        assert!(written.starts_with(&src[..start]));
+        assert!(written.ends_with(&src[end..]));
+        let rewritten = &written[start..written.len() - (src.len() - end)];
+        assert!(rewritten.contains("\"targetname\" \"spawn_a\""));
+        assert!(rewritten.contains("\"classname\" \"info_player_start\""));
+    }
+
+    #[test]
+    fn test_leading_comments() {
+        let src = concat!(
+            "// Generated by mapgen\n",
+            "world\n{\n\t\"id\" \"1\"\n\t\"classname\" \"worldspawn\"\n}\n",
+            "// Main light\n// keep it bright\n",
+            "entity\n{\n\t\"id\" \"2\"\n\t// inside a block\n\t\"classname\" \"light\"\n}\n",
+        );
+        let vmf: VMF = src.parse().unwrap();
+
+        let doc = vmf.parse_document().unwrap();
+        assert!(doc.leading_comments(1).is_empty());
+
+        let options = ParseOptions {
+            keep_comments: true,
+            ..Default::default()
+        };
+        let mut doc = vmf.parse_document_with(&options).unwrap();
+        assert_eq!(doc.leading_comments(0), ["// Generated by mapgen"]);
+        assert_eq!(doc.leading_comments(1), ["// Main light", "// keep it bright"]);
+        assert_eq!(doc.to_vmf_with(&preserving()), src);
+
+        if let Some(VMFValue::Entity(entity)) = doc.edit(1) {
+            entity.targetname = Some("sun".into());
+        }
+        let written = doc.to_vmf_with(&preserving());
+        assert!(written.contains("// Main light\n// keep it bright\nentity\n{"));
+        assert!(written.contains("\"targetname\" \"sun\""));
+        assert_eq!(written.matches("// Main light").count(), 1);
+    }
+
+    #[test]
+    fn test_sources_follow_restructuring() {
+        let src = concat!(
+            "entity\n{\n\t\"id\" \"3\"\n\t\"classname\" \"func_detail\"\n}\n",
+            "world\n{\n\t\"id\" \"1\"\n\t\"classname\" \"worldspawn\"\n}\n",
+            "entity\n{\n\t\"id\" \"2\"\n\t\"classname\" \"light\"\n}\n",
+        );
+        let vmf: VMF = src.parse().unwrap();
+        let mut doc = vmf.parse_document().unwrap();
+
+        doc.reorder_canonical();
+        assert!(doc.block_source(0).unwrap().starts_with("world"));
+        assert!(doc.block_source(1).unwrap().contains("func_detail"));
+
+        doc.merge_detail_into_world();
+        assert_eq!(doc.blocks.len(), 2);
+        assert_eq!(doc.block_source(0), None);
+        assert!(doc.block_source(1).unwrap().contains("light"));
+    }
+}
@@ -0,0 +1,51 @@
+use super::ParsedVmf;
+use crate::types::Point3D;
+
+impl ParsedVmf<'_> {
+    /// Returns the classname and origin of every spawn point entity.
+    ///
+    /// Spawn points are entities whose classname matches `info_player_*` or `info_*_spawn`,
+    /// e.g. `info_player_start`, `info_player_terrorist` or `info_survivor_spawn`.
+    /// Entities without an origin are skipped.
+    pub fn spawn_points(&self) -> Vec<(&str, Point3D)> {
+        self.entities()
+            .filter(|entity| is_spawn_classname(&entity.classname))
+            .filter_map(|entity| Some((entity.classname.as_ref(), entity.origin?)))
+            .collect()
+    }
+}
+
+fn is_spawn_classname(classname: &str) -> bool {
+    classname.starts_with("info_player_")
+        || classname
+            .strip_prefix("info_")
+            .and_then(|rest| rest.strip_suffix("_spawn"))
+            .is_some_and(|middle| !middle.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VMF;
+
+    #[test]
+    fn test_spawn_points_of_test_vmf() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        assert_eq!(
+            doc.spawn_points(),
+            vec![("info_player_start", Point3D::new(0.0, -256.0, 0.0))]
+        );
+    }
+
+    #[test]
+    fn test_spawn_classnames() {
+        assert!(is_spawn_classname("info_player_terrorist"));
+        assert!(is_spawn_classname("info_player_counterterrorist"));
+        assert!(is_spawn_classname("info_survivor_spawn"));
+        assert!(!is_spawn_classname("info_spawn"));
+        assert!(!is_spawn_classname("info_target"));
+        assert!(!is_spawn_classname("light_spawn"));
+    }
+}
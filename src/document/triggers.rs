@@ -0,0 +1,86 @@
+use super::{bounds::brush_bounds, ParsedVmf};
+use crate::types::{Entity, EntityOutput, Point3D};
+
+/// A `trigger_*` brush entity together with its volume and outputs.
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerVolume<'a> {
+    pub entity: &'a Entity<'a>,
+    /// Bounding box of the trigger's solids.
+    pub mins: Point3D,
+    pub maxs: Point3D,
+    pub outputs: &'a [EntityOutput<'a>],
+}
+
+impl ParsedVmf<'_> {
+    /// Returns every `trigger_*` entity with the bounding box of its brushes and its outputs.
+    ///
+    /// Triggers without any solid that forms a face have no volume and are skipped.
+    pub fn triggers(&self) -> Vec<TriggerVolume<'_>> {
+        self.entities()
+            .filter(|entity| entity.classname.starts_with("trigger_"))
+            .filter_map(|entity| {
+                let (mins, maxs) = brush_bounds(entity)?;
+                Some(TriggerVolume {
+                    entity,
+                    mins,
+                    maxs,
+                    outputs: &entity.outputs,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VMF;
+
+    #[test]
+    fn test_triggers() {
+        let vmf: VMF = r#"
+        entity
+        {
+            "id" "2"
+            "classname" "trigger_once"
+            "targetname" "start_trigger"
+            connections
+            {
+                "OnTrigger" "door,Open,,0,1"
+                "OnTrigger" "alarm,PlaySound,,0.5,1"
+            }
+            solid
+            {
+                "id" "3"
+                side { "id" "4" "plane" "(256 0 64) (320 0 64) (320 -16 64)" }
+                side { "id" "5" "plane" "(256 -16 0) (320 -16 0) (320 0 0)" }
+                side { "id" "6" "plane" "(256 0 64) (256 -16 64) (256 -16 0)" }
+                side { "id" "7" "plane" "(320 0 0) (320 -16 0) (320 -16 64)" }
+                side { "id" "8" "plane" "(320 0 64) (256 0 64) (256 0 0)" }
+                side { "id" "9" "plane" "(320 -16 0) (256 -16 0) (256 -16 64)" }
+            }
+        }
+        entity
+        {
+            "id" "10"
+            "classname" "trigger_multiple"
+        }
+        entity
+        {
+            "id" "11"
+            "classname" "logic_relay"
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let triggers = doc.triggers();
+        assert_eq!(triggers.len(), 1);
+        let trigger = &triggers[0];
+        assert_eq!(trigger.entity.id, 2);
+        assert_eq!(trigger.mins, Point3D::new(256.0, -16.0, 0.0));
+        assert_eq!(trigger.maxs, Point3D::new(320.0, 0.0, 64.0));
+        assert_eq!(trigger.outputs.len(), 2);
+    }
+}
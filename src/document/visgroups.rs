@@ -0,0 +1,155 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::ParsedVmf;
+use crate::types::{EditorData, World};
+use crate::VMFValue;
+
+fn visgroup_ids<'a>(editor: &'a Option<EditorData>) -> &'a [u32] {
+    editor.as_ref().map_or(&[], |editor| &editor.visgroupids)
+}
+
+impl<'src> ParsedVmf<'src> {
+    /// Splits the document into one sub-document per visgroup.
+    ///
+    /// Each sub-document keeps the `versioninfo` and `visgroups` blocks, every world with
+    /// only the solids and hidden entities in the visgroup, and the top-level entities in
+    /// the visgroup. Membership comes from the `visgroupid` keys of each object's editor
+    /// block, objects in several visgroups are copied into each of them. Brush entities
+    /// are split as a whole, by the visgroups of the entity.
+    ///
+    /// Only visgroups with at least one member get a sub-document.
+    pub fn split_by_visgroup(&self) -> HashMap<u32, ParsedVmf<'src>> {
+        let mut ids = BTreeSet::new();
+        for world in self.worlds() {
+            for solid in &world.solids {
+                ids.extend(visgroup_ids(&solid.editor));
+            }
+        }
+        for entity in self.entities() {
+            ids.extend(visgroup_ids(&entity.editor));
+        }
+
+        // Worlds without their solids and hidden entities, cloned once per visgroup
+        let shells: Vec<_> = self
+            .worlds()
+            .map(|world| World {
+                solids: Vec::new(),
+                hidden_entities: Vec::new(),
+                ..world.clone()
+            })
+            .collect();
+
+        ids.into_iter()
+            .map(|id| {
+                let mut shells = shells.iter();
+                let blocks = self
+                    .blocks
+                    .iter()
+                    .filter_map(|block| match block {
+                        VMFValue::VersionInfo(version_info) => {
+                            Some(VMFValue::VersionInfo(version_info.clone()))
+                        }
+                        VMFValue::VisGroups(visgroups) => {
+                            Some(VMFValue::VisGroups(visgroups.clone()))
+                        }
+                        VMFValue::World(world) => {
+                            let mut part = shells.next()?.clone();
+                            part.solids = world
+                                .solids
+                                .iter()
+                                .filter(|solid| visgroup_ids(&solid.editor).contains(&id))
+                                .cloned()
+                                .collect();
+                            part.hidden_entities = world
+                                .hidden_entities
+                                .iter()
+                                .filter(|entity| visgroup_ids(&entity.editor).contains(&id))
+                                .cloned()
+                                .collect();
+                            Some(VMFValue::World(Box::new(part)))
+                        }
+                        VMFValue::Entity(entity)
+                            if visgroup_ids(&entity.editor).contains(&id) =>
+                        {
+                            Some(VMFValue::Entity(entity.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                (id, ParsedVmf::new(blocks))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{VMFValue, VMF};
+
+    #[test]
+    fn test_split_by_visgroup() {
+        let vmf: VMF = r#"
+        visgroups
+        {
+            visgroup { "name" "detail" "visgroupid" "1" "color" "255 0 0" }
+            visgroup { "name" "lights" "visgroupid" "2" "color" "0 255 0" }
+        }
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side { "id" "3" "material" "BRICK/BRICKWALL001A" }
+                editor { "visgroupid" "1" }
+            }
+            solid
+            {
+                "id" "4"
+                side { "id" "5" "material" "BRICK/BRICKWALL001A" }
+            }
+        }
+        entity
+        {
+            "id" "6"
+            "classname" "light"
+            editor { "visgroupid" "2" }
+        }
+        entity
+        {
+            "id" "7"
+            "classname" "light_spot"
+            editor
+            {
+                "visgroupid" "1"
+                "visgroupid" "2"
+            }
+        }
+        entity
+        {
+            "id" "8"
+            "classname" "info_player_start"
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let parts = doc.split_by_visgroup();
+        assert_eq!(parts.len(), 2);
+
+        let detail = &parts[&1];
+        assert!(detail.blocks.iter().any(|b| matches!(b, VMFValue::VisGroups(_))));
+        let solids: Vec<_> = detail.solids().map(|solid| solid.id).collect();
+        assert_eq!(solids, [2]);
+        let entities: Vec<_> = detail.entities().map(|entity| entity.id).collect();
+        assert_eq!(entities, [7]);
+
+        let lights = &parts[&2];
+        assert_eq!(lights.worlds().count(), 1);
+        assert_eq!(lights.solids().count(), 0);
+        let entities: Vec<_> = lights.entities().map(|entity| entity.id).collect();
+        assert_eq!(entities, [6, 7]);
+    }
+}
@@ -1,31 +1,104 @@
-/// Error type for VMF parsing operations
-#[derive(Debug)]
+use chumsky::error::{Rich, RichReason};
+use thiserror::Error;
+
+use crate::parser::lexer::Token;
+
+/// Error type for VMF parsing operations.
+///
+/// This is the only error type returned by the public API. Lower level errors
+/// (I/O, UTF-8 and the token parser errors) are converted into it with `From`.
+#[derive(Error, Debug)]
 pub enum VMFError {
-    IoError(std::io::Error),
-    Utf8Error(std::str::Utf8Error),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("UTF-8 error: {0}")]
+    Utf8Error(#[from] std::str::Utf8Error),
+
+    #[error("Parse error: {0}")]
     ParseError(String),
+
+    /// A parse error at a position in the source. Lines and columns start at 1, columns
+    /// count characters.
+    #[error("{line}:{column}: {message}")]
+    ParseErrorAt {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Invalid value '{value}' for property '{key}': {reason}")]
+    InvalidProperty {
+        key: String,
+        value: String,
+        reason: String,
+    },
 }
 
-impl From<std::io::Error> for VMFError {
-    fn from(err: std::io::Error) -> Self {
-        VMFError::IoError(err)
+impl<'src> From<RichReason<'src, Token<'src>>> for VMFError {
+    fn from(reason: RichReason<'src, Token<'src>>) -> Self {
+        VMFError::ParseError(format!("{:?}", reason))
     }
 }
 
-impl From<std::str::Utf8Error> for VMFError {
-    fn from(err: std::str::Utf8Error) -> Self {
-        VMFError::Utf8Error(err)
+impl<'src> From<Rich<'src, Token<'src>>> for VMFError {
+    fn from(err: Rich<'src, Token<'src>>) -> Self {
+        VMFError::from(err.into_reason())
     }
 }
 
-impl std::fmt::Display for VMFError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            VMFError::IoError(err) => write!(f, "IO error: {}", err),
-            VMFError::Utf8Error(err) => write!(f, "UTF-8 error: {}", err),
-            VMFError::ParseError(msg) => write!(f, "Parse error: {}", msg),
-        }
+impl<'src> From<Vec<RichReason<'src, Token<'src>>>> for VMFError {
+    fn from(reasons: Vec<RichReason<'src, Token<'src>>>) -> Self {
+        let msg = reasons
+            .iter()
+            .map(|r| format!("{:?}", r))
+            .collect::<Vec<_>>()
+            .join("; ");
+        VMFError::ParseError(msg)
     }
 }
 
-impl std::error::Error for VMFError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::VersionInfo, util::lex, Parser};
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_io_error_conversion() {
+        let result = crate::VMF::open("does_not_exist.vmf");
+        assert!(matches!(result, Err(VMFError::IoError(_))));
+    }
+
+    #[test]
+    fn test_utf8_error_conversion() {
+        let bytes = vec![0xff, 0xfe];
+        let err: VMFError = std::str::from_utf8(&bytes).unwrap_err().into();
+        assert!(matches!(err, VMFError::Utf8Error(_)));
+        assert!(err.to_string().starts_with("UTF-8 error"));
+    }
+
+    #[test]
+    fn test_parser_reasons_conversion() {
+        let reasons = VersionInfo::parse(lex("versioninfo { }")).unwrap_err();
+        let err: VMFError = reasons.into();
+        assert!(matches!(err, VMFError::ParseError(_)));
+        assert!(err.to_string().starts_with("Parse error"));
+    }
+
+    #[test]
+    fn test_parse_error_at_display() {
+        let err = VMFError::ParseErrorAt {
+            line: 12,
+            column: 5,
+            message: "unexpected '}'".to_string(),
+        };
+        assert_eq!(err.to_string(), "12:5: unexpected '}'");
+    }
+
+    #[test]
+    fn test_error_is_std_error() {
+        fn assert_error<E: std::error::Error>() {}
+        assert_error::<VMFError>();
+    }
+}
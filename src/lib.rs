@@ -32,17 +32,36 @@
 //! - **Complete VMF support**: Handles versioninfo, visgroups, worlds, entities, solids, displacements, cameras, and more
 //! - **Strong typing**: All VMF constructs are represented as Rust types with proper error handling
 //!
+//! ## Cargo features
+//!
+//! - `fs` (default): loading files from disk with `VMF::open`, which reads the whole file
+//!   into memory. Without it the parser only needs a string in memory
+//!   (`"...".parse::<VMF>()`), which suits WASM and embedded targets.
+//! - `testing`: round trip assertions in `mnk_vmf::testing` for downstream test suites.
+//!
 //! ## Modules
 //!
 //! - [`vmf`]: Main entry point for loading and parsing VMF files
+//! - [`document`]: Whole-map queries over parsed data ([`ParsedVmf`])
 //! - [`types`]: All VMF data types (World, Entity, Solid, etc.)
+//! - [`writer`]: Writing parsed data back out as VMF ([`writer::ToVmf`])
 //! - [`parser`]: Low-level parsing utilities and traits
 
+pub mod document;
 mod error;
 mod parser;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
 pub mod vmf;
+pub mod writer;
 
+pub use document::*;
+pub use error::VMFError;
+pub use parser::lexer::{Token, TokenIter};
 pub use parser::util;
-pub use parser::Parser;
-pub use vmf::*;
+pub use parser::{Parser, VmfBlock};
+pub use vmf::{
+    parse_clipboard, parse_vmf, parse_vmf_reader, BlockIndex, EntityFilter, IndexedBlock,
+    ParseDiagnostic, ParseOptions, ParseTiming, ParseWarning, VMFValue, VMF,
+};
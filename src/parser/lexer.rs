@@ -1,5 +1,8 @@
+use std::ops::Range;
+
 use logos::Logos;
 
+/// A single lexical token of VMF source.
 #[derive(Logos, Debug, Copy, Clone, PartialEq)]
 pub enum Token<'a> {
     Error,
@@ -22,10 +25,14 @@ pub enum Token<'a> {
     RBracket,
 
     #[regex(r"[ \t\f\r\n]+", logos::skip)]
+    #[regex(r"//[^\r\n]*", logos::skip)]
     Whitespace,
 }
 
-pub(crate) struct TokenIter<'a> {
+/// Iterator over the tokens of a VMF source.
+///
+/// Whitespace is skipped. Input the lexer can't recognise yields `Err(())`.
+pub struct TokenIter<'a> {
     inner: logos::Lexer<'a, Token<'a>>,
 }
 
@@ -35,6 +42,25 @@ impl<'a> TokenIter<'a> {
             inner: Token::lexer(input),
         }
     }
+
+    /// Turns the iterator into one that also yields the byte range of every token
+    /// in the source, for tools like syntax highlighters.
+    ///
+    /// Unrecognised input is yielded as [`Token::Error`] with the range it covers.
+    ///
+    /// usage:
+    /// ```ignore
+    /// for (token, span) in TokenIter::new(src).spanned() {
+    ///     highlight(&src[span], token);
+    /// }
+    /// ```
+    pub fn spanned(self) -> impl Iterator<Item = (Token<'a>, Range<usize>)> {
+        let mut inner = self.inner;
+        std::iter::from_fn(move || {
+            let token = inner.next()?.unwrap_or(Token::Error);
+            Some((token, inner.span()))
+        })
+    }
 }
 
 impl<'a> Iterator for TokenIter<'a> {
@@ -44,3 +70,57 @@ impl<'a> Iterator for TokenIter<'a> {
         self.inner.next()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spanned_tokens() {
+        let src = "world\n{\n\t\"id\" \"1\"\n}";
+        let tokens: Vec<_> = TokenIter::new(src).spanned().collect();
+
+        assert_eq!(
+            tokens.iter().map(|(token, _)| *token).collect::<Vec<_>>(),
+            vec![
+                Token::Ident("world"),
+                Token::LBracket,
+                Token::QuotedText("id"),
+                Token::QuotedText("1"),
+                Token::RBracket,
+            ]
+        );
+        assert_eq!(&src[tokens[2].1.clone()], "\"id\"");
+
+        // Spans are ordered, don't overlap, and only whitespace lies between them
+        let mut end = 0;
+        for (_, span) in &tokens {
+            assert!(span.start >= end && span.start < span.end);
+            assert!(src[end..span.start].trim().is_empty());
+            end = span.end;
+        }
+        assert_eq!(end, src.len());
+    }
+
+    #[test]
+    fn test_spanned_error_token() {
+        let tokens: Vec<_> = TokenIter::new("a @ b").spanned().collect();
+        assert_eq!(tokens[1], (Token::Error, 2..3));
+    }
+
+    #[test]
+    fn test_comments_are_skipped() {
+        let src = "// header { \"\nentity // trailing\n{ \"url\" \"http://x\" }";
+        let tokens: Vec<_> = TokenIter::new(src).map(Result::unwrap).collect();
+        assert_eq!(
+            tokens,
+            [
+                Token::Ident("entity"),
+                Token::LBracket,
+                Token::QuotedText("url"),
+                Token::QuotedText("http://x"),
+                Token::RBracket,
+            ]
+        );
+    }
+}
@@ -1,5 +1,5 @@
-pub(crate) mod error;
 pub(crate) mod lexer;
+pub(crate) mod scan;
 pub mod util;
 
 use chumsky::{
@@ -71,6 +71,13 @@ pub trait Parser<'src>: InternalParser<'src> {
     }
 }
 
+/// A VMF block type, identified by the keyword that opens it (`world`, `entity`, `side`, ..).
+///
+/// usage: `assert_eq!(World::BLOCK_NAME, "world");`
+pub trait VmfBlock {
+    const BLOCK_NAME: &'static str;
+}
+
 /// Parse a number from `T`.
 pub(crate) fn number<'a, T, I>() -> impl ChumskyParser<'a, I, T, TokenError<'a>>
 where
@@ -84,6 +91,22 @@ where
     })
 }
 
+/// Parses a float value the way VMF writers emit them.
+///
+/// Integer-written values like `"5"` are accepted as `5.0`, surrounding whitespace is ignored.
+pub(crate) fn parse_float(value: &str) -> Result<f32, std::num::ParseFloatError> {
+    value.trim().parse::<f32>()
+}
+
+/// Parse a float with [`parse_float`].
+pub(crate) fn float<'a, I>() -> impl ChumskyParser<'a, I, f32, TokenError<'a>>
+where
+    I: TokenSource<'a>,
+{
+    select! { lexer::Token::QuotedText(s) => s }
+        .try_map(|s: &str, span| parse_float(s).map_err(|_| Rich::custom(span, "invalid float")))
+}
+
 /// Parse a boolean literal: `true` or `false`.
 pub(crate) fn boolean<'a, I>() -> impl ChumskyParser<'a, I, bool, TokenError<'a>>
 where
@@ -142,6 +165,17 @@ where
     quoted_string(key).ignore_then(number::<T, I>())
 }
 
+/// Takes a `key` string value, and tries to get a float value.
+/// The format of this is: "key" "0.5", integers like "key" "5" are accepted too.
+pub(crate) fn key_value_float<'src, I>(
+    key: &'src str,
+) -> impl ChumskyParser<'src, I, f32, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    quoted_string(key).ignore_then(float())
+}
+
 /// Takes a `key` string value, and tries to get a boolean value.
 /// The format of this is: "key" "false"
 pub(crate) fn key_value_boolean<'src, I>(
@@ -153,6 +187,51 @@ where
     quoted_string(key).ignore_then(boolean())
 }
 
+/// Parses a quoted string equal to `input`, ignoring ASCII case.
+/// Some exporters lowercase every key, so this lets known keys like `bSnapToGrid` still match.
+pub(crate) fn quoted_string_ci<'src, I>(
+    input: &'src str,
+) -> impl ChumskyParser<'src, I, &'src str, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    select! {
+        lexer::Token::QuotedText(s) if s.eq_ignore_ascii_case(input) => s
+    }
+}
+
+/// Like [`key_value`], but the key is matched case-insensitively.
+pub(crate) fn key_value_ci<'src, I>(
+    key: &'src str,
+) -> impl ChumskyParser<'src, I, &'src str, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    quoted_string_ci(key).ignore_then(any_quoted_string())
+}
+
+/// Like [`key_value_numeric`], but the key is matched case-insensitively.
+pub(crate) fn key_value_numeric_ci<'src, T, I>(
+    key: &'src str,
+) -> impl ChumskyParser<'src, I, T, TokenError<'src>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Debug,
+    I: TokenSource<'src>,
+{
+    quoted_string_ci(key).ignore_then(number::<T, I>())
+}
+
+/// Like [`key_value_boolean`], but the key is matched case-insensitively.
+pub(crate) fn key_value_boolean_ci<'src, I>(
+    key: &'src str,
+) -> impl ChumskyParser<'src, I, bool, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    quoted_string_ci(key).ignore_then(boolean())
+}
+
 /// Starts a parser on VMF blocks. VMF block usually starts with a key, then new line and open
 /// bracket.
 ///
@@ -179,24 +258,42 @@ where
 }
 
 /// Parses and skips any unknown/unrecognized block.
-/// It matches any identifier followed by a block, and recursively skips nested blocks.
-pub(crate) fn skip_unknown_block<'src, I>() -> impl ChumskyParser<'src, I, (), TokenError<'src>>
+/// It matches an identifier that isn't one of `known` followed by a block, and recursively
+/// skips nested blocks whatever their name. Blocks named in `known` are left to their own
+/// parsers, so a malformed known block is an error instead of being skipped.
+/// [`LBracket`](lexer::Token::LBracket) and [`RBracket`](lexer::Token::RBracket) are the `{`
+/// and `}` tokens, braces inside quoted values are part of the string token and don't count.
+pub(crate) fn skip_unknown_block<'src, I>(
+    known: &'static [&'static str],
+) -> impl ChumskyParser<'src, I, (), TokenError<'src>>
 where
     I: TokenSource<'src>,
 {
-    recursive(|skip_block| {
-        any()
-            .filter(|tok| matches!(tok, lexer::Token::Ident(_)))
-            .ignore_then(just(lexer::Token::LBracket))
+    fn block_body<'src, I>(
+        nested: impl ChumskyParser<'src, I, (), TokenError<'src>> + Clone,
+    ) -> impl ChumskyParser<'src, I, (), TokenError<'src>> + Clone
+    where
+        I: TokenSource<'src>,
+    {
+        just(lexer::Token::LBracket)
             .ignore_then(
-                none_of([lexer::Token::LBracket, lexer::Token::RBracket])
-                    .ignored()
-                    .or(skip_block)
+                // Nested blocks first, otherwise their name is taken as a plain token and
+                // their opening brace can't be matched
+                nested
+                    .or(none_of([lexer::Token::LBracket, lexer::Token::RBracket]).ignored())
                     .repeated(),
             )
             .then_ignore(just(lexer::Token::RBracket))
-            .ignored()
-    })
+    }
+
+    let nested = recursive(|nested| {
+        any()
+            .filter(|tok| matches!(tok, lexer::Token::Ident(_)))
+            .ignore_then(block_body(nested))
+    });
+    any()
+        .filter(move |tok| matches!(tok, lexer::Token::Ident(name) if !known.contains(name)))
+        .ignore_then(block_body(nested))
 }
 
 #[cfg(test)]
@@ -235,6 +332,95 @@ mod tests {
         assert_eq!(result.unwrap(), 42);
     }
 
+    #[test]
+    fn test_key_value_float_accepts_integers() {
+        for value in ["5", "5.0", "5.", " 5 ", "5e0"] {
+            let src = format!(r#""wait" "{}""#, value);
+            let result = key_value_float::<_>("wait").parse(lex(&src)).into_result();
+            assert_eq!(result, Ok(5.0), "{:?} did not parse as 5.0", value);
+        }
+
+        let result = key_value_float::<_>("wait").parse(lex(r#""wait" "five""#));
+        assert!(result.has_errors());
+    }
+
+    #[test]
+    fn test_key_value_ci() {
+        let result = key_value_ci::<_>("logicalpos").parse(lex(r#""LogicalPos" "[0 0]""#));
+        assert_eq!(result.into_result(), Ok("[0 0]"));
+
+        let result = key_value_ci::<_>("logicalpos").parse(lex(r#""logicalpo" "[0 0]""#));
+        assert!(result.has_errors());
+    }
+
+    #[test]
+    fn test_any_quoted_string_keeps_special_values() {
+        let values = [
+            "$basetexture",
+            "%compilenodraw",
+            "$envmap env_cubemap $envmaptint [.5 .5 .5]",
+            "materials/dev/dev_measuregeneric01b.vmt",
+            "models\\props_c17\\oildrum001.mdl",
+            "!activator,AddOutput,targetname $name%1,0,-1",
+            "{ } ( ) [ ] // ; : = # @ & *",
+            "",
+        ];
+
+        for value in values {
+            let src = format!("\"{}\"", value);
+            let result = any_quoted_string().parse(lex(&src)).into_result();
+            assert_eq!(result, Ok(value), "{:?} was not kept verbatim", value);
+        }
+    }
+
+    #[test]
+    fn test_block_names() {
+        use crate::types::*;
+
+        fn name_of<T: VmfBlock>() -> &'static str {
+            T::BLOCK_NAME
+        }
+
+        // Evaluated at compile time, a type losing its impl fails the build
+        const NAMES: [&str; 14] = [
+            VersionInfo::BLOCK_NAME,
+            VisGroups::BLOCK_NAME,
+            VisGroup::BLOCK_NAME,
+            ViewSettings::BLOCK_NAME,
+            World::BLOCK_NAME,
+            Entity::BLOCK_NAME,
+            Solid::BLOCK_NAME,
+            Side::BLOCK_NAME,
+            DispInfo::BLOCK_NAME,
+            EditorData::BLOCK_NAME,
+            Group::BLOCK_NAME,
+            Cameras::BLOCK_NAME,
+            Camera::BLOCK_NAME,
+            Cordon::BLOCK_NAME,
+        ];
+
+        assert_eq!(
+            NAMES,
+            [
+                "versioninfo",
+                "visgroups",
+                "visgroup",
+                "viewsettings",
+                "world",
+                "entity",
+                "solid",
+                "side",
+                "dispinfo",
+                "editor",
+                "group",
+                "cameras",
+                "camera",
+                "cordon",
+            ]
+        );
+        assert_eq!(name_of::<World>(), "world");
+    }
+
     #[test]
     fn test_open_close_block() {
         let stream = lex("blk {");
@@ -251,4 +437,38 @@ mod tests {
         }
         assert!(!r2.has_errors());
     }
+
+    #[test]
+    fn test_skip_unknown_block() {
+        let stream = lex(r#"custom
+        {
+            "key" "}"
+            nested
+            {
+                "brace" "{"
+                deeper { }
+            }
+            "after" "value"
+        }"#);
+        let result = skip_unknown_block(&[]).then_ignore(end()).parse(stream);
+        assert!(!result.has_errors(), "{:?}", result.errors().collect::<Vec<_>>());
+
+        // The parser stops at the block's own closing brace
+        let stream = lex(r#"custom { nested { "a" "}" } } }"#);
+        assert!(skip_unknown_block(&[]).then_ignore(end()).parse(stream).has_errors());
+        let stream = lex(r#"custom { nested { "a" "{" }"#);
+        assert!(skip_unknown_block(&[]).parse(stream).has_errors());
+
+        // Known blocks are left to their own parsers, but only at the outermost level
+        let stream = lex(r#"world { }"#);
+        assert!(skip_unknown_block(&["world"]).parse(stream).has_errors());
+        let stream = lex(r#"custom { world { } }"#);
+        assert!(!skip_unknown_block(&["world"]).parse(stream).has_errors());
+
+        let blocks = crate::parse_vmf(
+            r#"custom { nested { "a" "}" } } entity { "id" "1" "classname" "light" }"#,
+        )
+        .unwrap();
+        assert_eq!(blocks.len(), 1);
+    }
 }
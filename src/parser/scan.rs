@@ -0,0 +1,105 @@
+use std::ops::Range;
+
+use super::lexer::{Token, TokenIter};
+
+/// Byte range of a top-level block, from its name to the matching closing brace.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlockSpan<'src> {
+    pub name: &'src str,
+    pub span: Range<usize>,
+}
+
+/// Something the scanner could not turn into a complete block.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ScanError<'src> {
+    /// Tokens outside of any block.
+    Stray(Range<usize>),
+    /// A block whose closing brace is missing. The span runs to the end of the source.
+    Unterminated(BlockSpan<'src>),
+}
+
+/// Splits `src` into top-level blocks by matching braces, without parsing their contents.
+///
+/// Braces inside quoted strings are part of the string token, so they don't affect nesting.
+pub(crate) fn scan_blocks(src: &str) -> Vec<Result<BlockSpan<'_>, ScanError<'_>>> {
+    let mut items = Vec::new();
+    let mut tokens = TokenIter::new(src).spanned().peekable();
+    let mut stray: Option<Range<usize>> = None;
+
+    while let Some((token, span)) = tokens.next() {
+        let name = match (token, tokens.peek()) {
+            (Token::Ident(name), Some((Token::LBracket, _))) => name,
+            _ => {
+                // Merge neighbouring stray tokens into one range
+                stray = Some(match stray {
+                    Some(range) => range.start..span.end,
+                    None => span,
+                });
+                continue;
+            }
+        };
+
+        if let Some(range) = stray.take() {
+            items.push(Err(ScanError::Stray(range)));
+        }
+
+        let start = span.start;
+        let mut depth = 0usize;
+        let mut end = None;
+        for (token, span) in tokens.by_ref() {
+            match token {
+                Token::LBracket => depth += 1,
+                Token::RBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(span.end);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        items.push(match end {
+            Some(end) => Ok(BlockSpan {
+                name,
+                span: start..end,
+            }),
+            None => Err(ScanError::Unterminated(BlockSpan {
+                name,
+                span: start..src.len(),
+            })),
+        });
+    }
+
+    if let Some(range) = stray {
+        items.push(Err(ScanError::Stray(range)));
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_blocks() {
+        let src =
+            "world { \"id\" \"1\" solid { } }\nstray \"}\" }\nentity { \"a\" \"{\" }\nhidden {";
+        let items = scan_blocks(src);
+
+        assert_eq!(items.len(), 4);
+        let world = items[0].as_ref().unwrap();
+        assert_eq!(world.name, "world");
+        assert_eq!(&src[world.span.clone()], "world { \"id\" \"1\" solid { } }");
+        assert_eq!(items[1], Err(ScanError::Stray(29..40)));
+        assert_eq!(&src[29..40], "stray \"}\" }");
+        let entity = items[2].as_ref().unwrap();
+        assert_eq!(&src[entity.span.clone()], "entity { \"a\" \"{\" }");
+        assert!(matches!(
+            &items[3],
+            Err(ScanError::Unterminated(BlockSpan { name: "hidden", .. }))
+        ));
+    }
+}
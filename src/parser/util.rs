@@ -4,6 +4,8 @@ use chumsky::input::Stream;
 use logos::Logos as _;
 
 use super::lexer;
+use crate::vmf::error_at;
+use crate::VMFError;
 
 /// Macro to define individual property parsers and combine them with .or().
 /// When this Macro is used, it is necrssary to have chumsky's .or() and .map() in the scope.
@@ -16,10 +18,16 @@ use super::lexer;
 ///         // var_name = parser_call_expr => enum_variant_constructor_or_mapper_fn
 ///         p_some_bool = some_parser_that_outputs_bool("some_key") => YourPropertyEnumType::SomeBool,
 ///         p_some_num  = some_parser_that_outputs_u32("num_key")  => YourPropertyEnumType::SomeNum,
+///         // Keys that may appear several times are marked with #[repeated]. Consecutive
+///         // occurrences are collected, so the mapper gets a Vec<T> instead of a T.
+///         #[repeated]
+///         p_some_list = some_parser_that_outputs_u32("list_key") => YourPropertyEnumType::SomeList,
 ///         // ...
 ///     }
 /// }
 /// ```
+/// The block's fold should `extend` its collection with repeated values instead of
+/// assigning them, since non-consecutive occurrences arrive as separate items.
 #[macro_export]
 macro_rules! impl_block_properties_parser {
     (@build_or_chain $first_parser_var:ident) => {
@@ -29,15 +37,29 @@ macro_rules! impl_block_properties_parser {
         $first_parser_var.or(impl_block_properties_parser!(@build_or_chain $($rest_parser_vars),+))
     };
 
+    (@property $var_name:ident [] $parser_call_expr:expr => $value_mapper_fn:expr) => {
+        let $var_name = $parser_call_expr.map($value_mapper_fn);
+    };
+    (@property $var_name:ident [repeated] $parser_call_expr:expr => $value_mapper_fn:expr) => {
+        let $var_name = $parser_call_expr
+            .repeated()
+            .at_least(1)
+            .collect::<Vec<_>>()
+            .map($value_mapper_fn);
+    };
+
     (
         $any_property_let_name:ident: $PropEnumType:ty = {
             $(
+                $(#[$form:ident])?
                 $var_name:ident = $parser_call_expr:expr => $value_mapper_fn:expr
             ),+ $(,)? // Allow trailing comma
         }
     ) => {
         $(
-            let $var_name = $parser_call_expr.map($value_mapper_fn);
+            impl_block_properties_parser!(
+                @property $var_name [$($form)?] $parser_call_expr => $value_mapper_fn
+            );
         )+
         let $any_property_let_name =
             impl_block_properties_parser!(@build_or_chain $($var_name),+).boxed();
@@ -48,25 +70,22 @@ macro_rules! impl_block_properties_parser {
 pub fn lex(input: &str) -> Stream<IntoIter<lexer::Token<'_>>> {
     Stream::from_iter(
         lexer::Token::lexer(input)
-            .map(|tok| tok.expect("expected a valid token."))
+            .map(|tok| tok.unwrap_or(lexer::Token::Error))
             .collect::<Vec<lexer::Token<'_>>>(),
     )
 }
 
 /// Produces a vector of tokens (for reuse or benchmarking).
-pub fn tokenize(input: &str) -> Vec<lexer::Token<'_>> {
+///
+/// Returns a [`VMFError::ParseErrorAt`] pointing at the first piece of `input` that can't
+/// be tokenized.
+pub fn tokenize(input: &str) -> Result<Vec<lexer::Token<'_>>, VMFError> {
     lexer::Token::lexer(input)
-        .enumerate()
-        .map(|(idx, tok)| {
-            tok.unwrap_or_else(|_| {
-                let lexer = lexer::Token::lexer(input);
-                let span = lexer.span();
-                let context = &input
-                    [span.start.saturating_sub(20)..span.end.saturating_add(20).min(input.len())];
-                panic!(
-                    "Failed to tokenize at position {} (token #{})\nContext: {:?}",
-                    span.start, idx, context
-                );
+        .spanned()
+        .map(|(tok, span)| {
+            tok.map_err(|_| {
+                let message = format!("Failed to tokenize {:?}", &input[span.clone()]);
+                error_at(input, span.start, message)
             })
         })
         .collect()
@@ -87,28 +106,34 @@ mod tests {
     {
         "id" "1"
     }"#;
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         println!("Tokens: {:#?}", tokens);
     }
 
     #[test]
     fn test_tokenize_with_special_chars() {
         let input = r#""detail/detailsprites""#;
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         println!("Tokens: {:#?}", tokens);
     }
 
     #[test]
     fn test_tokenize_with_brackets() {
         let input = r#""[1 0 0 0]""#;
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         println!("Tokens: {:#?}", tokens);
     }
 
     #[test]
     fn test_tokenize_negative_number() {
         let input = r#""-1""#;
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         println!("Tokens: {:#?}", tokens);
     }
+
+    #[test]
+    fn test_tokenize_error_position() {
+        let err = tokenize("world\n{\n  \"id\" @\n}").unwrap_err();
+        assert!(matches!(err, VMFError::ParseErrorAt { line: 3, column: 8, .. }), "{}", err);
+    }
 }
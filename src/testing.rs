@@ -0,0 +1,144 @@
+//! Helpers for checking that maps survive a parse -> write -> parse round trip.
+//!
+//! Only available with the `testing` feature, so downstream crates can use them in their
+//! own test suites:
+//! ```toml
+//! [dev-dependencies]
+//! mnk-vmf = { version = "0.1", features = ["testing"] }
+//! ```
+
+use crate::{document::ParsedVmf, parse_vmf, writer::ToVmf};
+
+/// Parses `src`, writes it back out, parses the output again and asserts that both parses
+/// are equal.
+///
+/// Returns the written VMF.
+///
+/// # Panics
+/// Panics if `src` or the written output fails to parse, or if the two parses differ.
+pub fn assert_roundtrip(src: &str) -> String {
+    let original = ParsedVmf::new(parse_vmf(src).expect("round trip: input failed to parse"));
+    let written = original.to_vmf();
+    let reparsed =
+        ParsedVmf::new(parse_vmf(&written).expect("round trip: written output failed to parse"));
+
+    assert_eq!(
+        original.blocks, reparsed.blocks,
+        "round trip: written output parses to a different document"
+    );
+    written
+}
+
+/// Like [`assert_roundtrip`], but also asserts that the written output is byte-identical
+/// to `src`.
+///
+/// Only canonically formatted input can pass: tab indentation and Hammer's order for the
+/// keys with a dedicated field. Custom keys are written in source order after them.
+pub fn strict_roundtrip(src: &str) {
+    let written = assert_roundtrip(src);
+    assert_eq!(
+        written, src,
+        "strict round trip: written output differs from the input"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_test_vmf() {
+        let written = assert_roundtrip(include_str!("../test.vmf"));
+        assert!(written.starts_with("versioninfo\n{\n\t\"editorversion\" \"400\"\n"));
+    }
+
+    #[test]
+    fn test_strict_roundtrip() {
+        strict_roundtrip(
+            r#"versioninfo
+{
+	"editorversion" "400"
+	"editorbuild" "6157"
+	"mapversion" "16"
+	"formatversion" "100"
+	"prefab" "0"
+}
+visgroups
+{
+	visgroup
+	{
+		"name" "Trees"
+		"visgroupid" "5"
+		"color" "65 45 0"
+	}
+}
+viewsettings
+{
+	"bSnapToGrid" "1"
+	"bShowGrid" "1"
+	"bShowLogicalGrid" "0"
+	"nGridSpacing" "64"
+	"bShow3DGrid" "0"
+}
+world
+{
+	"id" "1"
+	"mapversion" "16"
+	"classname" "worldspawn"
+	"skyname" "sky_day01_01"
+	solid
+	{
+		"id" "9"
+		side
+		{
+			"id" "1"
+			"plane" "(-320 -320 0) (-320 320 0) (320 320 0)"
+			"material" "DEV/DEV_MEASUREGENERIC01B"
+			"uaxis" "[1 0 0 0] 0.25"
+			"vaxis" "[0 -1 0 0] 0.25"
+			"rotation" "0"
+			"lightmapscale" "16"
+			"smoothing_groups" "0"
+		}
+		editor
+		{
+			"color" "0 111 152"
+			"visgroupshown" "1"
+			"visgroupautoshown" "1"
+		}
+	}
+}
+entity
+{
+	"id" "7"
+	"classname" "info_player_start"
+	"angles" "-0 90 0"
+	"origin" "0 -256 0"
+	editor
+	{
+		"color" "0 255 0"
+		"visgroupshown" "1"
+		"visgroupautoshown" "1"
+		"logicalpos" "[0 10000]"
+	}
+}
+cameras
+{
+	"activecamera" "-1"
+}
+cordon
+{
+	"mins" "(-1024 -1024 -1024)"
+	"maxs" "(1024 1024 1024)"
+	"active" "0"
+}
+"#,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "strict round trip")]
+    fn test_strict_roundtrip_rejects_reformatted_input() {
+        strict_roundtrip("cameras\n{\n    \"activecamera\" \"-1\"\n}\n");
+    }
+}
@@ -3,15 +3,15 @@ use chumsky::{IterParser, Parser as ChumskyParser};
 use crate::{
     impl_block_properties_parser,
     parser::{
-        close_block, key_value, key_value_boolean, key_value_numeric, open_block, InternalParser,
-        TokenError, TokenSource,
+        close_block, key_value, key_value_boolean, key_value_float, key_value_numeric, open_block,
+        InternalParser, TokenError, TokenSource, VmfBlock,
     },
     types::point::{key_value_point3d, Point3D},
     Parser,
 };
 
 /// Represents a collection of cameras in the VMF file
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Cameras<'a> {
     pub activecamera: i32,
     pub cameras: Vec<Camera<'a>>,
@@ -27,7 +27,7 @@ impl<'a> Cameras<'a> {
 }
 
 /// Represents a camera entity in the VMF file
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Camera<'a> {
     pub id: u32,
     pub classname: &'a str,
@@ -75,6 +75,10 @@ enum CameraProperty<'a> {
 /// Public parser trait implementation that allows [`Cameras`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Cameras<'src> {}
 
+impl VmfBlock for Cameras<'_> {
+    const BLOCK_NAME: &'static str = "cameras";
+}
+
 /// A [`InternalParser`] implementation for [`Cameras`].
 ///
 /// usage: `let cameras = Cameras::parser().parse(input);`.
@@ -101,7 +105,7 @@ impl<'src> InternalParser<'src> for Cameras<'src> {
             }
         }
 
-        open_block("cameras")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(
                 property_list
                     .repeated()
@@ -131,6 +135,10 @@ impl<'src> InternalParser<'src> for Cameras<'src> {
 /// Public parser trait implementation that allows [`Camera`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Camera<'src> {}
 
+impl VmfBlock for Camera<'_> {
+    const BLOCK_NAME: &'static str = "camera";
+}
+
 /// A [`InternalParser`] implementation for [`Camera`].
 ///
 /// usage: `let camera = Camera::parser().parse(input);`.
@@ -161,18 +169,18 @@ impl<'src> InternalParser<'src> for Camera<'src> {
                 p_angles                   = key_value_point3d("angles")                  => CameraProperty::Angles,
                 p_targetname               = key_value("targetname")                      => CameraProperty::Targetname,
                 p_spawnflags               = key_value_numeric("spawnflags")              => CameraProperty::SpawnFlags,
-                p_wait                     = key_value_numeric("wait")                    => CameraProperty::Wait,
-                p_acceleration             = key_value_numeric("acceleration")            => CameraProperty::Acceleration,
-                p_deceleration             = key_value_numeric("deceleration")            => CameraProperty::Deceleration,
-                p_speed                    = key_value_numeric("speed")                   => CameraProperty::Speed,
-                p_fov                      = key_value_numeric("fov")                     => CameraProperty::Fov,
-                p_fov_rate                 = key_value_numeric("fov_rate")                => CameraProperty::FovRate,
+                p_wait                     = key_value_float("wait")                      => CameraProperty::Wait,
+                p_acceleration             = key_value_float("acceleration")              => CameraProperty::Acceleration,
+                p_deceleration             = key_value_float("deceleration")              => CameraProperty::Deceleration,
+                p_speed                    = key_value_float("speed")                     => CameraProperty::Speed,
+                p_fov                      = key_value_float("fov")                       => CameraProperty::Fov,
+                p_fov_rate                 = key_value_float("fov_rate")                  => CameraProperty::FovRate,
                 p_use_screen_aspect_ratio  = key_value_boolean("use_screen_aspect_ratio") => CameraProperty::UseScreenAspectRatio,
-                p_interp_time              = key_value_numeric("interp_time")             => CameraProperty::InterpTime,
+                p_interp_time              = key_value_float("interp_time")               => CameraProperty::InterpTime,
             }
         }
 
-        open_block("camera")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(property_list.repeated().collect::<Vec<CameraProperty>>())
             .then_ignore(close_block())
             .map(|properties: Vec<CameraProperty>| {
@@ -484,4 +492,19 @@ mod tests {
         assert_eq!(cameras.cameras[1].id, 2);
         assert_eq!(cameras.cameras[1].targetname, "camera2");
     }
+
+    #[test]
+    fn test_camera_integer_floats() {
+        for value in ["5", "5.0"] {
+            let input = format!(
+                r#"camera {{ "wait" "{0}" "speed" "{0}" "fov_rate" "{0}" "interp_time" "{0}" }}"#,
+                value
+            );
+            let camera = Camera::parse(lex(&input)).unwrap();
+            assert_eq!(camera.wait, Some(5.0));
+            assert_eq!(camera.speed, Some(5.0));
+            assert_eq!(camera.fov_rate, Some(5.0));
+            assert_eq!(camera.interp_time, Some(5.0));
+        }
+    }
 }
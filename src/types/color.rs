@@ -1,12 +1,13 @@
 use crate::{
     parser::{any_quoted_string, quoted_string, InternalParser, TokenError, TokenSource},
-    Parser,
+    Parser, VMFError,
 };
 
 use chumsky::{error::Rich, Parser as ChumskyParser};
+use std::str::FromStr;
 
 /// Represents an RGB color with three components
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -16,6 +17,16 @@ pub struct Color {
 /// Public parser trait implementation that allows [`Color`] to use ::parse(input) call.
 impl Parser<'_> for Color {}
 
+impl FromStr for Color {
+    type Err = VMFError;
+
+    /// Parses a color value like `"10 100 250"`, without the `"color"` key.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        parse_color_str(src)
+            .map_err(|err| VMFError::ParseError(format!("Invalid color: {}", err)))
+    }
+}
+
 /// A [`InternalParser`] implementation for [`Color`].
 /// Every key-value pair needs to be in order, like in the example bellow.
 ///
@@ -30,20 +41,23 @@ impl<'src> InternalParser<'src> for Color {
     {
         quoted_string("color")
             .ignore_then(any_quoted_string())
-            .try_map(|s: &str, span| {
-                let mut parts = s.split_whitespace().map(str::parse::<u8>);
-                let (r, g, b) = match (parts.next(), parts.next(), parts.next()) {
-                    (Some(Ok(r)), Some(Ok(g)), Some(Ok(b))) => (r, g, b),
-                    _ => return Err(Rich::custom(span, "invalid color components")),
-                };
-
-                if parts.next().is_some() {
-                    return Err(Rich::custom(span, "too many color components"));
-                }
-
-                Ok(Color { r, g, b })
-            })
+            .try_map(|s: &str, span| parse_color_str(s).map_err(|err| Rich::custom(span, err)))
+    }
+}
+
+/// Helper to parse a string like "10 100 250" into a [`Color`]
+pub(crate) fn parse_color_str(s: &str) -> Result<Color, String> {
+    let mut parts = s.split_whitespace().map(str::parse::<u8>);
+    let (r, g, b) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(r)), Some(Ok(g)), Some(Ok(b))) => (r, g, b),
+        _ => return Err("invalid color components".to_string()),
+    };
+
+    if parts.next().is_some() {
+        return Err("too many color components".to_string());
     }
+
+    Ok(Color { r, g, b })
 }
 
 #[cfg(test)]
@@ -99,4 +113,11 @@ mod tests {
         assert!(Color::parse(lex(r#""color" "0 300 0""#)).is_err());
         assert!(Color::parse(lex(r#""color" "0 0 999""#)).is_err());
     }
+
+    #[test]
+    fn test_color_from_str() {
+        assert_eq!("10 100 250".parse::<Color>().unwrap(), Color { r: 10, g: 100, b: 250 });
+        assert!("10 100".parse::<Color>().is_err());
+        assert!("0 0 256".parse::<Color>().is_err());
+    }
 }
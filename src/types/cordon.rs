@@ -1,17 +1,18 @@
 use chumsky::{error::Rich, IterParser, Parser as ChumskyParser};
+use std::str::FromStr;
 
 use crate::{
     impl_block_properties_parser,
     parser::{
-        any_quoted_string, close_block, key_value_boolean, open_block, quoted_string,
-        InternalParser, TokenError, TokenSource,
+        any_quoted_string, close_block, key_value_boolean, open_block, quoted_string, util::lex,
+        InternalParser, TokenError, TokenSource, VmfBlock,
     },
     types::point::{parse_point_from_numbers_str, Point3D},
-    Parser,
+    Parser, VMFError,
 };
 
 /// Represents a cordon entity (tool used to block off parts of the map)
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Cordon {
     /// Minimum bounds of the cordon box
     pub mins: Point3D,
@@ -27,6 +28,19 @@ enum CordonProperty {
     Mins(Point3D),
     Maxs(Point3D),
     Active(bool),
+    /// `mins`/`maxs` nested in a `box { }` sub-block
+    Box(Vec<CordonProperty>),
+}
+
+impl CordonProperty {
+    fn apply(self, cordon: &mut Cordon) {
+        match self {
+            CordonProperty::Mins(val) => cordon.mins = val,
+            CordonProperty::Maxs(val) => cordon.maxs = val,
+            CordonProperty::Active(val) => cordon.active = val,
+            CordonProperty::Box(props) => props.into_iter().for_each(|prop| prop.apply(cordon)),
+        }
+    }
 }
 
 /// Parse a Point3D with parentheses format "(x y z)"
@@ -63,6 +77,19 @@ where
 /// Public parser trait implementation that allows [`Cordon`] to use ::parse(input) call.
 impl Parser<'_> for Cordon {}
 
+impl VmfBlock for Cordon {
+    const BLOCK_NAME: &'static str = "cordon";
+}
+
+impl FromStr for Cordon {
+    type Err = VMFError;
+
+    /// Parses a whole `cordon { ... }` block.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Self::parse(lex(src)).map_err(VMFError::from)
+    }
+}
+
 /// A [`InternalParser`] implementation for [`Cordon`].
 ///
 /// usage: `let cordon = Cordon::parser().parse(input);`.
@@ -76,6 +103,19 @@ impl Parser<'_> for Cordon {}
 ///     "active" "0"
 /// }
 /// ```
+///
+/// Some tools nest the bounds in a `box` sub-block instead, which is accepted too:
+/// ```ignore
+/// cordon
+/// {
+///     "active" "1"
+///     box
+///     {
+///         "mins" "(-1024 -1024 -1024)"
+///         "maxs" "(1024 1024 1024)"
+///     }
+/// }
+/// ```
 impl<'src> InternalParser<'src> for Cordon {
     fn parser<I>() -> impl ChumskyParser<'src, I, Self, TokenError<'src>>
     where
@@ -89,17 +129,29 @@ impl<'src> InternalParser<'src> for Cordon {
             }
         }
 
-        open_block("cordon")
-            .ignore_then(property_list.repeated().collect::<Vec<CordonProperty>>())
+        let box_parser = open_block("box")
+            .ignore_then(
+                key_value_point_with_parens("mins")
+                    .map(CordonProperty::Mins)
+                    .or(key_value_point_with_parens("maxs").map(CordonProperty::Maxs))
+                    .repeated()
+                    .collect::<Vec<CordonProperty>>(),
+            )
+            .then_ignore(close_block())
+            .map(CordonProperty::Box);
+
+        open_block(Self::BLOCK_NAME)
+            .ignore_then(
+                property_list
+                    .or(box_parser)
+                    .repeated()
+                    .collect::<Vec<CordonProperty>>(),
+            )
             .then_ignore(close_block())
             .map(|properties: Vec<CordonProperty>| {
                 let mut cordon = Cordon::default();
                 for prop in properties {
-                    match prop {
-                        CordonProperty::Mins(val) => cordon.mins = val,
-                        CordonProperty::Maxs(val) => cordon.maxs = val,
-                        CordonProperty::Active(val) => cordon.active = val,
-                    }
+                    prop.apply(&mut cordon);
                 }
                 cordon
             })
@@ -134,7 +186,7 @@ mod tests {
         assert_eq!(cordon.maxs.x, 1024.0);
         assert_eq!(cordon.maxs.y, 1024.0);
         assert_eq!(cordon.maxs.z, 1024.0);
-        assert_eq!(cordon.active, false);
+        assert!(!cordon.active);
     }
 
     #[test]
@@ -155,7 +207,7 @@ mod tests {
         let cordon = result.unwrap();
         assert_eq!(cordon.mins.x, -500.0);
         assert_eq!(cordon.maxs.x, 500.0);
-        assert_eq!(cordon.active, true);
+        assert!(cordon.active);
     }
 
     #[test]
@@ -180,7 +232,7 @@ mod tests {
         assert_eq!(cordon.maxs.x, 100.0);
         assert_eq!(cordon.maxs.y, 200.0);
         assert_eq!(cordon.maxs.z, 300.0);
-        assert_eq!(cordon.active, true);
+        assert!(cordon.active);
     }
 
     #[test]
@@ -368,6 +420,36 @@ mod tests {
         let cordon = result.unwrap();
         assert_eq!(cordon.mins.x, 0.0);
         assert_eq!(cordon.maxs.x, 0.0);
-        assert_eq!(cordon.active, false); // Default value
+        assert!(!cordon.active); // Default value
+    }
+
+    #[test]
+    fn test_cordon_from_str() {
+        let cordon: Cordon = r#"cordon { "mins" "(-64 -64 0)" "maxs" "(64 64 128)" "active" "1" }"#
+            .parse()
+            .unwrap();
+        assert_eq!(cordon.mins, Point3D::new(-64.0, -64.0, 0.0));
+        assert_eq!(cordon.maxs, Point3D::new(64.0, 64.0, 128.0));
+        assert!(cordon.active);
+    }
+
+    #[test]
+    fn test_cordon_box_sub_block() {
+        let input = r#"
+        cordon
+        {
+            "active" "1"
+            box
+            {
+                "mins" "(-256 -128 -64)"
+                "maxs" "(256 128 64)"
+            }
+        }
+        "#;
+
+        let cordon = Cordon::parse(lex(input)).unwrap();
+        assert_eq!(cordon.mins, Point3D::new(-256.0, -128.0, -64.0));
+        assert_eq!(cordon.maxs, Point3D::new(256.0, 128.0, 64.0));
+        assert!(cordon.active);
     }
 }
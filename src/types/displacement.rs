@@ -4,14 +4,14 @@ use crate::{
     impl_block_properties_parser,
     parser::{
         any_quoted_string, close_block, key_value_boolean, key_value_numeric, open_block,
-        quoted_string, InternalParser, TokenError, TokenSource,
+        quoted_string, InternalParser, TokenError, TokenSource, VmfBlock,
     },
     types::point::{parse_point_from_numbers_str, Point3D},
     Parser,
 };
 
 /// Represents a displacement vertex
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct DispVertex {
     pub position: Point3D,
     pub normal: Point3D,
@@ -20,13 +20,13 @@ pub struct DispVertex {
 }
 
 /// Represents a displacement triangle
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct DispTri {
     pub indices: [u32; 3],
 }
 
 /// Represents displacement information for terrain
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct DispInfo {
     pub power: u32,              // Power of 2 determining grid size (2^power + 1)
     pub start_position: Point3D, // Starting position of the displacement
@@ -54,6 +54,60 @@ pub struct DispInfo {
     pub flags: u32,
 }
 
+impl DispInfo {
+    /// Displacement powers supported by Hammer and vbsp.
+    pub const POWER_RANGE: std::ops::RangeInclusive<u32> = 2..=4;
+
+    /// Most rows a per-vertex block can have, one per vertex row at the largest power.
+    pub const MAX_ROWS: usize = (1 << *Self::POWER_RANGE.end()) + 1;
+
+    /// Most numbers a single row can hold: one `x y z` triple per vertex at the largest power.
+    ///
+    /// Rows and blocks past these limits are rejected while parsing, so untrusted input
+    /// can't make the parser allocate more than a real displacement needs. A lower limit
+    /// can be set with [`ParseOptions::max_disp_rows`](crate::ParseOptions::max_disp_rows).
+    pub const MAX_ROW_VALUES: usize = Self::MAX_ROWS * 3;
+
+    /// Number of vertices along one edge of the displacement grid (`2^power + 1`).
+    ///
+    /// Returns `None` if `power` is outside [`DispInfo::POWER_RANGE`].
+    pub fn grid_size(&self) -> Option<usize> {
+        if !Self::POWER_RANGE.contains(&self.power) {
+            return None;
+        }
+        Some((1usize << self.power) + 1)
+    }
+
+    /// Checks that every per-vertex block that is present has one entry per grid vertex.
+    ///
+    /// Blocks that are absent (empty) are not reported.
+    pub fn validate(&self) -> Result<(), String> {
+        let size = self
+            .grid_size()
+            .ok_or_else(|| format!("unsupported power {}, expected 2 to 4", self.power))?;
+        let vertex_count = size * size;
+
+        let blocks = [
+            ("normals", self.normals.len()),
+            ("distances", self.distances.len()),
+            ("offsets", self.offsets.len()),
+            ("offset_normals", self.offset_normals.len()),
+            ("alphas", self.alphas.len()),
+        ];
+
+        for (name, len) in blocks {
+            if len != 0 && len != vertex_count {
+                return Err(format!(
+                    "{} has {} values, expected {} for power {}",
+                    name, len, vertex_count, self.power
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Internal [`DispInfo`] Properties to be used in a parser impl
 #[derive(Debug, Clone)]
 enum DispInfoProperty {
@@ -87,20 +141,40 @@ where
         any_quoted_string()
             .then(any_quoted_string())
             .try_map(move |(_key, value_str), span| {
+                // Check the size before parse_fn allocates anything for the row
+                let value_count = value_str.split_whitespace().count();
+                if value_count > DispInfo::MAX_ROW_VALUES {
+                    return Err(Rich::custom(
+                        span,
+                        format!(
+                            "Invalid {} data: row has {} values, at most {} are allowed",
+                            block_name,
+                            value_count,
+                            DispInfo::MAX_ROW_VALUES
+                        ),
+                    ));
+                }
+
                 // Key should be like "row0", "row1", etc.
                 parser_fn(value_str).map_err(|err_msg| {
                     Rich::custom(span, format!("Invalid {} data: {}", block_name, err_msg))
                 })
             });
 
+    // Any rows past MAX_ROWS are left unparsed, which makes close_block fail
     open_block(block_name)
-        .ignore_then(row_parser.repeated().collect::<Vec<Vec<T>>>())
+        .ignore_then(
+            row_parser
+                .repeated()
+                .at_most(DispInfo::MAX_ROWS)
+                .collect::<Vec<Vec<T>>>(),
+        )
         .then_ignore(close_block())
         .map(|rows: Vec<Vec<T>>| rows.into_iter().flatten().collect())
 }
 
 /// Parse a row of Point3D normals from a string like "x1 y1 z1 x2 y2 z2 ..."
-fn parse_normals_row<'src>(value_str: &'src str) -> Result<Vec<Point3D>, String> {
+fn parse_normals_row(value_str: &str) -> Result<Vec<Point3D>, String> {
     let mut normals = Vec::new();
     let mut parts = value_str.split_whitespace();
 
@@ -197,6 +271,10 @@ where
 /// Public parser trait implementation that allows [`DispInfo`] to use ::parse(input) call.
 impl Parser<'_> for DispInfo {}
 
+impl VmfBlock for DispInfo {
+    const BLOCK_NAME: &'static str = "dispinfo";
+}
+
 /// A [`InternalParser`] implementation for [`DispInfo`].
 ///
 /// usage: `let dispinfo = DispInfo::parser().parse(input);`.
@@ -280,7 +358,7 @@ impl<'src> InternalParser<'src> for DispInfo {
             .or(triangle_tags_parser)
             .or(allowed_verts_parser);
 
-        open_block("dispinfo")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(any_property.repeated().collect::<Vec<DispInfoProperty>>())
             .then_ignore(close_block())
             .map(|properties: Vec<DispInfoProperty>| {
@@ -331,7 +409,7 @@ mod tests {
         let dispinfo = result.unwrap();
         assert_eq!(dispinfo.power, 2);
         assert_eq!(dispinfo.elevation, 0.0);
-        assert_eq!(dispinfo.subdiv, false);
+        assert!(!dispinfo.subdiv);
         assert_eq!(dispinfo.start_position.x, 0.0);
         assert_eq!(dispinfo.start_position.y, 0.0);
         assert_eq!(dispinfo.start_position.z, 0.0);
@@ -361,7 +439,7 @@ mod tests {
         let dispinfo = result.unwrap();
         assert_eq!(dispinfo.power, 3);
         assert_eq!(dispinfo.elevation, 5.0);
-        assert_eq!(dispinfo.subdiv, true);
+        assert!(dispinfo.subdiv);
         assert_eq!(dispinfo.start_position.x, 100.0);
         assert_eq!(dispinfo.start_position.y, 200.0);
         assert_eq!(dispinfo.start_position.z, 0.0);
@@ -555,7 +633,7 @@ mod tests {
         assert_eq!(dispinfo.power, 3);
         assert_eq!(dispinfo.start_position.x, 128.0);
         assert_eq!(dispinfo.elevation, 10.0);
-        assert_eq!(dispinfo.subdiv, true);
+        assert!(dispinfo.subdiv);
         assert_eq!(dispinfo.flags, 0);
         assert_eq!(dispinfo.normals.len(), 2);
         assert_eq!(dispinfo.distances.len(), 2);
@@ -592,7 +670,7 @@ mod tests {
 
         let dispinfo = result.unwrap();
         assert_eq!(dispinfo.power, 2);
-        assert_eq!(dispinfo.subdiv, true);
+        assert!(dispinfo.subdiv);
         assert_eq!(dispinfo.elevation, 5.0);
         assert_eq!(dispinfo.normals.len(), 1);
         assert_eq!(dispinfo.distances.len(), 1);
@@ -701,6 +779,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dispinfo_validate() {
+        let input = r#"
+        dispinfo
+        {
+            "power" "2"
+            distances
+            {
+                "row0" "0 0 0 0 0"
+                "row1" "0 0 0 0 0"
+                "row2" "0 0 0 0 0"
+                "row3" "0 0 0 0 0"
+                "row4" "0 0 0 0 0"
+            }
+        }
+        "#;
+
+        let mut dispinfo = DispInfo::parse(lex(input)).unwrap();
+        assert_eq!(dispinfo.grid_size(), Some(5));
+        assert!(dispinfo.validate().is_ok());
+
+        dispinfo.distances.pop();
+        assert!(dispinfo.validate().is_err());
+    }
+
+    #[test]
+    fn test_dispinfo_oversized_row() {
+        let row = vec!["0"; DispInfo::MAX_ROW_VALUES + 1].join(" ");
+        let input = format!(
+            r#"dispinfo {{ "power" "2" distances {{ "row0" "{}" }} }}"#,
+            row
+        );
+        assert!(DispInfo::parse(lex(&input)).is_err());
+
+        let rows: String = (0..=DispInfo::MAX_ROWS)
+            .map(|i| format!(r#""row{}" "0" "#, i))
+            .collect();
+        let input = format!(r#"dispinfo {{ "power" "2" alphas {{ {} }} }}"#, rows);
+        assert!(DispInfo::parse(lex(&input)).is_err());
+    }
+
+    #[test]
+    fn test_dispinfo_power_out_of_range() {
+        for power in [0, 1, 5, 20] {
+            let dispinfo = DispInfo {
+                power,
+                ..Default::default()
+            };
+            assert_eq!(dispinfo.grid_size(), None);
+            assert!(dispinfo.validate().is_err(), "power {} accepted", power);
+        }
+
+        let dispinfo = DispInfo {
+            power: 4,
+            ..Default::default()
+        };
+        assert_eq!(dispinfo.grid_size(), Some(17));
+    }
+
     #[test]
     fn test_dispinfo_startposition_with_brackets() {
         let input = r#"
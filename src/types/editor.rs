@@ -1,24 +1,38 @@
 use chumsky::{IterParser, Parser as ChumskyParser};
+use std::borrow::Cow;
 
 use crate::{
     impl_block_properties_parser,
     parser::{
-        close_block, key_value, key_value_boolean, key_value_numeric, open_block, InternalParser,
-        TokenError, TokenSource,
+        close_block, key_value_boolean_ci, key_value_ci, key_value_numeric_ci, open_block,
+        InternalParser, TokenError, TokenSource, VmfBlock,
     },
-    types::Color,
+    types::{owned_str, Color},
     Parser,
 };
 
 /// Represents editor-specific data for entities and brushes
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct EditorData<'src> {
     pub color: Color,
     pub visgroupshown: bool,
     pub visgroupautoshown: bool,
     pub groupid: Option<u32>,
-    pub comments: Option<&'src str>,
-    pub logicalpos: Option<&'src str>,
+    /// Ids of the visgroups this object belongs to, one `visgroupid` key each.
+    pub visgroupids: Vec<u32>,
+    pub comments: Option<Cow<'src, str>>,
+    pub logicalpos: Option<Cow<'src, str>>,
+}
+
+impl EditorData<'_> {
+    /// Converts this editor data into one that owns all of its data.
+    pub fn into_owned(self) -> EditorData<'static> {
+        EditorData {
+            comments: self.comments.map(owned_str),
+            logicalpos: self.logicalpos.map(owned_str),
+            ..self
+        }
+    }
 }
 
 /// Internal [`EditorData`] Properties to be used in a parser impl
@@ -28,6 +42,7 @@ enum EditorDataProperty<'src> {
     VisGroupShown(bool),
     VisGroupAutoShown(bool),
     GroupId(u32),
+    VisGroupIds(Vec<u32>),
     Comments(&'src str),
     LogicalPos(&'src str),
 }
@@ -35,6 +50,10 @@ enum EditorDataProperty<'src> {
 /// Public parser trait implementation that allows [`EditorData`] to use ::parse(input) call.
 impl<'src> Parser<'src> for EditorData<'src> {}
 
+impl VmfBlock for EditorData<'_> {
+    const BLOCK_NAME: &'static str = "editor";
+}
+
 /// A [`InternalParser`] implementation for [`EditorData`].
 ///
 /// usage: `let editor = EditorData::parser().parse(input);`.
@@ -44,6 +63,8 @@ impl<'src> Parser<'src> for EditorData<'src> {}
 /// editor
 /// {
 ///     "color" "0 111 152"
+///     "visgroupid" "5"
+///     "visgroupid" "8"
 ///     "visgroupshown" "1"
 ///     "visgroupautoshown" "1"
 ///     "logicalpos" "[0 10000]"
@@ -58,15 +79,17 @@ impl<'src> InternalParser<'src> for EditorData<'src> {
         impl_block_properties_parser! {
             property_list: EditorDataProperty = {
                 p_color                = Color::parser()                       => EditorDataProperty::Color,
-                p_visgroupshown        = key_value_boolean("visgroupshown")    => EditorDataProperty::VisGroupShown,
-                p_visgroupautoshown    = key_value_boolean("visgroupautoshown") => EditorDataProperty::VisGroupAutoShown,
-                p_groupid              = key_value_numeric("groupid")          => EditorDataProperty::GroupId,
-                p_comments             = key_value("comments")                 => |s: &str| EditorDataProperty::Comments(s),
-                p_logicalpos           = key_value("logicalpos")               => |s: &str| EditorDataProperty::LogicalPos(s),
+                p_visgroupshown        = key_value_boolean_ci("visgroupshown") => EditorDataProperty::VisGroupShown,
+                p_visgroupautoshown    = key_value_boolean_ci("visgroupautoshown") => EditorDataProperty::VisGroupAutoShown,
+                p_groupid              = key_value_numeric_ci("groupid")       => EditorDataProperty::GroupId,
+                #[repeated]
+                p_visgroupid           = key_value_numeric_ci("visgroupid")    => EditorDataProperty::VisGroupIds,
+                p_comments             = key_value_ci("comments")              => |s: &str| EditorDataProperty::Comments(s),
+                p_logicalpos           = key_value_ci("logicalpos")            => |s: &str| EditorDataProperty::LogicalPos(s),
             }
         }
 
-        open_block("editor")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(
                 property_list
                     .repeated()
@@ -83,8 +106,9 @@ impl<'src> InternalParser<'src> for EditorData<'src> {
                             editor.visgroupautoshown = val
                         }
                         EditorDataProperty::GroupId(val) => editor.groupid = Some(val),
-                        EditorDataProperty::Comments(val) => editor.comments = Some(val),
-                        EditorDataProperty::LogicalPos(val) => editor.logicalpos = Some(val),
+                        EditorDataProperty::VisGroupIds(val) => editor.visgroupids.extend(val),
+                        EditorDataProperty::Comments(val) => editor.comments = Some(val.into()),
+                        EditorDataProperty::LogicalPos(val) => editor.logicalpos = Some(val.into()),
                     }
                 }
                 editor
@@ -119,10 +143,10 @@ mod tests {
         assert_eq!(editor.color.r, 0);
         assert_eq!(editor.color.g, 111);
         assert_eq!(editor.color.b, 152);
-        assert_eq!(editor.visgroupshown, true);
-        assert_eq!(editor.visgroupautoshown, true);
-        assert_eq!(editor.logicalpos, Some("[0 10000]"));
-        assert_eq!(editor.comments, Some("Test comment"));
+        assert!(editor.visgroupshown);
+        assert!(editor.visgroupautoshown);
+        assert_eq!(editor.logicalpos.as_deref(), Some("[0 10000]"));
+        assert_eq!(editor.comments.as_deref(), Some("Test comment"));
     }
 
     #[test]
@@ -144,10 +168,10 @@ mod tests {
         assert_eq!(editor.color.r, 255);
         assert_eq!(editor.color.g, 0);
         assert_eq!(editor.color.b, 0);
-        assert_eq!(editor.visgroupshown, true);
-        assert_eq!(editor.visgroupautoshown, true);
-        assert_eq!(editor.logicalpos, None);
-        assert_eq!(editor.comments, None);
+        assert!(editor.visgroupshown);
+        assert!(editor.visgroupautoshown);
+        assert_eq!(editor.logicalpos.as_deref(), None);
+        assert_eq!(editor.comments.as_deref(), None);
     }
 
     #[test]
@@ -171,10 +195,10 @@ mod tests {
         assert_eq!(editor.color.r, 100);
         assert_eq!(editor.color.g, 200);
         assert_eq!(editor.color.b, 50);
-        assert_eq!(editor.visgroupshown, false);
-        assert_eq!(editor.visgroupautoshown, false);
-        assert_eq!(editor.logicalpos, Some("[0 5000]"));
-        assert_eq!(editor.comments, Some("Out of order test"));
+        assert!(!editor.visgroupshown);
+        assert!(!editor.visgroupautoshown);
+        assert_eq!(editor.logicalpos.as_deref(), Some("[0 5000]"));
+        assert_eq!(editor.comments.as_deref(), Some("Out of order test"));
     }
 
     #[test]
@@ -194,8 +218,8 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let editor = result.unwrap();
-        assert_eq!(editor.logicalpos, Some("[0 0]"));
-        assert_eq!(editor.comments, None);
+        assert_eq!(editor.logicalpos.as_deref(), Some("[0 0]"));
+        assert_eq!(editor.comments.as_deref(), None);
     }
 
     #[test]
@@ -215,8 +239,8 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let editor = result.unwrap();
-        assert_eq!(editor.comments, Some("This brush needs work"));
-        assert_eq!(editor.logicalpos, None);
+        assert_eq!(editor.comments.as_deref(), Some("This brush needs work"));
+        assert_eq!(editor.logicalpos.as_deref(), None);
     }
 
     #[test]
@@ -235,7 +259,7 @@ mod tests {
         let default = EditorData::default();
         assert_eq!(editor.color.r, default.color.r);
         assert_eq!(editor.visgroupshown, default.visgroupshown);
-        assert_eq!(editor.comments, None);
+        assert_eq!(editor.comments.as_deref(), None);
     }
 
     #[test]
@@ -325,11 +349,34 @@ mod tests {
 
         let editor = result.unwrap();
         assert_eq!(
-            editor.comments,
+            editor.comments.as_deref(),
             Some("This is a very long comment that describes the purpose of this brush in detail")
         );
     }
 
+    #[test]
+    fn test_editor_repeated_visgroupid() {
+        let input = r#"
+        editor
+        {
+            "color" "0 255 0"
+            "visgroupid" "5"
+            "visgroupid" "8"
+            "visgroupshown" "1"
+            "visgroupid" "13"
+            "visgroupautoshown" "1"
+        }
+        "#;
+
+        let editor = EditorData::parse(lex(input)).unwrap();
+        assert_eq!(editor.visgroupids, vec![5, 8, 13]);
+        assert!(editor.visgroupshown);
+        assert!(editor.visgroupautoshown);
+
+        let editor = EditorData::parse(lex(r#"editor { "color" "0 255 0" }"#)).unwrap();
+        assert!(editor.visgroupids.is_empty());
+    }
+
     #[test]
     fn test_editor_duplicate_properties_last_wins() {
         let input = r#"
@@ -350,6 +397,6 @@ mod tests {
         assert_eq!(editor.color.r, 200);
         assert_eq!(editor.color.g, 200);
         assert_eq!(editor.color.b, 200);
-        assert_eq!(editor.visgroupshown, true);
+        assert!(editor.visgroupshown);
     }
 }
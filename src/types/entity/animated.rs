@@ -0,0 +1,97 @@
+use crate::types::{Entity, PropertiesExt};
+
+/// Classnames of props that play model animations.
+const ANIMATED_PROP_CLASSES: [&str; 6] = [
+    "prop_dynamic",
+    "prop_dynamic_override",
+    "prop_dynamic_ornament",
+    "prop_physics",
+    "prop_physics_override",
+    "prop_ragdoll",
+];
+
+/// Typed view over the animation keys of a model entity such as `prop_dynamic`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedProp<'a> {
+    pub targetname: Option<&'a str>,
+    /// Model path, e.g. `models/props_c17/door01_left.mdl`.
+    pub model: &'a str,
+    /// Index of the sequence shown in the editor.
+    pub sequence: Option<u32>,
+    /// Animation played when the prop spawns or finishes another animation.
+    pub default_anim: Option<&'a str>,
+    /// Name of a separate model whose animations this prop uses.
+    pub animate_name: Option<&'a str>,
+}
+
+impl<'a> AnimatedProp<'a> {
+    /// Builds an [`AnimatedProp`] from `entity`.
+    ///
+    /// Returns `None` if the entity is not an animated prop class or has no `model` key.
+    /// A `sequence` that isn't a number is treated as missing.
+    pub fn from_entity(entity: &'a Entity<'_>) -> Option<Self> {
+        if !ANIMATED_PROP_CLASSES.contains(&&*entity.classname) {
+            return None;
+        }
+
+        let props = &entity.properties;
+        Some(AnimatedProp {
+            targetname: entity.targetname.as_deref(),
+            model: entity.model.as_deref()?,
+            sequence: props
+                .get_parsed("sequence", |value| {
+                    value.parse::<u32>().map_err(|e| e.to_string())
+                })
+                .ok()
+                .flatten(),
+            default_anim: props.get_str("defaultanim").filter(|s| !s.is_empty()),
+            animate_name: props.get_str("animatename").filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, Parser};
+
+    #[test]
+    fn test_animated_prop_from_entity() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "30"
+            "classname" "prop_dynamic"
+            "targetname" "gate"
+            "model" "models/props_lab/blastdoor001c.mdl"
+            "sequence" "2"
+            "defaultanim" "open_idle"
+            "animatename" ""
+            "origin" "0 0 0"
+        }
+        "#))
+        .unwrap();
+
+        let prop = AnimatedProp::from_entity(&entity).expect("should be an animated prop");
+        assert_eq!(prop.model, "models/props_lab/blastdoor001c.mdl");
+        assert_eq!(prop.targetname, Some("gate"));
+        assert_eq!(prop.sequence, Some(2));
+        assert_eq!(prop.default_anim, Some("open_idle"));
+        assert_eq!(prop.animate_name, None);
+    }
+
+    #[test]
+    fn test_animated_prop_rejects_static_props() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "31"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+        }
+        "#))
+        .unwrap();
+
+        assert!(AnimatedProp::from_entity(&entity).is_none());
+    }
+}
@@ -1,47 +1,55 @@
-use chumsky::{IterParser, Parser as ChumskyParser};
-use std::collections::HashMap;
+use chumsky::{prelude::recursive, IterParser, Parser as ChumskyParser};
+use std::borrow::Cow;
 
 use crate::{
-    impl_block_properties_parser,
     parser::{
-        any_quoted_string, close_block, key_value, key_value_boolean, key_value_numeric,
-        open_block, quoted_string, InternalParser, TokenError, TokenSource,
+        any_quoted_string, close_block, open_block, parse_float, skip_unknown_block,
+        InternalParser, TokenError, TokenSource, VmfBlock,
     },
     types::{
+        color::parse_color_str,
         entity::{parse_output_entry, EntityOutput},
-        point::{key_value_point3d, Point3D},
-        Color, EditorData, Solid,
+        owned_str,
+        point::{parse_point_from_numbers_str, Point3D},
+        Color, EditorData, Group, Properties, PropertiesExt, Solid,
     },
     Parser,
 };
 
 /// Represents a generic entity in a VMF file
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Entity<'src> {
     pub id: u32,
-    pub classname: &'src str,
+    pub classname: Cow<'src, str>,
     pub origin: Option<Point3D>,
     pub angles: Option<Point3D>,
 
     // Common entity properties
-    pub targetname: Option<&'src str>,
-    pub parentname: Option<&'src str>,
-    pub target: Option<&'src str>,
-    pub model: Option<&'src str>,
+    pub targetname: Option<Cow<'src, str>>,
+    pub parentname: Option<Cow<'src, str>>,
+    pub target: Option<Cow<'src, str>>,
+    pub model: Option<Cow<'src, str>>,
     pub skin: Option<u32>,
     pub spawnflags: Option<u32>,
     pub rendermode: Option<u32>,
     pub renderamt: Option<u32>,
     pub rendercolor: Option<Color>,
+    pub renderfx: Option<u32>,
+    pub disablex360: Option<bool>,
     pub disableshadows: Option<bool>,
     pub disablereceiveshadows: Option<bool>,
     pub startdisabled: Option<bool>,
 
+    // Distance fading of models and sprites
+    pub fademindist: Option<f32>,
+    pub fademaxdist: Option<f32>,
+    pub fadescale: Option<f32>,
+
     // Entity connections (outputs)
     pub outputs: Vec<EntityOutput<'src>>,
 
     // Custom key-value pairs for entity-specific properties
-    pub properties: HashMap<&'src str, &'src str>,
+    pub properties: Properties<'src>,
 
     // Solids (for brush entities)
     pub solids: Vec<Solid<'src>>,
@@ -50,6 +58,104 @@ pub struct Entity<'src> {
     pub editor: Option<EditorData<'src>>,
 }
 
+/// An [`Entity`] that owns all of its data and no longer borrows the source file.
+///
+/// Owned entities are `Send + Sync`, so they can be moved to or shared between worker
+/// threads after the [`VMF`](crate::VMF) they were parsed from is dropped. Every type
+/// reachable from an entity must keep this guarantee, which is checked by a compile test.
+pub type EntityOwned = Entity<'static>;
+
+impl Entity<'_> {
+    /// Converts this entity into an [`EntityOwned`], copying any borrowed strings.
+    pub fn into_owned(self) -> EntityOwned {
+        Entity {
+            id: self.id,
+            classname: owned_str(self.classname),
+            origin: self.origin,
+            angles: self.angles,
+            targetname: self.targetname.map(owned_str),
+            parentname: self.parentname.map(owned_str),
+            target: self.target.map(owned_str),
+            model: self.model.map(owned_str),
+            skin: self.skin,
+            spawnflags: self.spawnflags,
+            rendermode: self.rendermode,
+            renderamt: self.renderamt,
+            rendercolor: self.rendercolor,
+            renderfx: self.renderfx,
+            disablex360: self.disablex360,
+            disableshadows: self.disableshadows,
+            disablereceiveshadows: self.disablereceiveshadows,
+            startdisabled: self.startdisabled,
+            fademindist: self.fademindist,
+            fademaxdist: self.fademaxdist,
+            fadescale: self.fadescale,
+            outputs: self
+                .outputs
+                .into_iter()
+                .map(EntityOutput::into_owned)
+                .collect(),
+            properties: self
+                .properties
+                .into_iter()
+                .map(|(key, value)| (owned_str(key), owned_str(value)))
+                .collect(),
+            solids: self.solids.into_iter().map(Solid::into_owned).collect(),
+            editor: self.editor.map(EditorData::into_owned),
+        }
+    }
+
+    /// Distance past which the entity can be culled, taken from `fademaxdist`.
+    ///
+    /// Returns `None` if the key is missing or not positive, which means the entity never
+    /// fades out.
+    pub fn cull_radius(&self) -> Option<f32> {
+        self.fademaxdist.filter(|dist| *dist > 0.0)
+    }
+
+    /// Returns the entity's orientation with the `pitch` key applied.
+    ///
+    /// Lights and a few other entities store their pitch in a separate `pitch` key, which
+    /// the engine uses instead of the first component of `angles`. An unparsable `pitch`
+    /// is ignored. Returns `None` if neither key is set.
+    pub fn effective_angles(&self) -> Option<Point3D> {
+        let pitch = self.properties.get_f32("pitch").ok().flatten();
+        match (self.angles, pitch) {
+            (Some(angles), Some(pitch)) => Some(Point3D { x: pitch, ..angles }),
+            (None, Some(pitch)) => Some(Point3D::new(pitch, 0.0, 0.0)),
+            (angles, None) => angles,
+        }
+    }
+
+    /// Returns the value of a custom key that has no dedicated field.
+    pub fn get_property(&self, key: &str) -> Option<&str> {
+        self.properties.get_str(key)
+    }
+
+    /// Iterates over the custom keys starting with `_`, like `_light` or `_minlight`.
+    ///
+    /// These are mostly compile-time lighting settings, returned in source order.
+    pub fn underscore_properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .filter(|(key, _)| key.starts_with('_'))
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+    }
+
+    /// Returns the `model` key if it is a model file path.
+    ///
+    /// Internal brush model references like `*12` are not paths and return `None`, use
+    /// [`brush_model_index`](Self::brush_model_index) for those.
+    pub fn model_path(&self) -> Option<&str> {
+        self.model.as_deref().filter(|model| !model.starts_with('*'))
+    }
+
+    /// Returns `N` if the `model` key references an internal brush model in the `*N` form.
+    pub fn brush_model_index(&self) -> Option<u32> {
+        self.model.as_deref()?.strip_prefix('*')?.parse().ok()
+    }
+}
+
 /// Internal [`Entity`] Properties to be used in a parser impl
 #[derive(Debug, Clone)]
 enum EntityProperty<'src> {
@@ -66,53 +172,113 @@ enum EntityProperty<'src> {
     RenderMode(u32),
     RenderAmt(u32),
     RenderColor(Color),
+    RenderFx(u32),
+    DisableX360(bool),
     DisableShadows(bool),
     DisableReceiveShadows(bool),
     StartDisabled(bool),
+    FadeMinDist(f32),
+    FadeMaxDist(f32),
+    FadeScale(f32),
     Editor(EditorData<'src>),
     Connections(Vec<EntityOutput<'src>>),
     Solid(Solid<'src>),
+    WrappedSolids(Vec<Solid<'src>>),
     Custom(&'src str, &'src str),
 }
 
-/// Parser for the connections block containing entity outputs
+/// Parser for the connections block containing entity outputs.
+/// Entries that aren't a valid Source 1 output, and any nested blocks, are skipped so a
+/// single odd connection doesn't lose the whole entity.
 fn parse_connections_block<'src, I>(
 ) -> impl ChumskyParser<'src, I, Vec<EntityOutput<'src>>, TokenError<'src>>
 where
     I: TokenSource<'src>,
 {
+    let entry = parse_output_entry()
+        .map(Some)
+        .or(any_quoted_string().then(any_quoted_string()).map(|_| None))
+        .or(skip_unknown_block(&[]).map(|_| None));
+
     open_block("connections")
-        .ignore_then(parse_output_entry().repeated().collect())
+        .ignore_then(entry.repeated().collect::<Vec<_>>())
         .then_ignore(close_block())
+        .map(|entries| entries.into_iter().flatten().collect())
 }
 
-/// Parse a color from rendercolor format "R G B"
-fn parse_rendercolor<'src, I>() -> impl ChumskyParser<'src, I, Color, TokenError<'src>>
+/// Parser for `hidden` and `group` blocks wrapping brush entity solids.
+/// The wrappers are dropped and all solids found inside them, at any depth, are returned.
+fn parse_solid_container<'src, I>(
+) -> impl ChumskyParser<'src, I, Vec<Solid<'src>>, TokenError<'src>>
 where
     I: TokenSource<'src>,
 {
-    use chumsky::error::Rich;
-
-    quoted_string("rendercolor")
-        .ignore_then(any_quoted_string())
-        .try_map(|s: &str, span| {
-            let mut parts = s.split_whitespace().map(str::parse::<u8>);
-            let (r, g, b) = match (parts.next(), parts.next(), parts.next()) {
-                (Some(Ok(r)), Some(Ok(g)), Some(Ok(b))) => (r, g, b),
-                _ => return Err(Rich::custom(span, "invalid rendercolor components")),
-            };
-
-            if parts.next().is_some() {
-                return Err(Rich::custom(span, "too many rendercolor components"));
-            }
+    recursive(|container| {
+        let item = Solid::parser()
+            .map(|solid| vec![solid])
+            .or(container)
+            .or(any_quoted_string()
+                .then(any_quoted_string())
+                .map(|_| Vec::new()))
+            .or(skip_unknown_block(&["solid", "hidden", "group"]).map(|_| Vec::new()));
 
-            Ok(Color { r, g, b })
-        })
+        open_block("hidden")
+            .or(open_block(Group::BLOCK_NAME))
+            .ignore_then(item.repeated().collect::<Vec<Vec<Solid>>>())
+            .then_ignore(close_block())
+            .map(|solids| solids.into_iter().flatten().collect())
+            .boxed()
+    })
+}
+
+/// Turns a key-value pair into a typed [`EntityProperty`] by dispatching on the key.
+///
+/// Matching the key once is much cheaper than trying a parser per known key, which adds up
+/// on maps with thousands of point entities. Known keys whose value doesn't fit the typed
+/// field are kept as [`EntityProperty::Custom`].
+fn entity_key_value<'src>(key: &'src str, value: &'src str) -> EntityProperty<'src> {
+    fn boolean(value: &str) -> Option<bool> {
+        match value {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    let typed = match key {
+        "id" => value.parse().ok().map(EntityProperty::Id),
+        "classname" => Some(EntityProperty::Classname(value)),
+        "origin" => parse_point_from_numbers_str(value).ok().map(EntityProperty::Origin),
+        "angles" => parse_point_from_numbers_str(value).ok().map(EntityProperty::Angles),
+        "targetname" => Some(EntityProperty::Targetname(value)),
+        "parentname" => Some(EntityProperty::Parentname(value)),
+        "target" => Some(EntityProperty::Target(value)),
+        "model" => Some(EntityProperty::Model(value)),
+        "skin" => value.parse().ok().map(EntityProperty::Skin),
+        "spawnflags" => value.parse().ok().map(EntityProperty::SpawnFlags),
+        "rendermode" => value.parse().ok().map(EntityProperty::RenderMode),
+        "renderamt" => value.parse().ok().map(EntityProperty::RenderAmt),
+        "rendercolor" => parse_color_str(value).ok().map(EntityProperty::RenderColor),
+        "renderfx" => value.parse().ok().map(EntityProperty::RenderFx),
+        "disablex360" => boolean(value).map(EntityProperty::DisableX360),
+        "disableshadows" => boolean(value).map(EntityProperty::DisableShadows),
+        "disablereceiveshadows" => boolean(value).map(EntityProperty::DisableReceiveShadows),
+        "startdisabled" => boolean(value).map(EntityProperty::StartDisabled),
+        "fademindist" => parse_float(value).ok().map(EntityProperty::FadeMinDist),
+        "fademaxdist" => parse_float(value).ok().map(EntityProperty::FadeMaxDist),
+        "fadescale" => parse_float(value).ok().map(EntityProperty::FadeScale),
+        _ => None,
+    };
+    typed.unwrap_or(EntityProperty::Custom(key, value))
 }
 
 /// Public parser trait implementation that allows [`Entity`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Entity<'src> {}
 
+impl VmfBlock for Entity<'_> {
+    const BLOCK_NAME: &'static str = "entity";
+}
+
 /// A [`InternalParser`] implementation for [`Entity`].
 ///
 /// usage: `let entity = Entity::parser().parse(input);`.
@@ -138,46 +304,25 @@ impl<'src> InternalParser<'src> for Entity<'src> {
     where
         I: TokenSource<'src>,
     {
-        // Known property parsers
-        impl_block_properties_parser! {
-            known_properties: EntityProperty = {
-                p_id                        = key_value_numeric("id")                          => EntityProperty::Id,
-                p_classname                 = key_value("classname")                           => |s: &str| EntityProperty::Classname(s),
-                p_origin                    = key_value_point3d("origin")                      => EntityProperty::Origin,
-                p_angles                    = key_value_point3d("angles")                      => EntityProperty::Angles,
-                p_targetname                = key_value("targetname")                          => |s: &str| EntityProperty::Targetname(s),
-                p_parentname                = key_value("parentname")                          => |s: &str| EntityProperty::Parentname(s),
-                p_target                    = key_value("target")                              => |s: &str| EntityProperty::Target(s),
-                p_model                     = key_value("model")                               => |s: &str| EntityProperty::Model(s),
-                p_skin                      = key_value_numeric("skin")                        => EntityProperty::Skin,
-                p_spawnflags                = key_value_numeric("spawnflags")                  => EntityProperty::SpawnFlags,
-                p_rendermode                = key_value_numeric("rendermode")                  => EntityProperty::RenderMode,
-                p_renderamt                 = key_value_numeric("renderamt")                   => EntityProperty::RenderAmt,
-                p_rendercolor               = parse_rendercolor()                              => EntityProperty::RenderColor,
-                p_disableshadows            = key_value_boolean("disableshadows")              => EntityProperty::DisableShadows,
-                p_disablereceiveshadows     = key_value_boolean("disablereceiveshadows")       => EntityProperty::DisableReceiveShadows,
-                p_startdisabled             = key_value_boolean("startdisabled")               => EntityProperty::StartDisabled,
-            }
-        }
+        // Every quoted key-value pair, typed by its key
+        let key_value_property = any_quoted_string()
+            .then(any_quoted_string())
+            .map(|(key, value): (&str, &str)| entity_key_value(key, value));
 
         // Nested block parsers
         let editor_parser = EditorData::parser().map(EntityProperty::Editor);
         let connections_parser = parse_connections_block().map(EntityProperty::Connections);
         let solid_parser = Solid::parser().map(EntityProperty::Solid);
-
-        // Custom property parser (catch-all for unknown properties)
-        let custom_property = any_quoted_string()
-            .then(any_quoted_string())
-            .map(|(key, value): (&str, &str)| EntityProperty::Custom(key, value));
+        let wrapped_solids_parser = parse_solid_container().map(EntityProperty::WrappedSolids);
 
         // Combine all parsers
-        let any_property = known_properties
+        let any_property = key_value_property
             .or(editor_parser)
             .or(connections_parser)
             .or(solid_parser)
-            .or(custom_property);
+            .or(wrapped_solids_parser);
 
-        open_block("entity")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(any_property.repeated().collect::<Vec<EntityProperty>>())
             .then_ignore(close_block())
             .map(|properties: Vec<EntityProperty>| {
@@ -185,28 +330,34 @@ impl<'src> InternalParser<'src> for Entity<'src> {
                 for prop in properties {
                     match prop {
                         EntityProperty::Id(val) => entity.id = val,
-                        EntityProperty::Classname(val) => entity.classname = val,
+                        EntityProperty::Classname(val) => entity.classname = val.into(),
                         EntityProperty::Origin(val) => entity.origin = Some(val),
                         EntityProperty::Angles(val) => entity.angles = Some(val),
-                        EntityProperty::Targetname(val) => entity.targetname = Some(val),
-                        EntityProperty::Parentname(val) => entity.parentname = Some(val),
-                        EntityProperty::Target(val) => entity.target = Some(val),
-                        EntityProperty::Model(val) => entity.model = Some(val),
+                        EntityProperty::Targetname(val) => entity.targetname = Some(val.into()),
+                        EntityProperty::Parentname(val) => entity.parentname = Some(val.into()),
+                        EntityProperty::Target(val) => entity.target = Some(val.into()),
+                        EntityProperty::Model(val) => entity.model = Some(val.into()),
                         EntityProperty::Skin(val) => entity.skin = Some(val),
                         EntityProperty::SpawnFlags(val) => entity.spawnflags = Some(val),
                         EntityProperty::RenderMode(val) => entity.rendermode = Some(val),
                         EntityProperty::RenderAmt(val) => entity.renderamt = Some(val),
                         EntityProperty::RenderColor(val) => entity.rendercolor = Some(val),
+                        EntityProperty::RenderFx(val) => entity.renderfx = Some(val),
+                        EntityProperty::DisableX360(val) => entity.disablex360 = Some(val),
                         EntityProperty::DisableShadows(val) => entity.disableshadows = Some(val),
                         EntityProperty::DisableReceiveShadows(val) => {
                             entity.disablereceiveshadows = Some(val)
                         }
                         EntityProperty::StartDisabled(val) => entity.startdisabled = Some(val),
+                        EntityProperty::FadeMinDist(val) => entity.fademindist = Some(val),
+                        EntityProperty::FadeMaxDist(val) => entity.fademaxdist = Some(val),
+                        EntityProperty::FadeScale(val) => entity.fadescale = Some(val),
                         EntityProperty::Editor(val) => entity.editor = Some(val),
                         EntityProperty::Connections(val) => entity.outputs = val,
                         EntityProperty::Solid(val) => entity.solids.push(val),
+                        EntityProperty::WrappedSolids(val) => entity.solids.extend(val),
                         EntityProperty::Custom(key, value) => {
-                            entity.properties.insert(key, value);
+                            entity.properties.insert(key.into(), value.into());
                         }
                     }
                 }
@@ -220,6 +371,43 @@ impl<'src> InternalParser<'src> for Entity<'src> {
 mod tests {
     use super::*;
     use crate::util::lex;
+    use crate::VMF;
+
+    #[test]
+    fn test_entity_owned_is_send_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        assert_send::<EntityOwned>();
+        assert_sync::<EntityOwned>();
+    }
+
+    #[test]
+    fn test_entity_into_owned() {
+        let owned = {
+            let input = String::from(
+                r#"entity
+                {
+                    "id" "3"
+                    "classname" "func_button"
+                    "targetname" "button"
+                    "wait" "4"
+                    connections { "OnPressed" "door,Open,,0,-1" }
+                    solid { "id" "4" side { "id" "5" "material" "TOOLS/TOOLSNODRAW" } }
+                }"#,
+            );
+            Entity::parse(lex(&input)).unwrap().into_owned()
+        };
+
+        let handle = std::thread::spawn(move || {
+            assert_eq!(owned.classname, "func_button");
+            assert_eq!(owned.targetname.as_deref(), Some("button"));
+            assert_eq!(owned.properties["wait"], "4");
+            assert_eq!(owned.outputs[0].target, "door");
+            assert_eq!(owned.solids[0].sides[0].material, "TOOLS/TOOLSNODRAW");
+            owned.id
+        });
+        assert_eq!(handle.join().unwrap(), 3);
+    }
 
     #[test]
     fn test_entity_simple_point_entity() {
@@ -281,10 +469,47 @@ mod tests {
         let entity = result.unwrap();
         assert_eq!(entity.id, 85);
         assert_eq!(entity.classname, "light");
-        assert_eq!(entity.properties.get("_light"), Some(&"255 255 255 400"));
-        assert_eq!(entity.properties.get("_lightHDR"), Some(&"-1 -1 -1 1"));
-        assert_eq!(entity.properties.get("_lightscaleHDR"), Some(&"1"));
-        assert_eq!(entity.properties.get("_quadratic_attn"), Some(&"1"));
+        assert_eq!(
+            entity.properties.get("_light").map(|v| v.as_ref()),
+            Some("255 255 255 400")
+        );
+        assert_eq!(
+            entity.properties.get("_lightHDR").map(|v| v.as_ref()),
+            Some("-1 -1 -1 1")
+        );
+        assert_eq!(
+            entity.properties.get("_lightscaleHDR").map(|v| v.as_ref()),
+            Some("1")
+        );
+        assert_eq!(
+            entity.properties.get("_quadratic_attn").map(|v| v.as_ref()),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn test_entity_special_character_values() {
+        let input = r#"
+        entity
+        {
+            "id" "12"
+            "classname" "prop_static"
+            "model" "models\props_c17\oildrum001.mdl"
+            "$basetexture" "concrete/concretefloor001a"
+            "%compilenodraw" "1"
+            "message" "$name%1 {not a block}"
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        let get = |key: &str| entity.properties.get(key).map(|v| v.as_ref());
+        assert_eq!(
+            entity.model.as_deref(),
+            Some("models\\props_c17\\oildrum001.mdl")
+        );
+        assert_eq!(get("$basetexture"), Some("concrete/concretefloor001a"));
+        assert_eq!(get("%compilenodraw"), Some("1"));
+        assert_eq!(get("message"), Some("$name%1 {not a block}"));
     }
 
     #[test]
@@ -318,6 +543,61 @@ mod tests {
         assert_eq!(entity.outputs[1].input, "TurnOff");
     }
 
+    #[test]
+    fn test_entity_empty_connections() {
+        let input = r#"
+        entity
+        {
+            "id" "21"
+            "classname" "logic_relay"
+            connections
+            {
+            }
+            "targetname" "relay"
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        assert_eq!(entity.outputs.len(), 0);
+        assert_eq!(entity.targetname.as_deref(), Some("relay"));
+
+        let entity = Entity::parse(lex(r#"entity { "id" "1" connections { } }"#)).unwrap();
+        assert!(entity.outputs.is_empty());
+    }
+
+    #[test]
+    fn test_entity_skips_malformed_connections() {
+        let input = r#"
+        entity
+        {
+            "id" "20"
+            "classname" "logic_relay"
+            connections
+            {
+                "OnTrigger" "door1,Open,,0,-1"
+                "OnTrigger" "m_target=door2 m_input=Close"
+                m_outputs
+                {
+                    "m_outputName" "OnTrigger"
+                }
+                "OnSpawn" "lamp,TurnOn,,0.5,1"
+            }
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        let targets: Vec<_> = entity.outputs.iter().map(|o| o.target.as_ref()).collect();
+        assert_eq!(targets, vec!["door1", "lamp"]);
+        assert_eq!(entity.outputs[1].output_name, "OnSpawn");
+
+        let vmf: VMF = input.parse().unwrap();
+        let (_, warnings) = vmf.parse_with_warnings().unwrap();
+        let skipped: Vec<_> = warnings.iter().map(|w| &input[w.span.clone()]).collect();
+        assert_eq!(skipped, [r#""OnTrigger" "m_target=door2 m_input=Close""#, "{"]);
+        assert!(warnings.iter().all(|w| w.block == "connections"));
+        assert!(warnings[1].message.contains("`m_outputs`"));
+    }
+
     #[test]
     fn test_entity_with_render_properties() {
         let input = r#"
@@ -369,9 +649,9 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let entity = result.unwrap();
-        assert_eq!(entity.targetname, Some("main_door"));
-        assert_eq!(entity.target, Some("door_trigger"));
-        assert_eq!(entity.parentname, Some("door_parent"));
+        assert_eq!(entity.targetname.as_deref(), Some("main_door"));
+        assert_eq!(entity.target.as_deref(), Some("door_trigger"));
+        assert_eq!(entity.parentname.as_deref(), Some("door_parent"));
     }
 
     #[test]
@@ -433,6 +713,201 @@ mod tests {
         assert_eq!(entity.classname, "test_entity");
     }
 
+    #[test]
+    fn test_entity_render_and_fade_fields() {
+        let input = r#"
+        entity
+        {
+            "id" "60"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+            "renderfx" "15"
+            "disablex360" "1"
+            "fademindist" "-1"
+            "fademaxdist" "1200.5"
+            "fadescale" "1"
+            "origin" "0 0 0"
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        assert_eq!(entity.renderfx, Some(15));
+        assert_eq!(entity.disablex360, Some(true));
+        assert_eq!(entity.fademindist, Some(-1.0));
+        assert_eq!(entity.fademaxdist, Some(1200.5));
+        assert_eq!(entity.fadescale, Some(1.0));
+        assert!(entity.properties.is_empty());
+
+        // Values that don't fit the typed field are kept as custom properties
+        let entity = Entity::parse(lex(
+            r#"entity { "id" "61" "classname" "prop_static" "renderfx" "glow" }"#,
+        ))
+        .unwrap();
+        assert_eq!(entity.renderfx, None);
+        assert_eq!(
+            entity.properties.get("renderfx").map(|v| v.as_ref()),
+            Some("glow")
+        );
+    }
+
+    #[test]
+    fn test_entity_cull_radius() {
+        let prop = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "62"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+            "fademaxdist" "2048"
+        }
+        "#))
+        .unwrap();
+        assert_eq!(prop.cull_radius(), Some(2048.0));
+
+        let no_fade = Entity {
+            fademaxdist: Some(0.0),
+            ..Default::default()
+        };
+        assert_eq!(no_fade.cull_radius(), None);
+        assert_eq!(Entity::default().cull_radius(), None);
+    }
+
+    #[test]
+    fn test_entity_effective_angles() {
+        let spot = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "7"
+            "classname" "light_spot"
+            "angles" "-45 90 0"
+            "pitch" "-90"
+        }
+        "#))
+        .unwrap();
+        assert_eq!(spot.angles, Some(Point3D::new(-45.0, 90.0, 0.0)));
+        assert_eq!(spot.effective_angles(), Some(Point3D::new(-90.0, 90.0, 0.0)));
+
+        let no_pitch = Entity {
+            angles: Some(Point3D::new(10.0, 20.0, 30.0)),
+            ..Default::default()
+        };
+        assert_eq!(no_pitch.effective_angles(), no_pitch.angles);
+        assert_eq!(Entity::default().effective_angles(), None);
+    }
+
+    #[test]
+    fn test_entity_underscore_properties() {
+        let light = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "8"
+            "classname" "light"
+            "_light" "255 255 255 200"
+            "_lightHDR" "-1 -1 -1 1"
+            "style" "0"
+        }
+        "#))
+        .unwrap();
+
+        let mut keys: Vec<_> = light.underscore_properties().collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [("_light", "255 255 255 200"), ("_lightHDR", "-1 -1 -1 1")]
+        );
+    }
+
+    #[test]
+    fn test_entity_keeps_multi_number_values() {
+        // Quoted values are single tokens, so the numbers and the exact spacing between
+        // them survive the whole top-level parse
+        let src = concat!(
+            "entity\n{\n\t\"id\" \"9\"\n\t\"classname\" \"light\"\n",
+            "\t\"_light\" \"255  255 255\t400\"\n}\n",
+        );
+        let vmf: crate::VMF = src.parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+        let light = doc.entities().next().unwrap();
+        assert_eq!(
+            light.properties.get_str("_light"),
+            Some("255  255 255\t400")
+        );
+    }
+
+    #[test]
+    fn test_entity_brush_model_index() {
+        let entity = |model: &'static str| Entity {
+            model: Some(model.into()),
+            ..Default::default()
+        };
+
+        let brush = entity("*5");
+        assert_eq!(brush.brush_model_index(), Some(5));
+        assert_eq!(brush.model_path(), None);
+
+        let prop = entity("models/foo.mdl");
+        assert_eq!(prop.brush_model_index(), None);
+        assert_eq!(prop.model_path(), Some("models/foo.mdl"));
+
+        assert_eq!(entity("*").brush_model_index(), None);
+        assert_eq!(Entity::default().model_path(), None);
+    }
+
+    #[test]
+    fn test_entity_solid_in_hidden_block() {
+        let input = r#"
+        entity
+        {
+            "id" "50"
+            "classname" "func_detail"
+            solid
+            {
+                "id" "51"
+                side { "id" "1" "material" "TOOLS/TOOLSNODRAW" }
+            }
+            hidden
+            {
+                solid
+                {
+                    "id" "52"
+                    side { "id" "2" "material" "TOOLS/TOOLSNODRAW" }
+                }
+            }
+            group
+            {
+                "id" "53"
+                hidden
+                {
+                    solid { "id" "54" }
+                }
+                editor { "color" "0 255 0" "visgroupshown" "1" "visgroupautoshown" "1" }
+            }
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        let ids: Vec<_> = entity.solids.iter().map(|solid| solid.id).collect();
+        assert_eq!(ids, vec![51, 52, 54]);
+        assert_eq!(entity.solids[1].sides[0].id, 2);
+    }
+
+    #[test]
+    fn test_entity_comma_separated_origin() {
+        let input = r#"
+        entity
+        {
+            "id" "2"
+            "classname" "info_target"
+            "origin" "0, 0, 64"
+            "angles" "0,90,0"
+        }
+        "#;
+
+        let entity = Entity::parse(lex(input)).unwrap();
+        assert_eq!(entity.origin, Some(Point3D::new(0.0, 0.0, 64.0)));
+        assert_eq!(entity.angles, Some(Point3D::new(0.0, 90.0, 0.0)));
+    }
+
     #[test]
     fn test_entity_invalid_block_name() {
         let input = r#"
@@ -562,4 +1037,17 @@ mod tests {
         assert_eq!(entity.solids[0].id, 1);
         assert_eq!(entity.solids[1].id, 2);
     }
+
+    #[test]
+    fn test_entity_integer_floats() {
+        for value in ["5", "5.0"] {
+            let input = format!(
+                r#"entity {{ "id" "1" "classname" "prop_static" "fademindist" "{0}" "fadescale" "{0}" }}"#,
+                value
+            );
+            let entity = Entity::parse(lex(&input)).unwrap();
+            assert_eq!(entity.fademindist, Some(5.0));
+            assert_eq!(entity.fadescale, Some(5.0));
+        }
+    }
 }
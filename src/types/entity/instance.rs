@@ -0,0 +1,131 @@
+use crate::types::{Entity, Point3D, Transform};
+
+/// Typed view over a `func_instance` entity.
+///
+/// Instances reference another VMF through the `file` keyvalue and place it at the
+/// entity's `origin` and `angles`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance<'a> {
+    pub targetname: Option<&'a str>,
+    pub file: &'a str,
+    pub origin: Point3D,
+    pub angles: Point3D,
+    pub fixup_style: Option<u32>,
+}
+
+impl<'a> Instance<'a> {
+    /// Builds an [`Instance`] from `entity`.
+    ///
+    /// Returns `None` if the entity is not a `func_instance` or if it has no `file` key.
+    pub fn from_entity(entity: &'a Entity<'_>) -> Option<Self> {
+        if entity.classname != "func_instance" {
+            return None;
+        }
+
+        let file: &str = entity.properties.get("file")?;
+        let fixup_style = entity
+            .properties
+            .get("fixup_style")
+            .and_then(|s| s.parse().ok());
+
+        Some(Instance {
+            targetname: entity.targetname.as_deref(),
+            file,
+            origin: entity.origin.unwrap_or_default(),
+            angles: entity.angles.unwrap_or_default(),
+            fixup_style,
+        })
+    }
+
+    /// Returns the transform that moves geometry from the instanced map into the parent map.
+    pub fn transform(&self) -> Transform {
+        Transform::new(self.origin, self.angles)
+    }
+}
+
+impl Entity<'_> {
+    /// Returns the typed [`Instance`] view if this is a `func_instance` with a `file` key.
+    pub fn as_instance(&self) -> Option<Instance<'_>> {
+        Instance::from_entity(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, Parser};
+
+    #[test]
+    fn test_instance_from_entity() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "12"
+            "classname" "func_instance"
+            "targetname" "door_a"
+            "file" "instances/door.vmf"
+            "fixup_style" "0"
+            "angles" "0 90 0"
+            "origin" "64 0 0"
+        }
+        "#))
+        .unwrap();
+
+        let instance = entity.as_instance().expect("should be an instance");
+        assert_eq!(instance.file, "instances/door.vmf");
+        assert_eq!(instance.targetname, Some("door_a"));
+        assert_eq!(instance.fixup_style, Some(0));
+    }
+
+    #[test]
+    fn test_instance_transform_yaw_and_offset() {
+        let instance = Instance {
+            targetname: None,
+            file: "instances/door.vmf",
+            origin: Point3D::new(64.0, 0.0, 0.0),
+            angles: Point3D::new(0.0, 90.0, 0.0),
+            fixup_style: None,
+        };
+
+        let p = instance.transform().apply(Point3D::new(16.0, 0.0, 8.0));
+        let expected = Point3D::new(64.0, 16.0, 8.0);
+        assert!((p - expected).length() < 1e-4, "{:?}", p);
+    }
+
+    #[test]
+    fn test_instance_requires_classname_and_file() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "classname" "func_instance"
+        }
+        "#))
+        .unwrap();
+        assert!(Instance::from_entity(&entity).is_none());
+
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "classname" "info_target"
+            "file" "instances/door.vmf"
+        }
+        "#))
+        .unwrap();
+        assert!(Instance::from_entity(&entity).is_none());
+    }
+
+    #[test]
+    fn test_as_instance_on_other_entity() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "3"
+            "classname" "prop_static"
+            "model" "models/props_c17/oildrum001.mdl"
+            "origin" "0 0 0"
+        }
+        "#))
+        .unwrap();
+        assert_eq!(entity.as_instance(), None);
+    }
+}
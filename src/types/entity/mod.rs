@@ -1,8 +1,16 @@
+mod animated;
 #[allow(clippy::module_inception)]
 mod entity;
+mod instance;
 mod output;
 mod pointentity;
+mod query;
+mod view;
 
+pub use animated::*;
 pub use entity::*;
+pub use instance::*;
 pub use output::*;
 pub use pointentity::*;
+pub use query::*;
+pub use view::*;
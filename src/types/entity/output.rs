@@ -1,18 +1,34 @@
 use chumsky::Parser as ChumskyParser;
+use std::borrow::Cow;
 
-use crate::parser::{any_quoted_string, TokenError, TokenSource};
+use crate::parser::{any_quoted_string, parse_float, TokenError, TokenSource};
+use crate::types::owned_str;
 
 /// Represents an output connection between entities
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct EntityOutput<'src> {
-    pub output_name: &'src str,
-    pub target: &'src str,
-    pub input: &'src str,
-    pub parameter: &'src str,
+    pub output_name: Cow<'src, str>,
+    pub target: Cow<'src, str>,
+    pub input: Cow<'src, str>,
+    pub parameter: Cow<'src, str>,
     pub delay: f32,
     pub times_to_fire: i32,
 }
 
+impl EntityOutput<'_> {
+    /// Converts this output into one that owns its strings.
+    pub fn into_owned(self) -> EntityOutput<'static> {
+        EntityOutput {
+            output_name: owned_str(self.output_name),
+            target: owned_str(self.target),
+            input: owned_str(self.input),
+            parameter: owned_str(self.parameter),
+            delay: self.delay,
+            times_to_fire: self.times_to_fire,
+        }
+    }
+}
+
 impl<'src> EntityOutput<'src> {
     /// Parse an output string in the format: "target,input,parameter,delay,times_to_fire"
     /// Example: "motor*,TurnOn,,0,-1"
@@ -27,9 +43,7 @@ impl<'src> EntityOutput<'src> {
             parts.next(),
         ) {
             (Some(a), Some(b), Some(c), Some(d), Some(e)) => {
-                let delay = d
-                    .parse::<f32>()
-                    .map_err(|e| format!("invalid delay '{}': {}", d, e))?;
+                let delay = parse_float(d).map_err(|e| format!("invalid delay '{}': {}", d, e))?;
                 let times_to_fire = e
                     .parse::<i32>()
                     .map_err(|e| format!("invalid times_to_fire '{}': {}", e, e))?;
@@ -39,10 +53,10 @@ impl<'src> EntityOutput<'src> {
         };
 
         Ok(EntityOutput::<'src> {
-            output_name,
-            target,
-            input,
-            parameter,
+            output_name: output_name.into(),
+            target: target.into(),
+            input: input.into(),
+            parameter: parameter.into(),
             delay,
             times_to_fire,
         })
@@ -85,6 +99,15 @@ mod tests {
         assert_eq!(output.times_to_fire, -1);
     }
 
+    #[test]
+    fn test_parse_output_string_integer_delay() {
+        for delay in ["5", "5.0"] {
+            let value = format!("door,Open,,{},1", delay);
+            let output = EntityOutput::parse_output_string("OnTrigger", &value).unwrap();
+            assert_eq!(output.delay, 5.0);
+        }
+    }
+
     #[test]
     fn test_parse_output_string_with_parameter() {
         let result = EntityOutput::parse_output_string("OnStartTouch", "door1,Open,fast,0.5,1");
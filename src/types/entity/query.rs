@@ -0,0 +1,153 @@
+use crate::types::PropertiesExt;
+
+use super::Entity;
+
+/// A single condition of an [`EntityQuery`].
+#[derive(Debug, Clone, PartialEq)]
+enum Criterion {
+    Classname(String),
+    Targetname(String),
+    HasProperty(String),
+    PropertyEquals(String, String),
+    PropertyAbove(String, f32),
+}
+
+impl Criterion {
+    fn matches(&self, entity: &Entity) -> bool {
+        match self {
+            Criterion::Classname(classname) => entity.classname == *classname,
+            Criterion::Targetname(name) => entity.targetname.as_deref() == Some(name.as_str()),
+            Criterion::HasProperty(key) => entity.properties.contains_key(key.as_str()),
+            Criterion::PropertyEquals(key, value) => {
+                entity.properties.get_str(key) == Some(value.as_str())
+            }
+            Criterion::PropertyAbove(key, min) => entity
+                .properties
+                .get_f32(key)
+                .ok()
+                .flatten()
+                .is_some_and(|value| value > *min),
+        }
+    }
+}
+
+/// Selects entities that match all of a set of conditions.
+///
+/// Property conditions look at the custom keys in [`Entity::properties`], keys with a
+/// dedicated field like `targetname` have their own methods.
+///
+/// usage:
+/// ```ignore
+/// let lights = EntityQuery::new()
+///     .classname("light")
+///     .has_property("_light")
+///     .filter(doc.entities());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EntityQuery {
+    criteria: Vec<Criterion>,
+}
+
+impl EntityQuery {
+    /// Creates a query that matches every entity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only matches entities with this exact classname.
+    pub fn classname(self, classname: impl Into<String>) -> Self {
+        self.with(Criterion::Classname(classname.into()))
+    }
+
+    /// Only matches entities with this exact targetname.
+    pub fn targetname(self, name: impl Into<String>) -> Self {
+        self.with(Criterion::Targetname(name.into()))
+    }
+
+    /// Only matches entities that set the custom key `key`.
+    pub fn has_property(self, key: impl Into<String>) -> Self {
+        self.with(Criterion::HasProperty(key.into()))
+    }
+
+    /// Only matches entities whose custom key `key` is exactly `value`.
+    pub fn property_equals(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.with(Criterion::PropertyEquals(key.into(), value.into()))
+    }
+
+    /// Only matches entities whose custom key `key` is a number greater than `min`.
+    pub fn property_above(self, key: impl Into<String>, min: f32) -> Self {
+        self.with(Criterion::PropertyAbove(key.into(), min))
+    }
+
+    /// Returns `true` if `entity` meets every condition.
+    pub fn matches(&self, entity: &Entity) -> bool {
+        self.criteria.iter().all(|criterion| criterion.matches(entity))
+    }
+
+    /// Returns the entities that meet every condition, in a single pass.
+    pub fn filter<'a, 'src: 'a>(
+        &self,
+        entities: impl IntoIterator<Item = &'a Entity<'src>>,
+    ) -> Vec<&'a Entity<'src>> {
+        entities
+            .into_iter()
+            .filter(|entity| self.matches(entity))
+            .collect()
+    }
+
+    fn with(mut self, criterion: Criterion) -> Self {
+        self.criteria.push(criterion);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, Parser};
+
+    fn entity(src: &str) -> Entity<'_> {
+        Entity::parse(lex(src)).unwrap()
+    }
+
+    #[test]
+    fn test_entity_query() {
+        let entities = vec![
+            entity(r#"entity { "id" "1" "classname" "light" "_light" "255 255 255 200" }"#),
+            entity(r#"entity { "id" "2" "classname" "light" }"#),
+            entity(r#"entity { "id" "3" "classname" "info_target" "_light" "0 0 0 0" }"#),
+            entity(r#"entity { "id" "4" "classname" "light" "_light" "255 0 0 50" }"#),
+        ];
+        let ids = |query: EntityQuery| -> Vec<u32> {
+            query.filter(&entities).iter().map(|e| e.id).collect()
+        };
+
+        assert_eq!(
+            ids(EntityQuery::new().classname("light").has_property("_light")),
+            vec![1, 4]
+        );
+        assert_eq!(
+            ids(EntityQuery::new()
+                .classname("light")
+                .property_equals("_light", "255 0 0 50")),
+            vec![4]
+        );
+        assert_eq!(ids(EntityQuery::new()), vec![1, 2, 3, 4]);
+        assert!(ids(EntityQuery::new().targetname("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_entity_query_property_above() {
+        let entities = vec![
+            entity(r#"entity { "id" "1" "classname" "func_door" "speed" "100" }"#),
+            entity(r#"entity { "id" "2" "classname" "func_door" "speed" "400" }"#),
+            entity(r#"entity { "id" "3" "classname" "func_door" "speed" "fast" }"#),
+        ];
+        let query = EntityQuery::new()
+            .classname("func_door")
+            .property_above("speed", 200.0);
+
+        let ids: Vec<_> = query.filter(&entities).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+}
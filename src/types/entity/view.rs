@@ -0,0 +1,310 @@
+use crate::types::{Color, EditorData, Point3D, Properties, Solid, World};
+
+use super::Entity;
+
+/// Read access shared by [`Entity`] and [`World`], so code that walks keyvalues or
+/// brushes can treat worldspawn like any other entity.
+pub trait EntityLike {
+    fn id(&self) -> u32;
+
+    fn classname(&self) -> &str;
+
+    fn targetname(&self) -> Option<&str>;
+
+    /// Origin of the entity. Worldspawn always sits at the map origin.
+    fn origin(&self) -> Option<Point3D>;
+
+    /// Keys that have no dedicated field.
+    fn properties(&self) -> &Properties<'_>;
+
+    /// Brushes owned by the entity, empty for point entities.
+    fn solids(&self) -> &[Solid<'_>];
+
+    fn editor(&self) -> Option<&EditorData<'_>>;
+
+    /// Every keyvalue of the block as it would appear in the file, typed fields first and
+    /// then the custom [`properties`](Self::properties) in source order. Keys that aren't set
+    /// are left out.
+    fn keyvalues(&self) -> Vec<(&str, String)>;
+}
+
+fn point(point: Point3D) -> String {
+    format!("{} {} {}", point.x, point.y, point.z)
+}
+
+fn color(color: Color) -> String {
+    format!("{} {} {}", color.r, color.g, color.b)
+}
+
+fn boolean(value: bool) -> String {
+    u8::from(value).to_string()
+}
+
+/// Drops the unset typed keys and appends the custom properties in source order.
+fn collect_keyvalues<'a>(
+    typed: impl IntoIterator<Item = (&'a str, Option<String>)>,
+    properties: &'a Properties<'_>,
+) -> Vec<(&'a str, String)> {
+    let custom = properties
+        .iter()
+        .map(|(key, value)| (key.as_ref(), value.to_string()));
+
+    typed
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .chain(custom)
+        .collect()
+}
+
+impl EntityLike for Entity<'_> {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn classname(&self) -> &str {
+        &self.classname
+    }
+
+    fn targetname(&self) -> Option<&str> {
+        self.targetname.as_deref()
+    }
+
+    fn origin(&self) -> Option<Point3D> {
+        self.origin
+    }
+
+    fn properties(&self) -> &Properties<'_> {
+        &self.properties
+    }
+
+    fn solids(&self) -> &[Solid<'_>] {
+        &self.solids
+    }
+
+    fn editor(&self) -> Option<&EditorData<'_>> {
+        self.editor.as_ref()
+    }
+
+    fn keyvalues(&self) -> Vec<(&str, String)> {
+        let typed = [
+            ("id", Some(self.id.to_string())),
+            ("classname", Some(self.classname.to_string())),
+            ("origin", self.origin.map(point)),
+            ("angles", self.angles.map(point)),
+            ("targetname", self.targetname.as_ref().map(|v| v.to_string())),
+            ("parentname", self.parentname.as_ref().map(|v| v.to_string())),
+            ("target", self.target.as_ref().map(|v| v.to_string())),
+            ("model", self.model.as_ref().map(|v| v.to_string())),
+            ("skin", self.skin.map(|v| v.to_string())),
+            ("spawnflags", self.spawnflags.map(|v| v.to_string())),
+            ("rendermode", self.rendermode.map(|v| v.to_string())),
+            ("renderamt", self.renderamt.map(|v| v.to_string())),
+            ("rendercolor", self.rendercolor.map(color)),
+            ("renderfx", self.renderfx.map(|v| v.to_string())),
+            ("disablex360", self.disablex360.map(boolean)),
+            ("disableshadows", self.disableshadows.map(boolean)),
+            ("disablereceiveshadows", self.disablereceiveshadows.map(boolean)),
+            ("startdisabled", self.startdisabled.map(boolean)),
+            ("fademindist", self.fademindist.map(|v| v.to_string())),
+            ("fademaxdist", self.fademaxdist.map(|v| v.to_string())),
+            ("fadescale", self.fadescale.map(|v| v.to_string())),
+        ];
+        collect_keyvalues(typed, &self.properties)
+    }
+}
+
+impl EntityLike for World<'_> {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn classname(&self) -> &str {
+        &self.classname
+    }
+
+    fn targetname(&self) -> Option<&str> {
+        self.targetname.as_deref()
+    }
+
+    fn origin(&self) -> Option<Point3D> {
+        Some(Point3D::default())
+    }
+
+    fn properties(&self) -> &Properties<'_> {
+        &self.properties
+    }
+
+    fn solids(&self) -> &[Solid<'_>] {
+        &self.solids
+    }
+
+    fn editor(&self) -> Option<&EditorData<'_>> {
+        self.editor.as_ref()
+    }
+
+    fn keyvalues(&self) -> Vec<(&str, String)> {
+        let typed = [
+            ("id", Some(self.id.to_string())),
+            ("mapversion", Some(self.mapversion.to_string())),
+            ("classname", Some(self.classname.to_string())),
+            ("detailmaterial", self.detailmaterial.as_ref().map(|v| v.to_string())),
+            ("detailvbsp", self.detailvbsp.as_ref().map(|v| v.to_string())),
+            ("maxpropscreenwidth", self.maxpropscreenwidth.map(|v| v.to_string())),
+            ("skyname", self.skyname.as_ref().map(|v| v.to_string())),
+            ("sounds", self.sounds.map(|v| v.to_string())),
+            ("maxrange", self.maxrange.map(|v| v.to_string())),
+            ("maxoccludeearea", self.maxoccludeearea.map(|v| v.to_string())),
+            ("minoccluderarea", self.minoccluderarea.map(|v| v.to_string())),
+            ("maxoccludeearea_csgo", self.maxoccludeearea_csgo.map(|v| v.to_string())),
+            ("minoccluderarea_csgo", self.minoccluderarea_csgo.map(|v| v.to_string())),
+            ("difficulty_level", self.difficulty_level.map(|v| v.to_string())),
+            ("hdr_level", self.hdr_level.map(|v| v.to_string())),
+            ("targetname", self.targetname.as_ref().map(|v| v.to_string())),
+            ("target", self.target.as_ref().map(|v| v.to_string())),
+            ("hidden", self.hidden.map(boolean)),
+        ];
+        collect_keyvalues(typed, &self.properties)
+    }
+}
+
+/// Borrowed view of either a world or an entity, see [`EntityLike`].
+#[derive(Debug, Clone, Copy)]
+pub enum EntityView<'a, 'src> {
+    World(&'a World<'src>),
+    Entity(&'a Entity<'src>),
+}
+
+impl<'a, 'src> EntityView<'a, 'src> {
+    fn inner(&self) -> &'a dyn EntityLike {
+        match *self {
+            EntityView::World(world) => world,
+            EntityView::Entity(entity) => entity,
+        }
+    }
+}
+
+impl EntityLike for EntityView<'_, '_> {
+    fn id(&self) -> u32 {
+        self.inner().id()
+    }
+
+    fn classname(&self) -> &str {
+        self.inner().classname()
+    }
+
+    fn targetname(&self) -> Option<&str> {
+        self.inner().targetname()
+    }
+
+    fn origin(&self) -> Option<Point3D> {
+        self.inner().origin()
+    }
+
+    fn properties(&self) -> &Properties<'_> {
+        self.inner().properties()
+    }
+
+    fn solids(&self) -> &[Solid<'_>] {
+        self.inner().solids()
+    }
+
+    fn editor(&self) -> Option<&EditorData<'_>> {
+        self.inner().editor()
+    }
+
+    fn keyvalues(&self) -> Vec<(&str, String)> {
+        self.inner().keyvalues()
+    }
+}
+
+impl<'src> World<'src> {
+    /// Returns a view of the worldspawn that can be used wherever entities are handled.
+    pub fn as_entity_view(&self) -> EntityView<'_, 'src> {
+        EntityView::World(self)
+    }
+}
+
+impl<'src> Entity<'src> {
+    /// Returns a view of the entity, see [`World::as_entity_view`].
+    pub fn as_entity_view(&self) -> EntityView<'_, 'src> {
+        EntityView::Entity(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, Parser};
+
+    fn describe(entity: &impl EntityLike) -> String {
+        format!(
+            "{} {} {:?}",
+            entity.id(),
+            entity.classname(),
+            entity.origin()
+        )
+    }
+
+    #[test]
+    fn test_entity_like_world_and_entity() {
+        let world = World::parse(lex(
+            r#"world { "id" "1" "classname" "worldspawn" "skyname" "sky_day01_01" "comment" "hi" }"#,
+        ))
+        .unwrap();
+        let entity = Entity::parse(lex(
+            r#"entity { "id" "2" "classname" "info_target" "origin" "0 0 64" "comment" "hi" }"#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            describe(&world),
+            "1 worldspawn Some(Point3D { x: 0.0, y: 0.0, z: 0.0 })"
+        );
+        assert_eq!(
+            describe(&entity),
+            "2 info_target Some(Point3D { x: 0.0, y: 0.0, z: 64.0 })"
+        );
+
+        let views = [world.as_entity_view(), entity.as_entity_view()];
+        let classnames: Vec<_> = views.iter().map(|view| view.classname()).collect();
+        assert_eq!(classnames, vec!["worldspawn", "info_target"]);
+        assert!(views
+            .iter()
+            .all(|view| view.properties().get("comment").map(|v| v.as_ref()) == Some("hi")));
+    }
+
+    #[test]
+    fn test_keyvalues() {
+        let entity = Entity::parse(lex(r#"
+        entity
+        {
+            "id" "5"
+            "classname" "light"
+            "style" "0"
+            "origin" "-192 192 128.5"
+            "disableshadows" "1"
+            "_light" "255 255 255 400"
+        }
+        "#))
+        .unwrap();
+
+        assert_eq!(
+            entity.as_entity_view().keyvalues(),
+            vec![
+                ("id", "5".to_string()),
+                ("classname", "light".to_string()),
+                ("origin", "-192 192 128.5".to_string()),
+                ("disableshadows", "1".to_string()),
+                ("style", "0".to_string()),
+                ("_light", "255 255 255 400".to_string()),
+            ]
+        );
+
+        let world =
+            World::parse(lex(r#"world { "id" "1" "classname" "worldspawn" "comment" "hi" }"#))
+                .unwrap();
+        let keyvalues = world.keyvalues();
+        assert!(keyvalues.contains(&("classname", "worldspawn".to_string())));
+        assert!(keyvalues.contains(&("comment", "hi".to_string())));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::types::{Color, PropertiesExt, World};
+
+/// Typed view over the fog keys that some games store on worldspawn.
+///
+/// ```ignore
+/// "fogenable" "1"
+/// "fogcolor" "128 140 160"
+/// "fogstart" "512"
+/// "fogend" "4096"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldFog {
+    pub enabled: bool,
+    pub color: Option<Color>,
+    pub start: Option<f32>,
+    pub end: Option<f32>,
+}
+
+impl WorldFog {
+    /// Builds a [`WorldFog`] from the custom keys of `world`.
+    ///
+    /// Returns `None` if the world has none of the fog keys. Keys with values that don't
+    /// parse are treated as missing.
+    pub fn from_world(world: &World) -> Option<Self> {
+        let props = &world.properties;
+        if !["fogenable", "fogcolor", "fogstart", "fogend"]
+            .iter()
+            .any(|key| props.contains_key(key))
+        {
+            return None;
+        }
+
+        Some(WorldFog {
+            enabled: props.get_bool("fogenable").ok().flatten().unwrap_or(false),
+            color: props.get_color("fogcolor").ok().flatten(),
+            start: props.get_f32("fogstart").ok().flatten(),
+            end: props.get_f32("fogend").ok().flatten(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, Parser};
+
+    #[test]
+    fn test_world_fog() {
+        let world = World::parse(lex(r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            "fogenable" "1"
+            "fogcolor" "128 140 160"
+            "fogstart" "512"
+            "fogend" "4096.5"
+        }
+        "#))
+        .unwrap();
+
+        assert_eq!(world.get_property("fogstart"), Some("512"));
+        assert_eq!(
+            WorldFog::from_world(&world),
+            Some(WorldFog {
+                enabled: true,
+                color: Some(Color {
+                    r: 128,
+                    g: 140,
+                    b: 160
+                }),
+                start: Some(512.0),
+                end: Some(4096.5),
+            })
+        );
+    }
+
+    #[test]
+    fn test_world_without_fog() {
+        let world = World::parse(lex(r#"world { "id" "1" "classname" "worldspawn" }"#)).unwrap();
+        assert_eq!(WorldFog::from_world(&world), None);
+    }
+}
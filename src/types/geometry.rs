@@ -0,0 +1,160 @@
+use super::Point3D;
+
+/// Distance within which a point counts as lying on a plane.
+pub(crate) const ON_EPSILON: f32 = 0.1;
+
+/// Half size of the initial winding clipped down to a brush face. Anything still this far
+/// out after clipping belongs to a face that isn't closed off by the other sides.
+pub(crate) const BOGUS_RANGE: f32 = 65536.0;
+
+/// An infinite plane, `normal · p = dist`. The normal points out of the solid.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Plane {
+    pub normal: Point3D,
+    pub dist: f32,
+}
+
+impl Plane {
+    /// Builds the plane through three points in VMF winding order (clockwise seen from
+    /// outside the brush). Returns `None` if the points are collinear.
+    pub fn from_points(p1: Point3D, p2: Point3D, p3: Point3D) -> Option<Plane> {
+        let normal = (p1 - p2).cross(p3 - p2).normalized()?;
+        Some(Plane {
+            normal,
+            dist: normal.dot(p2),
+        })
+    }
+
+    /// Signed distance of `point` from the plane, positive in front (outside).
+    pub fn distance_to(&self, point: Point3D) -> f32 {
+        self.normal.dot(point) - self.dist
+    }
+
+    /// A large square on the plane, used as the starting point for clipping.
+    pub(crate) fn base_winding(&self) -> Vec<Point3D> {
+        let n = self.normal;
+        let up = if n.z.abs() >= n.x.abs() && n.z.abs() >= n.y.abs() {
+            Point3D::new(1.0, 0.0, 0.0)
+        } else {
+            Point3D::new(0.0, 0.0, 1.0)
+        };
+
+        let up = (up - n * up.dot(n)).normalized().unwrap_or_default() * BOGUS_RANGE;
+        let right = up.cross(n);
+        let origin = n * self.dist;
+
+        vec![
+            origin - right + up,
+            origin + right + up,
+            origin + right - up,
+            origin - right - up,
+        ]
+    }
+}
+
+/// Picks three points of a convex `polygon` in VMF winding order for a plane facing
+/// along `normal`. Returns `None` for degenerate polygons.
+pub(crate) fn plane_points(
+    polygon: &[Point3D],
+    normal: Point3D,
+) -> Option<(Point3D, Point3D, Point3D)> {
+    let (&p1, rest) = polygon.split_first()?;
+    let &p2 = rest.first()?;
+    // The third point furthest from the p1-p2 line gives the most stable plane
+    let &p3 = rest[1..].iter().max_by(|a, b| {
+        let area = |p: Point3D| (p1 - p2).cross(p - p2).length();
+        area(**a).total_cmp(&area(**b))
+    })?;
+
+    let plane = Plane::from_points(p1, p2, p3)?;
+    if plane.normal.dot(normal) < 0.0 {
+        Some((p3, p2, p1))
+    } else {
+        Some((p1, p2, p3))
+    }
+}
+
+/// Cuts away the part of `polygon` in front of `plane`.
+pub(crate) fn clip_polygon(polygon: &[Point3D], plane: &Plane) -> Vec<Point3D> {
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let d_current = plane.distance_to(current);
+        let d_next = plane.distance_to(next);
+
+        if d_current <= ON_EPSILON {
+            result.push(current);
+        }
+        let crosses = (d_current > ON_EPSILON && d_next < -ON_EPSILON)
+            || (d_current < -ON_EPSILON && d_next > ON_EPSILON);
+        if crosses {
+            let t = d_current / (d_current - d_next);
+            result.push(current + (next - current) * t);
+        }
+    }
+
+    result
+}
+
+/// Axis aligned bounding box `(mins, maxs)` of `points`, `None` if there are none.
+pub(crate) fn bounds_of(points: impl IntoIterator<Item = Point3D>) -> Option<(Point3D, Point3D)> {
+    points.into_iter().fold(None, |bounds, point| match bounds {
+        Some((mins, maxs)) => Some((point.min(mins), point.max(maxs))),
+        None => Some((point, point)),
+    })
+}
+
+/// Area of a planar polygon.
+pub(crate) fn polygon_area(polygon: &[Point3D]) -> f32 {
+    let Some(&first) = polygon.first() else {
+        return 0.0;
+    };
+    let mut sum = Point3D::default();
+    for pair in polygon[1..].windows(2) {
+        sum = sum + (pair[0] - first).cross(pair[1] - first);
+    }
+    sum.length() * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plane_from_points() {
+        // Top face of a brush, normal points up and out
+        let plane = Plane::from_points(
+            Point3D::new(-64.0, 64.0, 64.0),
+            Point3D::new(64.0, 64.0, 64.0),
+            Point3D::new(64.0, -64.0, 64.0),
+        )
+        .unwrap();
+        assert_eq!(plane.normal, Point3D::new(0.0, 0.0, 1.0));
+        assert_eq!(plane.dist, 64.0);
+        assert_eq!(plane.distance_to(Point3D::new(0.0, 0.0, 0.0)), -64.0);
+
+        let line = Point3D::new(1.0, 1.0, 1.0);
+        assert!(Plane::from_points(line, line * 2.0, line * 3.0).is_none());
+    }
+
+    #[test]
+    fn test_clip_polygon() {
+        let square = [
+            Point3D::new(-1.0, -1.0, 0.0),
+            Point3D::new(1.0, -1.0, 0.0),
+            Point3D::new(1.0, 1.0, 0.0),
+            Point3D::new(-1.0, 1.0, 0.0),
+        ];
+        let plane = Plane {
+            normal: Point3D::new(1.0, 0.0, 0.0),
+            dist: 0.0,
+        };
+
+        let half = clip_polygon(&square, &plane);
+        assert_eq!(half.len(), 4);
+        assert!(half.iter().all(|p| p.x <= 0.0));
+        assert_eq!(polygon_area(&square), 4.0);
+        assert_eq!(polygon_area(&half), 2.0);
+    }
+}
@@ -2,18 +2,32 @@ use chumsky::{prelude::recursive, IterParser, Parser as ChumskyParser};
 
 use crate::{
     impl_block_properties_parser,
-    parser::{close_block, key_value_numeric, open_block, InternalParser, TokenError, TokenSource},
+    parser::{
+        close_block, key_value_numeric, open_block, InternalParser, TokenError, TokenSource,
+        VmfBlock,
+    },
     types::EditorData,
     Parser,
 };
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Group<'src> {
     pub id: u32,
     pub editor: Option<EditorData<'src>>,
     pub groups: Vec<Group<'src>>,
 }
 
+impl Group<'_> {
+    /// Converts this group into one that owns all of its data.
+    pub fn into_owned(self) -> Group<'static> {
+        Group {
+            id: self.id,
+            editor: self.editor.map(EditorData::into_owned),
+            groups: self.groups.into_iter().map(Group::into_owned).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum GroupProperty<'src> {
     Id(u32),
@@ -24,6 +38,10 @@ enum GroupProperty<'src> {
 /// Public parser trait implementation that allows [`Group`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Group<'src> {}
 
+impl VmfBlock for Group<'_> {
+    const BLOCK_NAME: &'static str = "group";
+}
+
 /// A [`InternalParser`] implementation for [`Group`].
 ///
 /// usage: `let editor = Group::parser().parse(input);`.
@@ -31,16 +49,16 @@ impl<'src> Parser<'src> for Group<'src> {}
 /// The format that is being parsed here is:
 /// ```ignore
 /// group
-/// 	{
-/// 		"id" "772983"
-/// 		editor
-/// 		{
-/// 			"color" "254 255 0"
-/// 			"groupid" "772977"
-/// 			"visgroupshown" "1"
-/// 			"visgroupautoshown" "1"
-/// 		}
-/// 	}
+///     {
+///         "id" "772983"
+///         editor
+///         {
+///             "color" "254 255 0"
+///             "groupid" "772977"
+///             "visgroupshown" "1"
+///             "visgroupautoshown" "1"
+///         }
+///     }
 ///```
 impl<'src> InternalParser<'src> for Group<'src> {
     fn parser<I>() -> impl ChumskyParser<'src, I, Self, TokenError<'src>>
@@ -56,7 +74,7 @@ impl<'src> InternalParser<'src> for Group<'src> {
                 }
             }
 
-            open_block("group")
+            open_block(Group::BLOCK_NAME)
                 .boxed()
                 .ignore_then(property_list.repeated().collect::<Vec<GroupProperty>>())
                 .then_ignore(close_block())
@@ -1,18 +1,21 @@
 // Basic types
 mod color;
 mod editor;
-pub mod error;
 mod point;
+mod properties;
 mod versioninfo;
 mod viewsettings;
 mod visgroup;
 
 // World and geometry types
 mod displacement;
+mod fog;
+pub(crate) mod geometry;
 mod group;
 mod side;
 mod solid;
 mod textureaxis;
+mod transform;
 mod world;
 
 // Entity types
@@ -27,10 +30,23 @@ pub use cordon::*;
 pub use displacement::*;
 pub use editor::*;
 pub use entity::*;
+pub use fog::*;
+pub use geometry::Plane;
 pub use group::*;
+pub use point::*;
+pub use properties::*;
 pub use side::*;
 pub use solid::*;
+pub use textureaxis::*;
+pub use transform::*;
 pub use versioninfo::*;
 pub use viewsettings::*;
 pub use visgroup::*;
 pub use world::*;
+
+use std::borrow::Cow;
+
+/// Detaches a borrowed string from the source buffer.
+pub(crate) fn owned_str(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
@@ -1,7 +1,14 @@
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
 use chumsky::{error::Rich, Parser as ChumskyParser};
 
-use crate::parser::{any_quoted_string, quoted_string, TokenError, TokenSource};
+use crate::{
+    parser::{any_quoted_string, quoted_string, TokenError, TokenSource},
+    VMFError,
+};
 
+/// A point or direction in 3D space, in Hammer units.
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Point3D {
     pub x: f32,
@@ -9,6 +16,133 @@ pub struct Point3D {
     pub z: f32,
 }
 
+impl Point3D {
+    /// Creates a new [`Point3D`] instance.
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Dot product of two vectors.
+    pub fn dot(&self, other: Point3D) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Cross product of two vectors.
+    pub fn cross(&self, other: Point3D) -> Point3D {
+        Point3D {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// Length of the vector.
+    pub fn length(&self) -> f32 {
+        self.dot(*self).sqrt()
+    }
+
+    /// Returns the vector scaled to unit length, or `None` for a zero-length vector.
+    pub fn normalized(&self) -> Option<Point3D> {
+        let len = self.length();
+        if len > f32::EPSILON {
+            Some(*self * (1.0 / len))
+        } else {
+            None
+        }
+    }
+
+    /// Component-wise minimum of two points.
+    pub fn min(&self, other: Point3D) -> Point3D {
+        Point3D::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Component-wise maximum of two points.
+    pub fn max(&self, other: Point3D) -> Point3D {
+        Point3D::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Rotates the point around the origin by Source engine `angles`.
+    ///
+    /// `angles` is stored the same way as the `angles` keyvalue: `x` is pitch, `y` is yaw
+    /// and `z` is roll, all in degrees.
+    pub fn rotate(&self, angles: Point3D) -> Point3D {
+        apply_matrix(&rotation_matrix(angles), *self)
+    }
+}
+
+impl FromStr for Point3D {
+    type Err = VMFError;
+
+    /// Parses a point value like `"1.0 2.5 -3.0"`, without the surrounding quotes.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        parse_point_from_numbers_str(src)
+            .map_err(|err| VMFError::ParseError(format!("Invalid point: {}", err)))
+    }
+}
+
+impl Add for Point3D {
+    type Output = Point3D;
+
+    fn add(self, rhs: Point3D) -> Point3D {
+        Point3D::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Point3D {
+    type Output = Point3D;
+
+    fn sub(self, rhs: Point3D) -> Point3D {
+        Point3D::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<f32> for Point3D {
+    type Output = Point3D;
+
+    fn mul(self, rhs: f32) -> Point3D {
+        Point3D::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Neg for Point3D {
+    type Output = Point3D;
+
+    fn neg(self) -> Point3D {
+        Point3D::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Builds a rotation matrix from Source engine (pitch, yaw, roll) angles in degrees.
+/// This mirrors `AngleMatrix` from the Source SDK.
+pub(crate) fn rotation_matrix(angles: Point3D) -> [[f32; 3]; 3] {
+    let (sp, cp) = angles.x.to_radians().sin_cos();
+    let (sy, cy) = angles.y.to_radians().sin_cos();
+    let (sr, cr) = angles.z.to_radians().sin_cos();
+
+    [
+        [cp * cy, sr * sp * cy - cr * sy, cr * sp * cy + sr * sy],
+        [cp * sy, sr * sp * sy + cr * cy, cr * sp * sy - sr * cy],
+        [-sp, sr * cp, cr * cp],
+    ]
+}
+
+/// Multiplies `point` by a row-major 3x3 `matrix`.
+pub(crate) fn apply_matrix(matrix: &[[f32; 3]; 3], point: Point3D) -> Point3D {
+    Point3D {
+        x: matrix[0][0] * point.x + matrix[0][1] * point.y + matrix[0][2] * point.z,
+        y: matrix[1][0] * point.x + matrix[1][1] * point.y + matrix[1][2] * point.z,
+        z: matrix[2][0] * point.x + matrix[2][1] * point.y + matrix[2][2] * point.z,
+    }
+}
+
 /// Parses a key-value pair where the value is a Point3D
 pub(crate) fn key_value_point3d<'src, I>(
     key: &'src str,
@@ -26,8 +160,11 @@ where
 }
 
 /// Helper to parse a string segment like "1.0 2.5 -3.0" into a [`Point3D`]
+/// Components may also be separated by commas ("1.0, 2.5, -3.0"), as written by some exporters.
 pub(crate) fn parse_point_from_numbers_str(numbers_str: &str) -> Result<Point3D, String> {
-    let mut parts = numbers_str.split_whitespace();
+    let mut parts = numbers_str
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty());
 
     if let (Some(x), Some(y), Some(z)) = (parts.next(), parts.next(), parts.next()) {
         let x = x
@@ -45,6 +182,15 @@ pub(crate) fn parse_point_from_numbers_str(numbers_str: &str) -> Result<Point3D,
     }
 }
 
+/// Formats a plane as a `plane` value: `(x y z) (x y z) (x y z)`.
+///
+/// Numbers use the shortest form that reads back as the same value, so this is the inverse
+/// of parsing the value.
+pub fn plane_to_vmf_string((p1, p2, p3): (Point3D, Point3D, Point3D)) -> String {
+    let point = |p: Point3D| format!("({} {} {})", p.x, p.y, p.z);
+    format!("{} {} {}", point(p1), point(p2), point(p3))
+}
+
 /// Parses a "plane" to get tuple of three [`Point3D`]
 /// Format for this is: "key" "(p1x p1y p1z) (p2x p2y p2z) (p3x p3y p3z)"
 pub(crate) fn key_value_plane<'src, I>(
@@ -59,7 +205,7 @@ where
             let mut points = [Point3D::default(); 3];
             let mut remainder = plane_value_str.trim();
 
-            for i in 0..3 {
+            for (i, point) in points.iter_mut().enumerate() {
                 // Find the opening parenthesis
                 if let Some(open_idx) = remainder.find('(') {
                     remainder = &remainder[open_idx + 1..];
@@ -76,7 +222,7 @@ where
                     remainder = &remainder[close_idx + 1..];
 
                     match parse_point_from_numbers_str(numbers_part) {
-                        Ok(point) => points[i] = point,
+                        Ok(parsed) => *point = parsed,
                         Err(err_msg) => {
                             return Err(Rich::custom(
                                 span,
@@ -108,7 +254,9 @@ mod tests {
     use chumsky::Parser as _;
 
     use crate::{
-        types::point::{key_value_plane, Point3D},
+        types::point::{
+            key_value_plane, parse_point_from_numbers_str, plane_to_vmf_string, Point3D,
+        },
         util::lex,
     };
 
@@ -147,6 +295,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_plane_to_vmf_string_round_trip() {
+        let values = [
+            "(0 0 0) (0 128 0) (128 128 0)",
+            "(-512 -256 64) (-512 256 64) (512 256 -64)",
+            "(1 -2 3) (-4 5 -6) (7 -8 9)",
+        ];
+        for value in values {
+            let src = format!(r#""plane" "{}""#, value);
+            let plane = key_value_plane("plane").parse(lex(&src)).unwrap();
+            assert_eq!(plane_to_vmf_string(plane), value);
+        }
+    }
+
     #[test]
     fn test_parse_plane_malformed_numbers() {
         let stream = lex(r#""test_plane" "(1.0 2.0 oops) (4.0 5.0 6.0) (7.0 8.0 9.0)""#);
@@ -163,6 +325,64 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_point_comma_separated() {
+        let spaces = parse_point_from_numbers_str("0 0 64").unwrap();
+        assert_eq!(parse_point_from_numbers_str("0, 0, 64").unwrap(), spaces);
+        assert_eq!(parse_point_from_numbers_str("0,0,64").unwrap(), spaces);
+        assert_eq!(spaces, Point3D::new(0.0, 0.0, 64.0));
+        assert!(parse_point_from_numbers_str("0,,").is_err());
+    }
+
+    fn assert_close(a: Point3D, b: Point3D) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point3D::new(1.0, 2.0, 3.0);
+        let b = Point3D::new(4.0, 5.0, 6.0);
+        assert_eq!(a + b, Point3D::new(5.0, 7.0, 9.0));
+        assert_eq!(b - a, Point3D::new(3.0, 3.0, 3.0));
+        assert_eq!(a * 2.0, Point3D::new(2.0, 4.0, 6.0));
+        assert_eq!(-a, Point3D::new(-1.0, -2.0, -3.0));
+        assert_eq!(a.dot(b), 32.0);
+        assert_eq!(
+            Point3D::new(1.0, 0.0, 0.0).cross(Point3D::new(0.0, 1.0, 0.0)),
+            Point3D::new(0.0, 0.0, 1.0)
+        );
+        assert!(Point3D::default().normalized().is_none());
+        assert_eq!(
+            a.min(Point3D::new(0.0, 5.0, 3.0)),
+            Point3D::new(0.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            a.max(Point3D::new(0.0, 5.0, 3.0)),
+            Point3D::new(1.0, 5.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_point_rotate() {
+        let forward = Point3D::new(1.0, 0.0, 0.0);
+
+        // Yaw turns +X towards +Y
+        assert_close(
+            forward.rotate(Point3D::new(0.0, 90.0, 0.0)),
+            Point3D::new(0.0, 1.0, 0.0),
+        );
+        // Positive pitch looks down
+        assert_close(
+            forward.rotate(Point3D::new(90.0, 0.0, 0.0)),
+            Point3D::new(0.0, 0.0, -1.0),
+        );
+        // Roll turns +Y towards +Z
+        assert_close(
+            Point3D::new(0.0, 1.0, 0.0).rotate(Point3D::new(0.0, 0.0, 90.0)),
+            Point3D::new(0.0, 0.0, 1.0),
+        );
+    }
+
     #[test]
     fn test_parse_plane_too_few_points() {
         let stream = lex(r#""test_plane" "(1.0 2.0 3.0) (4.0 5.0 6.0)""#);
@@ -170,4 +390,14 @@ mod tests {
         let result = parser.parse(stream).into_result();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_point_from_str() {
+        assert_eq!(
+            "1.0 2.5 -3.0".parse::<Point3D>().unwrap(),
+            Point3D::new(1.0, 2.5, -3.0)
+        );
+        assert!("1.0 2.5".parse::<Point3D>().is_err());
+        assert!("1.0 x 2.0".parse::<Point3D>().is_err());
+    }
 }
@@ -0,0 +1,286 @@
+use std::borrow::Cow;
+use std::ops::Index;
+
+use crate::{
+    parser::parse_float,
+    types::{color::parse_color_str, point::parse_point_from_numbers_str},
+    VMFError,
+};
+
+use super::{Color, Point3D};
+
+/// The custom key-value pairs of an [`Entity`](super::Entity) or [`World`](super::World),
+/// the keys without a dedicated field.
+///
+/// Pairs are kept in the order they appear in the source, so a written map lists them
+/// like the original file. Keys are unique and case-sensitive: inserting an existing key
+/// replaces its value in place. Lookups scan the pairs, which is fast for the handful of
+/// keys an entity has.
+///
+/// Two maps are equal if they hold the same pairs, in any order.
+#[derive(Debug, Default, Clone)]
+pub struct Properties<'src> {
+    pairs: Vec<(Cow<'src, str>, Cow<'src, str>)>,
+}
+
+impl<'src> Properties<'src> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of pairs in the map.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if the map holds no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns the value of `key`, or `None` if it isn't set.
+    pub fn get(&self, key: &str) -> Option<&Cow<'src, str>> {
+        self.pairs.iter().find(|(k, _)| k.as_ref() == key).map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if it isn't set.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Cow<'src, str>> {
+        self.pairs
+            .iter_mut()
+            .find(|(k, _)| k.as_ref() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns `true` if `key` is set.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Sets `key` to `value` and returns the previous value. A new key is appended, an
+    /// existing one keeps its position.
+    pub fn insert(
+        &mut self,
+        key: Cow<'src, str>,
+        value: Cow<'src, str>,
+    ) -> Option<Cow<'src, str>> {
+        match self.get_mut(&key) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                self.pairs.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes `key` and returns its value, keeping the order of the other pairs.
+    pub fn remove(&mut self, key: &str) -> Option<Cow<'src, str>> {
+        let index = self.pairs.iter().position(|(k, _)| k.as_ref() == key)?;
+        Some(self.pairs.remove(index).1)
+    }
+
+    /// Iterates over the pairs in source order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Cow<'src, str>, &Cow<'src, str>)> {
+        self.pairs.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Iterates over the keys in source order.
+    pub fn keys(&self) -> impl Iterator<Item = &Cow<'src, str>> {
+        self.pairs.iter().map(|(key, _)| key)
+    }
+
+    /// Iterates over the values in source order, matching [`keys`](Self::keys).
+    pub fn values(&self) -> impl Iterator<Item = &Cow<'src, str>> {
+        self.pairs.iter().map(|(_, value)| value)
+    }
+}
+
+impl PartialEq for Properties<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<'src> Index<&str> for Properties<'src> {
+    type Output = Cow<'src, str>;
+
+    /// Returns the value of `key`, panicking if it isn't set.
+    fn index(&self, key: &str) -> &Cow<'src, str> {
+        self.get(key).expect("no property for key")
+    }
+}
+
+impl<'src> FromIterator<(Cow<'src, str>, Cow<'src, str>)> for Properties<'src> {
+    fn from_iter<I: IntoIterator<Item = (Cow<'src, str>, Cow<'src, str>)>>(iter: I) -> Self {
+        let mut properties = Properties::new();
+        properties.extend(iter);
+        properties
+    }
+}
+
+impl<'src> Extend<(Cow<'src, str>, Cow<'src, str>)> for Properties<'src> {
+    fn extend<I: IntoIterator<Item = (Cow<'src, str>, Cow<'src, str>)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'src> IntoIterator for Properties<'src> {
+    type Item = (Cow<'src, str>, Cow<'src, str>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.into_iter()
+    }
+}
+
+/// Typed accessors for the custom [`Properties`] of [`Entity`](super::Entity) and
+/// [`World`](super::World).
+///
+/// Values are parsed on demand. A missing key returns `Ok(None)`, a value that can't be
+/// parsed returns [`VMFError::InvalidProperty`].
+///
+/// usage:
+/// ```ignore
+/// use mnk_vmf::types::PropertiesExt;
+///
+/// let wait = entity.properties.get_f32("wait")?.unwrap_or(1.0);
+/// ```
+pub trait PropertiesExt {
+    /// Returns the raw string value stored under `key`.
+    fn get_str(&self, key: &str) -> Option<&str>;
+
+    /// Parses the value under `key` as an `f32`, integer-written values like `"5"` included.
+    fn get_f32(&self, key: &str) -> Result<Option<f32>, VMFError> {
+        self.get_parsed(key, |value| parse_float(value).map_err(|e| e.to_string()))
+    }
+
+    /// Parses the value under `key` as an `i32`.
+    fn get_i32(&self, key: &str) -> Result<Option<i32>, VMFError> {
+        self.get_parsed(key, |value| value.parse::<i32>().map_err(|e| e.to_string()))
+    }
+
+    /// Parses the value under `key` as a VMF boolean (`"1"` or `"0"`).
+    fn get_bool(&self, key: &str) -> Result<Option<bool>, VMFError> {
+        self.get_parsed(key, |value| match value {
+            "1" => Ok(true),
+            "0" => Ok(false),
+            _ => Err("expected \"0\" or \"1\"".to_string()),
+        })
+    }
+
+    /// Parses the value under `key` as a `"x y z"` vector.
+    fn get_vec3(&self, key: &str) -> Result<Option<Point3D>, VMFError> {
+        self.get_parsed(key, parse_point_from_numbers_str)
+    }
+
+    /// Parses the value under `key` as an `"r g b"` color.
+    fn get_color(&self, key: &str) -> Result<Option<Color>, VMFError> {
+        self.get_parsed(key, parse_color_str)
+    }
+
+    /// Parses the value under `key` with `parse`, wrapping failures into a [`VMFError`].
+    fn get_parsed<T>(
+        &self,
+        key: &str,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, VMFError> {
+        let Some(value) = self.get_str(key) else {
+            return Ok(None);
+        };
+
+        parse(value.trim())
+            .map(Some)
+            .map_err(|reason| VMFError::InvalidProperty {
+                key: key.to_string(),
+                value: value.to_string(),
+                reason,
+            })
+    }
+}
+
+impl PropertiesExt for Properties<'_> {
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).map(|value| value.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::Entity, util::lex, Parser};
+
+    fn entity() -> Entity<'static> {
+        Entity::parse(lex(r#"
+        entity
+        {
+            "id" "1"
+            "classname" "func_door"
+            "speed" "100.5"
+            "wait" "5"
+            "lip" "-4"
+            "locked" "1"
+            "movedir" "0 90 0"
+            "broken" "fast"
+        }
+        "#))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_f32() {
+        let entity = entity();
+        assert_eq!(entity.properties.get_f32("speed").unwrap(), Some(100.5));
+        assert_eq!(entity.properties.get_f32("wait").unwrap(), Some(5.0));
+        assert_eq!(entity.properties.get_f32("missing").unwrap(), None);
+        assert!(entity.properties.get_f32("broken").is_err());
+    }
+
+    #[test]
+    fn test_get_i32() {
+        let entity = entity();
+        assert_eq!(entity.properties.get_i32("lip").unwrap(), Some(-4));
+        assert!(entity.properties.get_i32("speed").is_err());
+    }
+
+    #[test]
+    fn test_get_bool() {
+        let entity = entity();
+        assert_eq!(entity.properties.get_bool("locked").unwrap(), Some(true));
+        assert!(entity.properties.get_bool("lip").is_err());
+    }
+
+    #[test]
+    fn test_get_vec3() {
+        let entity = entity();
+        assert_eq!(
+            entity.properties.get_vec3("movedir").unwrap(),
+            Some(Point3D::new(0.0, 90.0, 0.0))
+        );
+
+        let err = entity.properties.get_vec3("broken").unwrap_err();
+        assert!(matches!(err, VMFError::InvalidProperty { ref key, .. } if key == "broken"));
+        assert!(err.to_string().contains("fast"));
+    }
+
+    #[test]
+    fn test_insert_keeps_order() {
+        let mut properties = Properties::new();
+        properties.insert("b".into(), "1".into());
+        properties.insert("a".into(), "2".into());
+        assert_eq!(properties.insert("b".into(), "3".into()), Some("1".into()));
+        properties.insert("c".into(), "4".into());
+        assert_eq!(properties.remove("a"), Some("2".into()));
+
+        let pairs: Vec<_> = properties.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect();
+        assert_eq!(pairs, [("b", "3"), ("c", "4")]);
+
+        // Equality ignores the order
+        let reversed: Properties = properties.clone().into_iter().rev().collect();
+        assert_eq!(reversed, properties);
+    }
+}
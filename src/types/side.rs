@@ -1,25 +1,30 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
 use chumsky::IterParser;
 use chumsky::Parser as ChumskyParser;
 
 use crate::impl_block_properties_parser;
 use crate::parser::{
-    close_block, key_value, key_value_numeric, open_block, skip_unknown_block, InternalParser,
-    TokenError, TokenSource,
+    close_block, key_value, key_value_float, key_value_numeric, open_block, skip_unknown_block,
+    util::lex, InternalParser, TokenError, TokenSource, VmfBlock,
 };
 use crate::types::point::key_value_plane;
 use crate::types::textureaxis::key_value_texture_axis;
-use crate::Parser;
+use crate::{Parser, VMFError};
 
+use super::geometry::Plane;
+use super::owned_str;
 use super::point::Point3D;
 use super::textureaxis::TextureAxis;
 use super::DispInfo;
 
 /// Represents a side (face) of a solid brush
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Side<'src> {
     pub id: u32,
     pub plane: (Point3D, Point3D, Point3D),
-    pub material: &'src str,
+    pub material: Cow<'src, str>,
     pub uaxis: TextureAxis,
     pub vaxis: TextureAxis,
     pub rotation: f32,
@@ -28,6 +33,61 @@ pub struct Side<'src> {
     pub dispinfo: Option<DispInfo>, // Displacement information for terrain
 }
 
+impl Side<'_> {
+    /// Converts this side into one that owns all of its data.
+    pub fn into_owned(self) -> Side<'static> {
+        Side {
+            material: owned_str(self.material),
+            ..self
+        }
+    }
+
+    /// Moves the side by `offset` without the texture sliding across it, like Hammer does
+    /// with texture lock enabled.
+    ///
+    /// The texture follows the face by subtracting the offset's projection onto each
+    /// texture axis, in texels, from the axis shift. The shift is not wrapped to the texture
+    /// size, since that isn't known here.
+    pub fn translate_with_texture_lock(&mut self, offset: Point3D) {
+        let (p1, p2, p3) = self.plane;
+        self.plane = (p1 + offset, p2 + offset, p3 + offset);
+
+        for axis in [&mut self.uaxis, &mut self.vaxis] {
+            if axis.scale != 0.0 {
+                axis.shift -= offset.dot(axis.direction()) / axis.scale;
+            }
+        }
+
+        if let Some(dispinfo) = &mut self.dispinfo {
+            dispinfo.start_position = dispinfo.start_position + offset;
+        }
+    }
+
+    /// The plane this side lies on, `None` if its three points are collinear.
+    pub fn plane_equation(&self) -> Option<Plane> {
+        let (p1, p2, p3) = self.plane;
+        Plane::from_points(p1, p2, p3)
+    }
+
+    /// Returns `true` if the three plane points are collinear or coincident.
+    ///
+    /// Such a side has no normal, so it can't bound the brush and breaks any geometry built
+    /// from it.
+    pub fn is_degenerate(&self) -> bool {
+        let (p1, p2, p3) = self.plane;
+        // Twice the area of the triangle through the points
+        (p1 - p2).cross(p3 - p2).length() < 0.01
+    }
+
+    /// Returns `true` if the side uses one of the `tools/` materials, like `tools/toolsnodraw`
+    /// or `tools/toolsskip`. The prefix is matched case-insensitively.
+    pub fn is_tool_texture(&self) -> bool {
+        self.material
+            .get(..6)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("tools/"))
+    }
+}
+
 /// Side properties used for parser impl
 enum SideProperty<'src> {
     Id(u32),
@@ -44,6 +104,21 @@ enum SideProperty<'src> {
 /// Public parser trait implementation that allows [`Side`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Side<'src> {}
 
+impl VmfBlock for Side<'_> {
+    const BLOCK_NAME: &'static str = "side";
+}
+
+impl FromStr for Side<'static> {
+    type Err = VMFError;
+
+    /// Parses a single `side { ... }` block.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Side::parse(lex(src))
+            .map(Side::into_owned)
+            .map_err(VMFError::from)
+    }
+}
+
 /// A [`Side`] implementation for [`Side`].
 /// Every key-value pair needs to be in order, like in the example bellow.
 ///
@@ -73,7 +148,7 @@ impl<'src> InternalParser<'src> for Side<'src> {
                 p_material            = key_value("material")                   => SideProperty::Material,
                 p_uaxis               = key_value_texture_axis("uaxis")         => SideProperty::UAxis,
                 p_vaxis               = key_value_texture_axis("vaxis")         => SideProperty::VAxis,
-                p_rotation            = key_value_numeric("rotation")           => SideProperty::Rotation,
+                p_rotation            = key_value_float("rotation")             => SideProperty::Rotation,
                 p_lightmap_scale      = key_value_numeric("lightmapscale")      => SideProperty::LightmapScale,
                 p_smoothing_groups    = key_value_numeric("smoothing_groups")   => SideProperty::SmoothingGroups,
             }
@@ -83,9 +158,9 @@ impl<'src> InternalParser<'src> for Side<'src> {
         let any_property_or_block = property_list
             .or(dispinfo_parser)
             .map(Some)
-            .or(skip_unknown_block().map(|_| None));
+            .or(skip_unknown_block(&["dispinfo"]).map(|_| None));
 
-        open_block("side")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(
                 any_property_or_block
                     .repeated()
@@ -94,19 +169,17 @@ impl<'src> InternalParser<'src> for Side<'src> {
             .then_ignore(close_block())
             .map(|properties: Vec<Option<SideProperty>>| {
                 let mut side = Side::default();
-                for prop_opt in properties {
-                    if let Some(prop) = prop_opt {
-                        match prop {
-                            SideProperty::Id(val) => side.id = val,
-                            SideProperty::Plane(val) => side.plane = val,
-                            SideProperty::Material(val) => side.material = val,
-                            SideProperty::UAxis(val) => side.uaxis = val,
-                            SideProperty::VAxis(val) => side.vaxis = val,
-                            SideProperty::Rotation(val) => side.rotation = val,
-                            SideProperty::LightmapScale(val) => side.lightmapscale = val,
-                            SideProperty::SmoothingGroups(val) => side.smoothing_groups = val,
-                            SideProperty::DispInfo(val) => side.dispinfo = Some(val),
-                        }
+                for prop in properties.into_iter().flatten() {
+                    match prop {
+                        SideProperty::Id(val) => side.id = val,
+                        SideProperty::Plane(val) => side.plane = val,
+                        SideProperty::Material(val) => side.material = val.into(),
+                        SideProperty::UAxis(val) => side.uaxis = val,
+                        SideProperty::VAxis(val) => side.vaxis = val,
+                        SideProperty::Rotation(val) => side.rotation = val,
+                        SideProperty::LightmapScale(val) => side.lightmapscale = val,
+                        SideProperty::SmoothingGroups(val) => side.smoothing_groups = val,
+                        SideProperty::DispInfo(val) => side.dispinfo = Some(val),
                     }
                 }
                 side
@@ -121,6 +194,60 @@ mod tests {
     use super::*;
     use chumsky::Parser as ChumskyParser;
 
+    #[test]
+    fn test_translate_with_texture_lock() {
+        let mut side = Side::parse(lex(r#"
+        side
+        {
+            "id" "1"
+            "plane" "(-64 64 64) (64 64 64) (64 -64 64)"
+            "material" "DEV/DEV_MEASUREGENERIC01B"
+            "uaxis" "[1 0 0 8] 0.25"
+            "vaxis" "[0 -1 0 0] 0.25"
+        }
+        "#))
+        .unwrap();
+        let corner = side.plane.0;
+        let texel = |side: &Side, p| (side.uaxis.texel(p), side.vaxis.texel(p));
+        let before = texel(&side, corner);
+
+        // 16 units along the u axis is 64 texels at scale 0.25
+        let offset = Point3D::new(16.0, 0.0, 0.0);
+        side.translate_with_texture_lock(offset);
+
+        assert_eq!(side.uaxis.shift, 8.0 - 64.0);
+        assert_eq!(side.vaxis.shift, 0.0);
+        assert_eq!(side.plane.0, corner + offset);
+        assert_eq!(texel(&side, corner + offset), before);
+    }
+
+    #[test]
+    fn test_side_is_degenerate() {
+        let side = |plane: &str| {
+            let src = format!(r#"side {{ "id" "1" "plane" "{}" }}"#, plane);
+            Side::parse(lex(&src)).unwrap().is_degenerate()
+        };
+
+        assert!(!side("(-64 64 64) (64 64 64) (64 -64 64)"));
+        assert!(side("(0 0 0) (64 0 0) (128 0 0)"));
+        assert!(side("(0 0 64) (0 0 64) (64 0 64)"));
+        assert!(side("(0 0 0) (0 0 0) (0 0 0)"));
+    }
+
+    #[test]
+    fn test_side_is_tool_texture() {
+        let side = |material: &str| {
+            let src = format!(r#"side {{ "id" "1" "material" "{}" }}"#, material);
+            Side::parse(lex(&src)).unwrap().is_tool_texture()
+        };
+
+        assert!(side("TOOLS/TOOLSNODRAW"));
+        assert!(side("tools/toolsskip"));
+        assert!(!side("BRICK/BRICKFLOOR001A"));
+        assert!(!side("TOOLS"));
+        assert!(!side(""));
+    }
+
     #[test]
     fn test_parse_side_complete_valid_order() {
         let input = r#"
@@ -447,4 +574,25 @@ mod tests {
             "Parsing should fail on unknown property if not explicitly skipped"
         );
     }
+
+    #[test]
+    fn test_side_from_str() {
+        let side: Side = r#"
+        side
+        {
+            "id" "7"
+            "plane" "(-64 64 64) (64 64 64) (64 -64 64)"
+            "material" "BRICK/BRICKWALL001A"
+            "uaxis" "[1 0 0 0] 0.25"
+            "vaxis" "[0 -1 0 0] 0.25"
+        }
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(side.id, 7);
+        assert_eq!(side.material, "BRICK/BRICKWALL001A");
+
+        let err = r#"side { "id" "seven" }"#.parse::<Side>().unwrap_err();
+        assert!(matches!(err, VMFError::ParseError(_)));
+    }
 }
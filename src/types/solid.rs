@@ -1,21 +1,202 @@
 use chumsky::{IterParser, Parser as ChumskyParser};
+use std::str::FromStr;
 
 use crate::{
     impl_block_properties_parser,
-    parser::{close_block, key_value_numeric, open_block, InternalParser, TokenError, TokenSource},
-    types::{EditorData, Side},
-    Parser,
+    parser::{
+        close_block, key_value_numeric, open_block, util::lex, InternalParser, TokenError,
+        TokenSource, VmfBlock,
+    },
+    types::{
+        geometry::{bounds_of, clip_polygon, plane_points, polygon_area, BOGUS_RANGE, ON_EPSILON},
+        EditorData, Plane, Point3D, Side, TextureAxis,
+    },
+    Parser, VMFError,
 };
 
 /// Represents a solid brush in the VMF file
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Solid<'src> {
     pub id: u32,
     pub sides: Vec<Side<'src>>,
     pub editor: Option<EditorData<'src>>,
 }
 
+impl<'src> Solid<'src> {
+    /// Converts this solid into one that owns all of its data.
+    pub fn into_owned(self) -> Solid<'static> {
+        Solid {
+            id: self.id,
+            sides: self.sides.into_iter().map(Side::into_owned).collect(),
+            editor: self.editor.map(EditorData::into_owned),
+        }
+    }
+
+    /// Reconstructs the polygon of every side, in the same order as [`sides`](Self::sides).
+    ///
+    /// Each side's plane is clipped by the planes of all other sides. A side that doesn't
+    /// touch the brush, or whose plane is invalid, gets an empty polygon.
+    pub fn face_polygons(&self) -> Vec<Vec<Point3D>> {
+        let planes: Vec<_> = self.sides.iter().map(Side::plane_equation).collect();
+
+        planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| {
+                let Some(plane) = plane else {
+                    return Vec::new();
+                };
+                let mut polygon = plane.base_winding();
+                for (j, other) in planes.iter().enumerate() {
+                    if let Some(other) = other.as_ref().filter(|_| i != j) {
+                        polygon = clip_polygon(&polygon, other);
+                        if polygon.len() < 3 {
+                            return Vec::new();
+                        }
+                    }
+                }
+                polygon
+            })
+            .collect()
+    }
+
+    /// Axis aligned bounding box `(mins, maxs)` of the brush, built from its face polygons.
+    ///
+    /// Returns `None` if no side forms a face.
+    pub fn bounds(&self) -> Option<(Point3D, Point3D)> {
+        bounds_of(self.face_polygons().into_iter().flatten())
+    }
+
+    /// Returns `true` if every side forms a face of the brush.
+    ///
+    /// A brush is the intersection of its sides' half-spaces, so it is always convex. Sides
+    /// that end up with no face, because they were meant to carve a concave shape or their
+    /// plane is flipped or degenerate, make the brush invalid and fail to compile.
+    pub fn is_convex(&self) -> bool {
+        !self.sides.is_empty()
+            && self
+                .face_polygons()
+                .iter()
+                .all(|polygon| polygon_area(polygon) > 0.01)
+    }
+
+    /// Splits the solid by the plane through `plane_point` facing along `normal`.
+    ///
+    /// Returns `(front, back)`: the part on the side `normal` points to and the part behind
+    /// the plane. If the plane doesn't cut the solid, the whole solid is returned on the side
+    /// it lies on and the other half is `None`. A zero `normal` counts as not cutting, and
+    /// the solid is returned as the front half.
+    ///
+    /// Face accounting: each half keeps the original sides that still have some area on
+    /// its side of the plane, and gains one cap side on the plane itself. Cutting a cube
+    /// through the middle gives two halves of 5 + 1 sides each, while cutting off a corner
+    /// gives a 3 + 1 sided corner and a 6 + 1 sided remainder.
+    ///
+    /// Both halves keep the solid's id and editor data. Cap sides get id 0, the material
+    /// `TOOLS/TOOLSNODRAW` and world aligned texture axes, so ids need to be reassigned
+    /// before the halves are written out together.
+    pub fn split(&self, plane_point: Point3D, normal: Point3D) -> (Option<Self>, Option<Self>) {
+        let Some(normal) = normal.normalized() else {
+            return (Some(self.clone()), None);
+        };
+        let front_cap = Plane {
+            normal: -normal,
+            dist: -normal.dot(plane_point),
+        };
+        let back_cap = Plane {
+            normal,
+            dist: normal.dot(plane_point),
+        };
+
+        let polygons = self.face_polygons();
+        let vertices = || polygons.iter().flatten();
+        if !vertices().any(|&v| back_cap.distance_to(v) < -ON_EPSILON) {
+            return (Some(self.clone()), None);
+        }
+        if !vertices().any(|&v| back_cap.distance_to(v) > ON_EPSILON) {
+            return (None, Some(self.clone()));
+        }
+
+        let half = |cap: Plane| {
+            let mut sides: Vec<_> = self
+                .sides
+                .iter()
+                .zip(&polygons)
+                .filter(|(_, polygon)| polygon.iter().any(|&v| cap.distance_to(v) < -ON_EPSILON))
+                .map(|(side, _)| side.clone())
+                .collect();
+
+            let mut cap_polygon = cap.base_winding();
+            for side_plane in self.sides.iter().filter_map(Side::plane_equation) {
+                cap_polygon = clip_polygon(&cap_polygon, &side_plane);
+            }
+            if let Some(plane) = plane_points(&cap_polygon, cap.normal) {
+                let (uaxis, vaxis) = TextureAxis::world_aligned(cap.normal);
+                sides.push(Side {
+                    id: 0,
+                    plane,
+                    material: "TOOLS/TOOLSNODRAW".into(),
+                    uaxis,
+                    vaxis,
+                    lightmapscale: 16,
+                    ..Default::default()
+                });
+            }
+
+            Solid {
+                id: self.id,
+                sides,
+                editor: self.editor.clone(),
+            }
+        };
+
+        (Some(half(front_cap)), Some(half(back_cap)))
+    }
+
+    /// Returns `true` if the sides enclose a finite volume, i.e. every face is bounded
+    /// by the other sides.
+    pub fn is_closed(&self) -> bool {
+        let limit = BOGUS_RANGE * 0.5;
+        let polygons = self.face_polygons();
+        let faces = polygons
+            .iter()
+            .filter(|polygon| !polygon.is_empty())
+            .count();
+
+        faces >= 4
+            && polygons.iter().flatten().all(|point| {
+                point.x.abs() < limit && point.y.abs() < limit && point.z.abs() < limit
+            })
+    }
+
+    /// Total area of the brush's faces.
+    pub fn surface_area(&self) -> f32 {
+        self.face_polygons()
+            .iter()
+            .map(|polygon| polygon_area(polygon))
+            .sum()
+    }
+
+    /// Volume enclosed by the brush.
+    ///
+    /// Sums the pyramids from the origin to every face (divergence theorem), so it is only
+    /// meaningful for a closed brush, see [`is_closed`](Self::is_closed).
+    pub fn volume(&self) -> f32 {
+        let volume: f32 = self
+            .sides
+            .iter()
+            .zip(self.face_polygons())
+            .filter_map(|(side, polygon)| {
+                let plane = side.plane_equation()?;
+                Some(polygon_area(&polygon) * plane.dist / 3.0)
+            })
+            .sum();
+        volume.abs()
+    }
+}
+
 /// Internal [`Solid`] Properties to be used in a parser impl
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 enum SolidProperty<'src> {
     Id(u32),
@@ -26,6 +207,21 @@ enum SolidProperty<'src> {
 /// Public parser trait implementation that allows [`Solid`] to use ::parse(input) call.
 impl<'src> Parser<'src> for Solid<'src> {}
 
+impl VmfBlock for Solid<'_> {
+    const BLOCK_NAME: &'static str = "solid";
+}
+
+impl FromStr for Solid<'static> {
+    type Err = VMFError;
+
+    /// Parses a single `solid { ... }` block, like the text Hammer puts on the clipboard.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Solid::parse(lex(src))
+            .map(Solid::into_owned)
+            .map_err(VMFError::from)
+    }
+}
+
 /// A [`InternalParser`] implementation for [`Solid`].
 ///
 /// usage: `let solid = Solid::parser().parse(input);`.
@@ -76,7 +272,7 @@ impl<'src> InternalParser<'src> for Solid<'src> {
         // Combine all parsers
         let any_property = property_list.or(side_parser).or(editor_parser);
 
-        open_block("solid")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(any_property.repeated().collect::<Vec<SolidProperty>>())
             .then_ignore(close_block())
             .map(|properties: Vec<SolidProperty>| {
@@ -97,8 +293,192 @@ impl<'src> InternalParser<'src> for Solid<'src> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Plane;
     use crate::util::lex;
 
+    /// A side on the plane `normal · p = dist`, facing along `normal`.
+    fn side_facing(id: u32, normal: Point3D, dist: f32) -> Side<'static> {
+        let winding = Plane { normal, dist }.base_winding();
+        let (mut p1, p2, mut p3) = (winding[0], winding[1], winding[2]);
+        if Plane::from_points(p1, p2, p3).unwrap().normal.dot(normal) < 0.0 {
+            std::mem::swap(&mut p1, &mut p3);
+        }
+        Side {
+            id,
+            plane: (p1, p2, p3),
+            ..Default::default()
+        }
+    }
+
+    fn solid_from(planes: &[(Point3D, f32)]) -> Solid<'static> {
+        Solid {
+            id: 1,
+            sides: planes
+                .iter()
+                .enumerate()
+                .map(|(i, &(normal, dist))| side_facing(i as u32, normal, dist))
+                .collect(),
+            editor: None,
+        }
+    }
+
+    const X: Point3D = Point3D {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    const Y: Point3D = Point3D {
+        x: 0.0,
+        y: 1.0,
+        z: 0.0,
+    };
+    const Z: Point3D = Point3D {
+        x: 0.0,
+        y: 0.0,
+        z: 1.0,
+    };
+
+    #[test]
+    fn test_solid_cube_is_valid() {
+        let cube = cube();
+
+        assert!(cube.is_convex());
+        assert!(cube.is_closed());
+        for polygon in cube.face_polygons() {
+            assert_eq!(polygon.len(), 4);
+            assert_eq!(polygon_area(&polygon), 128.0 * 128.0);
+        }
+        assert_eq!(
+            cube.bounds(),
+            Some((
+                Point3D::new(-64.0, -64.0, -64.0),
+                Point3D::new(64.0, 64.0, 64.0)
+            ))
+        );
+        assert_eq!(Solid::default().bounds(), None);
+    }
+
+    #[test]
+    fn test_solid_volume_and_surface_area() {
+        let unit = solid_from(&[
+            (X, 1.0),
+            (-X, 0.0),
+            (Y, 1.0),
+            (-Y, 0.0),
+            (Z, 1.0),
+            (-Z, 0.0),
+        ]);
+        assert!((unit.volume() - 1.0).abs() < 1e-4);
+        assert!((unit.surface_area() - 6.0).abs() < 1e-4);
+
+        let cube = cube();
+        assert_eq!(cube.volume(), 128.0 * 128.0 * 128.0);
+        assert_eq!(cube.surface_area(), 6.0 * 128.0 * 128.0);
+        assert_eq!(Solid::default().volume(), 0.0);
+    }
+
+    #[test]
+    fn test_solid_open_and_concave() {
+        // Cube without its top
+        let open = solid_from(&[(X, 64.0), (-X, 64.0), (Y, 64.0), (-Y, 64.0), (-Z, 64.0)]);
+        assert!(open.is_convex());
+        assert!(!open.is_closed());
+
+        // L shape: the outer sides of each arm are cut away by the other arm's inner sides
+        let concave = solid_from(&[
+            (-X, 0.0),
+            (-Y, 0.0),
+            (X, 128.0),
+            (Y, 64.0),
+            (X, 64.0),
+            (Y, 128.0),
+            (Z, 64.0),
+            (-Z, 0.0),
+        ]);
+        assert!(!concave.is_convex());
+        assert!(concave.is_closed());
+
+        // A side facing into the brush leaves nothing
+        let flipped = solid_from(&[
+            (X, 64.0),
+            (X, -64.0),
+            (Y, 64.0),
+            (-Y, 64.0),
+            (Z, 64.0),
+            (-Z, 64.0),
+        ]);
+        assert!(!flipped.is_convex());
+        assert!(!flipped.is_closed());
+    }
+
+    fn cube() -> Solid<'static> {
+        solid_from(&[
+            (X, 64.0),
+            (-X, 64.0),
+            (Y, 64.0),
+            (-Y, 64.0),
+            (Z, 64.0),
+            (-Z, 64.0),
+        ])
+    }
+
+    #[test]
+    fn test_solid_split_in_half() {
+        let (front, back) = cube().split(Point3D::default(), X);
+        let (front, back) = (front.unwrap(), back.unwrap());
+
+        // 4 cut sides, the uncut side facing away from the plane and the cap
+        assert_eq!(front.sides.len(), 6);
+        assert_eq!(back.sides.len(), 6);
+        for half in [&front, &back] {
+            assert!(half.is_convex());
+            assert!(half.is_closed());
+            let cap = half.sides.last().unwrap();
+            assert_eq!(cap.id, 0);
+            assert_eq!(cap.material, "TOOLS/TOOLSNODRAW");
+        }
+
+        let cap_normal = |solid: &Solid| solid.sides[5].plane_equation().unwrap().normal;
+        assert_eq!(cap_normal(&front), -X);
+        assert_eq!(cap_normal(&back), X);
+        assert!(front.face_polygons().iter().flatten().all(|p| p.x >= 0.0));
+        assert!(back.face_polygons().iter().flatten().all(|p| p.x <= 0.0));
+    }
+
+    #[test]
+    fn test_solid_split_corner() {
+        let (corner, rest) =
+            cube().split(Point3D::new(32.0, 32.0, 32.0), Point3D::new(1.0, 1.0, 1.0));
+        let (corner, rest) = (corner.unwrap(), rest.unwrap());
+
+        assert_eq!(corner.sides.len(), 4);
+        assert_eq!(rest.sides.len(), 7);
+        assert!(corner.is_convex() && corner.is_closed());
+        assert!(rest.is_convex() && rest.is_closed());
+    }
+
+    #[test]
+    fn test_solid_split_misses() {
+        let cube = cube();
+        let (front, back) = cube.split(Point3D::new(100.0, 0.0, 0.0), X);
+        assert!(front.is_none());
+        assert_eq!(back.as_ref(), Some(&cube));
+
+        let (front, back) = cube.split(Point3D::new(-100.0, 0.0, 0.0), X);
+        assert_eq!(front.as_ref(), Some(&cube));
+        assert!(back.is_none());
+    }
+
+    #[test]
+    fn test_solid_geometry_of_test_map() {
+        let vmf: crate::VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+        for solid in doc.solids() {
+            assert!(solid.is_convex(), "solid {} is not convex", solid.id);
+            assert!(solid.is_closed(), "solid {} is not closed", solid.id);
+        }
+    }
+
     #[test]
     fn test_solid_complete() {
         let input = r#"
@@ -340,4 +720,54 @@ mod tests {
             "Parser should fail on missing closing brace"
         );
     }
+
+    #[test]
+    fn test_solid_from_str() {
+        // Copied from Hammer with Ctrl+C
+        let solid: Solid = r#"solid
+{
+	"id" "12"
+	side
+	{
+		"id" "1"
+		"plane" "(-64 64 64) (64 64 64) (64 -64 64)"
+		"material" "TOOLS/TOOLSNODRAW"
+		"uaxis" "[1 0 0 0] 0.25"
+		"vaxis" "[0 -1 0 0] 0.25"
+		"rotation" "0"
+		"lightmapscale" "16"
+		"smoothing_groups" "0"
+	}
+	side
+	{
+		"id" "2"
+		"plane" "(-64 -64 0) (64 -64 0) (64 64 0)"
+		"material" "TOOLS/TOOLSNODRAW"
+		"uaxis" "[1 0 0 0] 0.25"
+		"vaxis" "[0 -1 0 0] 0.25"
+		"rotation" "0"
+		"lightmapscale" "16"
+		"smoothing_groups" "0"
+	}
+	editor
+	{
+		"color" "0 180 0"
+		"visgroupshown" "1"
+		"visgroupautoshown" "1"
+	}
+}
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(solid.id, 12);
+        assert_eq!(solid.sides.len(), 2);
+        assert_eq!(solid.sides[1].id, 2);
+        assert!(solid.sides.iter().all(Side::is_tool_texture));
+        assert!(solid.editor.is_some());
+
+        let err = r#"solid { "id" "12" side { "id" "1" }"#.parse::<Solid>().unwrap_err();
+        assert!(matches!(err, VMFError::ParseError(_)));
+        assert!("side { \"id\" \"1\" }".parse::<Solid>().is_err());
+    }
 }
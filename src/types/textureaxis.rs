@@ -2,6 +2,8 @@ use chumsky::{error::Rich, Parser as ChumskyParser};
 
 use crate::parser::{any_quoted_string, quoted_string, TokenError, TokenSource};
 
+use super::Point3D;
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct TextureAxis {
     pub x: f32,
@@ -11,6 +13,64 @@ pub struct TextureAxis {
     pub scale: f32,
 }
 
+/// Face normal and the (u, v) directions Hammer uses to world-align a texture on it.
+/// This is the `baseaxis` table from the Source SDK.
+const BASE_AXES: [[[f32; 3]; 3]; 6] = [
+    [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0]],
+    [[0.0, 0.0, -1.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0]],
+    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0]],
+    [[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0]],
+    [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]],
+    [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]],
+];
+
+impl TextureAxis {
+    /// The axis direction in world space.
+    pub fn direction(&self) -> Point3D {
+        Point3D::new(self.x, self.y, self.z)
+    }
+
+    /// Texture coordinate (in texels) of a world space `point` along this axis.
+    pub fn texel(&self, point: Point3D) -> f32 {
+        point.dot(self.direction()) / self.scale + self.shift
+    }
+
+    /// Formats the axis as a `uaxis`/`vaxis` value: `[x y z shift] scale`.
+    ///
+    /// Numbers use the shortest form that reads back as the same value, so this is the
+    /// inverse of parsing the value.
+    pub fn to_vmf_string(&self) -> String {
+        format!(
+            "[{} {} {} {}] {}",
+            self.x, self.y, self.z, self.shift, self.scale
+        )
+    }
+
+    /// Returns the world aligned `(uaxis, vaxis)` Hammer gives a new face with `normal`,
+    /// at the default scale of 0.25.
+    pub fn world_aligned(normal: Point3D) -> (TextureAxis, TextureAxis) {
+        let point = |v: [f32; 3]| Point3D::new(v[0], v[1], v[2]);
+        let mut best = &BASE_AXES[0];
+        let mut best_dot = f32::MIN;
+        for axes in &BASE_AXES {
+            let dot = normal.dot(point(axes[0]));
+            if dot > best_dot {
+                best_dot = dot;
+                best = axes;
+            }
+        }
+
+        let axis = |v: [f32; 3]| TextureAxis {
+            x: v[0],
+            y: v[1],
+            z: v[2],
+            shift: 0.0,
+            scale: 0.25,
+        };
+        (axis(best[1]), axis(best[2]))
+    }
+}
+
 /// Helper to parse a string segment like "1.0 0.0 0.0 16.0" into (x, y, z, shift)
 fn parse_texture_vector_str(numbers_str: &str) -> Result<(f32, f32, f32, f32), String> {
     let mut parts = numbers_str.split_whitespace();
@@ -30,9 +90,9 @@ fn parse_texture_vector_str(numbers_str: &str) -> Result<(f32, f32, f32, f32), S
         let shift = shift_str
             .parse::<f32>()
             .map_err(|e| format!("invalid shift '{}': {}", shift_str, e))?;
-        return Ok((x, y, z, shift));
+        Ok((x, y, z, shift))
     } else {
-        return Err("expected multiple of 3 numbers".into());
+        Err("expected multiple of 3 numbers".into())
     }
 }
 
@@ -152,6 +212,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_vmf_string_round_trip() {
+        let values = [
+            "[1 0 0 0] 0.25",
+            "[0 -1 0 128] 0.5",
+            "[0.707107 -0.707107 0 -16.5] 0.125",
+            "[0 0 -1 -0.0078125] 1",
+        ];
+        for value in values {
+            let src = format!(r#""uaxis" "{}""#, value);
+            let axis = key_value_texture_axis("uaxis").parse(lex(&src)).unwrap();
+            assert_eq!(axis.to_vmf_string(), value);
+        }
+    }
+
     #[test]
     fn test_parse_texture_axis_missing_bracket_open() {
         let stream = lex(r#""uaxis" "1 0 0 0] 0.25""#);
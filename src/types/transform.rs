@@ -0,0 +1,58 @@
+use super::point::{apply_matrix, rotation_matrix, Point3D};
+
+/// A rigid transform made of a rotation followed by a translation.
+///
+/// Used to move geometry from an instance's local space into the parent map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Point3D,
+    pub rotation: [[f32; 3]; 3],
+}
+
+impl Transform {
+    /// Creates a transform from a Source engine `origin` and (pitch, yaw, roll) `angles`.
+    pub fn new(origin: Point3D, angles: Point3D) -> Self {
+        Self {
+            translation: origin,
+            rotation: rotation_matrix(angles),
+        }
+    }
+
+    /// Creates a transform that leaves points unchanged.
+    pub fn identity() -> Self {
+        Self::new(Point3D::default(), Point3D::default())
+    }
+
+    /// Rotates `p` and then translates it.
+    pub fn apply(&self, p: Point3D) -> Point3D {
+        apply_matrix(&self.rotation, p) + self.translation
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_transform() {
+        let p = Point3D::new(1.0, 2.0, 3.0);
+        assert_eq!(Transform::identity().apply(p), p);
+    }
+
+    #[test]
+    fn test_transform_matches_point_rotate() {
+        let angles = Point3D::new(30.0, 45.0, 10.0);
+        let origin = Point3D::new(10.0, -20.0, 5.0);
+        let p = Point3D::new(3.0, 4.0, 5.0);
+
+        let expected = p.rotate(angles) + origin;
+        let actual = Transform::new(origin, angles).apply(p);
+        assert!((expected - actual).length() < 1e-4);
+    }
+}
@@ -1,11 +1,16 @@
-use chumsky::Parser as ChumskyParser;
+use chumsky::{error::Rich, Parser as ChumskyParser};
+use std::str::FromStr;
 
-use crate::parser::{
-    close_block, key_value_numeric, open_block, InternalParser, Parser, TokenError, TokenSource,
+use crate::{
+    parser::{
+        any_quoted_string, close_block, key_value_numeric, open_block, quoted_string, util::lex,
+        InternalParser, Parser, TokenError, TokenSource, VmfBlock,
+    },
+    VMFError,
 };
 
 /// `VersionInfo` holds the VMF Header information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct VersionInfo {
     pub editor_version: u32,
     pub editor_build: u32,
@@ -31,11 +36,47 @@ impl VersionInfo {
             prefab,
         }
     }
+
+    /// Returns `true` if the file is a prefab rather than a map.
+    pub fn is_prefab(&self) -> bool {
+        self.prefab != 0
+    }
+}
+
+/// Parses the `prefab` flag. Besides `"0"`/`"1"`, some tools write it as `"false"`/`"true"`.
+fn key_value_prefab<'src, I>() -> impl ChumskyParser<'src, I, u32, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    quoted_string("prefab").ignore_then(any_quoted_string().try_map(|value: &str, span| {
+        if value.eq_ignore_ascii_case("true") {
+            Ok(1)
+        } else if value.eq_ignore_ascii_case("false") {
+            Ok(0)
+        } else {
+            value
+                .parse()
+                .map_err(|_| Rich::custom(span, "invalid prefab flag"))
+        }
+    }))
 }
 
 /// Public parser trait implementation that allows [`VersionInfo`] to use ::parse(input) call.
 impl Parser<'_> for VersionInfo {}
 
+impl VmfBlock for VersionInfo {
+    const BLOCK_NAME: &'static str = "versioninfo";
+}
+
+impl FromStr for VersionInfo {
+    type Err = VMFError;
+
+    /// Parses a whole `versioninfo { ... }` block.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Self::parse(lex(src)).map_err(VMFError::from)
+    }
+}
+
 /// A [`InternalParser`] implementation for [`VersionInfo`].
 /// Every key-value pair needs to be in order, like in the example bellow.
 ///
@@ -55,13 +96,13 @@ impl<'src> InternalParser<'src> for VersionInfo {
     where
         I: TokenSource<'src>,
     {
-        open_block("versioninfo")
+        open_block(Self::BLOCK_NAME)
             .ignored()
             .then(key_value_numeric::<u32, I>("editorversion"))
             .then(key_value_numeric::<u32, I>("editorbuild"))
             .then(key_value_numeric::<u16, I>("mapversion"))
             .then(key_value_numeric::<u16, I>("formatversion"))
-            .then(key_value_numeric::<u32, I>("prefab"))
+            .then(key_value_prefab())
             .map(|(((((_, vi), eb), mv), fv), pf)| VersionInfo::new(vi, eb, mv, fv, pf))
             .then_ignore(close_block())
             .boxed()
@@ -143,4 +184,52 @@ mod tests {
             "Parser should fail on invalid number format"
         );
     }
+
+    #[test]
+    fn test_version_info_from_str() {
+        let version_info: VersionInfo = r#"versioninfo
+        {
+            "editorversion" "400"
+            "editorbuild" "6157"
+            "mapversion" "16"
+            "formatversion" "100"
+            "prefab" "0"
+        }"#
+        .parse()
+        .unwrap();
+        assert_eq!(version_info, VersionInfo::new(400, 6157, 16, 100, 0));
+
+        let err = "versioninfo { }".parse::<VersionInfo>().unwrap_err();
+        assert!(matches!(err, VMFError::ParseError(_)));
+    }
+
+    #[test]
+    fn test_version_info_prefab_flag() {
+        let version_info = |prefab: &str| {
+            format!(
+                r#"versioninfo
+                {{
+                    "editorversion" "400"
+                    "editorbuild" "6157"
+                    "mapversion" "1"
+                    "formatversion" "100"
+                    "prefab" "{}"
+                }}"#,
+                prefab
+            )
+            .parse::<VersionInfo>()
+        };
+
+        let numeric = version_info("1").unwrap();
+        assert_eq!(numeric.prefab, 1);
+        assert!(numeric.is_prefab());
+
+        let text = version_info("true").unwrap();
+        assert_eq!(text.prefab, 1);
+        assert!(text.is_prefab());
+
+        assert!(!version_info("0").unwrap().is_prefab());
+        assert!(!version_info("FALSE").unwrap().is_prefab());
+        assert!(version_info("maybe").is_err());
+    }
 }
@@ -1,32 +1,115 @@
 use chumsky::{IterParser, Parser as ChumskyParser};
+use std::str::FromStr;
 
 use crate::{
     impl_block_properties_parser,
     parser::{
-        close_block, key_value_boolean, key_value_numeric, open_block, InternalParser, TokenError,
-        TokenSource,
+        close_block, key_value_boolean_ci, key_value_numeric_ci, open_block, util::lex,
+        InternalParser, TokenError, TokenSource, VmfBlock,
     },
-    Parser,
+    Parser, VMFError,
 };
 
 /// ViewSettings holds all the parameters for an editor
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct ViewSettings {
-    snap_to_grid: bool,
-    show_grid: bool,
-    show_logical_grid: bool,
-    grid_spacing: u32,
-    show_3d_grid: bool,
-    hide_objects: bool,
-    hide_walls: bool,
-    hide_stripes: bool,
-    hide_neighbors: bool,
-    hide_detail: bool,
-    show_brushes: bool,
-    show_entities: bool,
-    show_light_radius: bool,
-    show_lighting_preview: bool,
-    show_wireframe: bool,
+    pub(crate) snap_to_grid: bool,
+    pub(crate) show_grid: bool,
+    pub(crate) show_logical_grid: bool,
+    pub(crate) grid_spacing: u32,
+    pub(crate) show_3d_grid: bool,
+    pub(crate) hide_objects: bool,
+    pub(crate) hide_walls: bool,
+    pub(crate) hide_stripes: bool,
+    pub(crate) hide_neighbors: bool,
+    pub(crate) hide_detail: bool,
+    pub(crate) show_brushes: bool,
+    pub(crate) show_entities: bool,
+    pub(crate) show_light_radius: bool,
+    pub(crate) show_lighting_preview: bool,
+    pub(crate) show_wireframe: bool,
+}
+
+impl ViewSettings {
+    /// Whether objects snap to the grid (`bSnapToGrid`).
+    pub fn snap_to_grid(&self) -> bool {
+        self.snap_to_grid
+    }
+
+    /// Whether the 2D views draw the grid (`bShowGrid`).
+    pub fn show_grid(&self) -> bool {
+        self.show_grid
+    }
+
+    /// Whether the logical view draws its grid (`bShowLogicalGrid`).
+    pub fn show_logical_grid(&self) -> bool {
+        self.show_logical_grid
+    }
+
+    /// Grid size in units (`nGridSpacing`).
+    pub fn grid_spacing(&self) -> u32 {
+        self.grid_spacing
+    }
+
+    /// Sets the grid size in units.
+    pub fn set_grid_spacing(&mut self, spacing: u32) {
+        self.grid_spacing = spacing;
+    }
+
+    /// Whether the 3D view draws the grid (`bShow3DGrid`).
+    pub fn show_3d_grid(&self) -> bool {
+        self.show_3d_grid
+    }
+
+    /// Value of the `bHideObjects` setting.
+    pub fn hide_objects(&self) -> bool {
+        self.hide_objects
+    }
+
+    /// Value of the `bHideWalls` setting.
+    pub fn hide_walls(&self) -> bool {
+        self.hide_walls
+    }
+
+    /// Value of the `bHideStripes` setting.
+    pub fn hide_stripes(&self) -> bool {
+        self.hide_stripes
+    }
+
+    /// Value of the `bHideNeighbors` setting.
+    pub fn hide_neighbors(&self) -> bool {
+        self.hide_neighbors
+    }
+
+    /// Value of the `bHideDetail` setting.
+    pub fn hide_detail(&self) -> bool {
+        self.hide_detail
+    }
+
+    /// Value of the `bShowBrushes` setting.
+    pub fn show_brushes(&self) -> bool {
+        self.show_brushes
+    }
+
+    /// Value of the `bShowEntities` setting.
+    pub fn show_entities(&self) -> bool {
+        self.show_entities
+    }
+
+    /// Value of the `bShowLightRadius` setting.
+    pub fn show_light_radius(&self) -> bool {
+        self.show_light_radius
+    }
+
+    /// Value of the `bShowLightingPreview` setting.
+    pub fn show_lighting_preview(&self) -> bool {
+        self.show_lighting_preview
+    }
+
+    /// Value of the `bShowWireframe` setting.
+    pub fn show_wireframe(&self) -> bool {
+        self.show_wireframe
+    }
 }
 
 /// Internal [`ViewSettings`] Properties to be used in a parser impl
@@ -52,6 +135,19 @@ enum ViewSettingsProperty {
 /// Public parser trait implementation that allows [`ViewSettings`] to use ::parse(input) call.
 impl Parser<'_> for ViewSettings {}
 
+impl VmfBlock for ViewSettings {
+    const BLOCK_NAME: &'static str = "viewsettings";
+}
+
+impl FromStr for ViewSettings {
+    type Err = VMFError;
+
+    /// Parses a whole `viewsettings { ... }` block.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Self::parse(lex(src)).map_err(VMFError::from)
+    }
+}
+
 /// A [`ViewSettings`] implementation for [`ViewSettings`].
 /// Every key-value pair needs to be in order, like in the example bellow.
 ///
@@ -73,24 +169,24 @@ impl<'src> InternalParser<'src> for ViewSettings {
     {
         impl_block_properties_parser! {
             property_list: ViewSettingsProperty = {
-                p_snap_to_grid        = key_value_boolean("bSnapToGrid")          => ViewSettingsProperty::SnapToGrid,
-                p_show_grid           = key_value_boolean("bShowGrid")            => ViewSettingsProperty::ShowGrid,
-                p_show_logical_grid   = key_value_boolean("bShowLogicalGrid")     => ViewSettingsProperty::ShowLogicalGrid,
-                p_grid_spacing        = key_value_numeric("nGridSpacing")         => ViewSettingsProperty::GridSpacing,
-                p_show_3d_grid        = key_value_boolean("bShow3DGrid")          => ViewSettingsProperty::Show3DGrid,
-                p_hide_objects        = key_value_boolean("bHideObjects")         => ViewSettingsProperty::HideObjects,
-                p_hide_walls          = key_value_boolean("bHideWalls")           => ViewSettingsProperty::HideWalls,
-                p_hide_stripes        = key_value_boolean("bHideStripes")         => ViewSettingsProperty::HideStripes,
-                p_hide_neighbors      = key_value_boolean("bHideNeighbors")       => ViewSettingsProperty::HideNeighbors,
-                p_hide_detail         = key_value_boolean("bHideDetail")          => ViewSettingsProperty::HideDetail,
-                p_show_brushes        = key_value_boolean("bShowBrushes")         => ViewSettingsProperty::ShowBrushes,
-                p_show_entities       = key_value_boolean("bShowEntities")        => ViewSettingsProperty::ShowEntities,
-                p_show_light_radius   = key_value_boolean("bShowLightRadius")     => ViewSettingsProperty::ShowLightRadius,
-                p_show_lighting_preview = key_value_boolean("bShowLightingPreview") => ViewSettingsProperty::ShowLightingPreview,
-                p_show_wireframe      = key_value_boolean("bShowWireframe")       => ViewSettingsProperty::ShowWireframe,
+                p_snap_to_grid        = key_value_boolean_ci("bSnapToGrid")       => ViewSettingsProperty::SnapToGrid,
+                p_show_grid           = key_value_boolean_ci("bShowGrid")         => ViewSettingsProperty::ShowGrid,
+                p_show_logical_grid   = key_value_boolean_ci("bShowLogicalGrid")  => ViewSettingsProperty::ShowLogicalGrid,
+                p_grid_spacing        = key_value_numeric_ci("nGridSpacing")      => ViewSettingsProperty::GridSpacing,
+                p_show_3d_grid        = key_value_boolean_ci("bShow3DGrid")       => ViewSettingsProperty::Show3DGrid,
+                p_hide_objects        = key_value_boolean_ci("bHideObjects")      => ViewSettingsProperty::HideObjects,
+                p_hide_walls          = key_value_boolean_ci("bHideWalls")        => ViewSettingsProperty::HideWalls,
+                p_hide_stripes        = key_value_boolean_ci("bHideStripes")      => ViewSettingsProperty::HideStripes,
+                p_hide_neighbors      = key_value_boolean_ci("bHideNeighbors")    => ViewSettingsProperty::HideNeighbors,
+                p_hide_detail         = key_value_boolean_ci("bHideDetail")       => ViewSettingsProperty::HideDetail,
+                p_show_brushes        = key_value_boolean_ci("bShowBrushes")      => ViewSettingsProperty::ShowBrushes,
+                p_show_entities       = key_value_boolean_ci("bShowEntities")     => ViewSettingsProperty::ShowEntities,
+                p_show_light_radius   = key_value_boolean_ci("bShowLightRadius")  => ViewSettingsProperty::ShowLightRadius,
+                p_show_lighting_preview = key_value_boolean_ci("bShowLightingPreview") => ViewSettingsProperty::ShowLightingPreview,
+                p_show_wireframe      = key_value_boolean_ci("bShowWireframe")    => ViewSettingsProperty::ShowWireframe,
             }
         }
-        open_block("viewsettings")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(
                 property_list
                     .repeated()
@@ -137,7 +233,9 @@ mod tests {
     use super::*;
     use chumsky::error::RichReason;
 
-    fn parse_viewsettings_str(input_str: &str) -> Result<ViewSettings, Vec<RichReason<Token<'_>>>> {
+    fn parse_viewsettings_str(
+        input_str: &str,
+    ) -> Result<ViewSettings, Vec<RichReason<'_, Token<'_>>>> {
         ViewSettings::parse(lex(input_str))
     }
 
@@ -249,6 +347,29 @@ mod tests {
         assert_eq!(settings, expected);
     }
 
+    #[test]
+    fn test_viewsettings_lowercased_keys() {
+        let input = r#"
+        viewsettings
+        {
+            "bsnaptogrid" "1"
+            "bshowgrid" "1"
+            "ngridspacing" "32"
+            "BSHOWWIREFRAME" "1"
+        }"#;
+
+        let settings = parse_viewsettings_str(input).unwrap();
+        let expected = ViewSettings {
+            snap_to_grid: true,
+            show_grid: true,
+            grid_spacing: 32,
+            show_wireframe: true,
+            ..Default::default()
+        };
+
+        assert_eq!(settings, expected);
+    }
+
     #[test]
     fn test_viewsettings_empty_block() {
         let input = r#"
@@ -422,4 +543,32 @@ mod tests {
             result.err()
         );
     }
+
+    #[test]
+    fn test_viewsettings_from_str() {
+        let settings: ViewSettings = r#"viewsettings { "bSnapToGrid" "1" "nGridSpacing" "8" }"#
+            .parse()
+            .unwrap();
+        assert!(settings.snap_to_grid);
+        assert_eq!(settings.grid_spacing, 8);
+
+        assert!("viewsettings { \"bSnapToGrid\" \"1\" } extra"
+            .parse::<ViewSettings>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_viewsettings_grid_spacing_accessors() {
+        let mut settings: ViewSettings =
+            r#"viewsettings { "bSnapToGrid" "1" "nGridSpacing" "64" "bShowWireframe" "1" }"#
+                .parse()
+                .unwrap();
+        assert_eq!(settings.grid_spacing(), 64);
+        assert!(settings.snap_to_grid());
+        assert!(settings.show_wireframe());
+        assert!(!settings.show_grid());
+
+        settings.set_grid_spacing(16);
+        assert_eq!(settings.grid_spacing(), 16);
+    }
 }
@@ -1,9 +1,10 @@
 use chumsky::{prelude::recursive, IterParser, Parser as ChumskyParser};
 
 use crate::{
+    impl_block_properties_parser,
     parser::{
-        any_quoted_string, close_block, number, open_block, quoted_string, InternalParser,
-        TokenError, TokenSource,
+        close_block, key_value, key_value_numeric, open_block, InternalParser, TokenError,
+        TokenSource, VmfBlock,
     },
     types::Color,
     Parser,
@@ -11,19 +12,19 @@ use crate::{
 
 /// Represents a visgroup in the VMF file
 /// Visgroups can be nested and contain properties like name, id, and color
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct VisGroup<'a> {
     /// The name of the visgroup
-    name: &'a str,
+    pub(crate) name: &'a str,
 
     /// The unique identifier for the visgroup
-    visgroupid: u32,
+    pub(crate) visgroupid: u32,
 
     /// The color of the visgroup in RGB format
-    color: Color,
+    pub(crate) color: Color,
 
     /// Child visgroups contained within this visgroup
-    children: Vec<VisGroup<'a>>,
+    pub(crate) children: Vec<VisGroup<'a>>,
 }
 
 impl<'a> VisGroup<'a> {
@@ -44,19 +45,48 @@ impl<'a> VisGroup<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct VisGroups<'a>(Vec<VisGroup<'a>>);
+pub struct VisGroups<'a>(pub(crate) Vec<VisGroup<'a>>);
 
 impl<'a> VisGroups<'a> {
     pub fn new(visgroups: Vec<VisGroup<'a>>) -> VisGroups<'a> {
         Self(visgroups)
     }
+
+    /// Returns the `(visgroupid, name)` of every visgroup, nested ones included.
+    /// Parents come before their children, in file order.
+    pub fn flatten(&self) -> Vec<(u32, &'a str)> {
+        fn walk<'a>(visgroup: &VisGroup<'a>, out: &mut Vec<(u32, &'a str)>) {
+            out.push((visgroup.visgroupid, visgroup.name));
+            for child in &visgroup.children {
+                walk(child, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        for visgroup in &self.0 {
+            walk(visgroup, &mut out);
+        }
+        out
+    }
+}
+
+/// Internal [`VisGroup`] Properties to be used in a parser impl
+#[derive(Debug, Clone)]
+enum VisGroupProperty<'src> {
+    Name(&'src str),
+    VisGroupId(u32),
+    Color(Color),
+    Child(VisGroup<'src>),
 }
 
 /// Public parser trait implementation that allows [`VisGroups`] to use ::parse(input) call.
 impl<'src> Parser<'src> for VisGroups<'src> {}
 
+impl VmfBlock for VisGroups<'_> {
+    const BLOCK_NAME: &'static str = "visgroups";
+}
+
 /// A [`InternalParser`] implementation for [`VisGroups`].
-/// Every key-value pair needs to be in order, like in the example bellow.
 ///
 /// usage:
 /// ```ignore
@@ -88,7 +118,7 @@ impl<'src> InternalParser<'src> for VisGroups<'src> {
     where
         I: TokenSource<'src>,
     {
-        open_block("visgroups")
+        open_block(VisGroups::BLOCK_NAME)
             .ignore_then(VisGroup::parser::<I>().repeated().collect())
             .then_ignore(close_block())
             .map(VisGroups::new)
@@ -98,8 +128,12 @@ impl<'src> InternalParser<'src> for VisGroups<'src> {
 /// Public parser trait implementation that allows [`VisGroup`] to use ::parse(input) call.
 impl<'src> Parser<'src> for VisGroup<'src> {}
 
+impl VmfBlock for VisGroup<'_> {
+    const BLOCK_NAME: &'static str = "visgroup";
+}
+
 /// A [`InternalParser`] implementation for [`VisGroup`].
-/// Every key-value pair needs to be in order, like in the example bellow.
+/// Keys can come in any order. A missing `color` leaves the visgroup black.
 ///
 /// usage: `let visgroup = VisGroup::parser().parse();`.
 ///
@@ -118,19 +152,32 @@ impl<'src> InternalParser<'src> for VisGroup<'src> {
         I: TokenSource<'src>,
     {
         recursive(|vis_group| {
-            open_block("visgroup")
+            impl_block_properties_parser! {
+                property_list: VisGroupProperty = {
+                    p_name       = key_value("name")               => |s: &'src str| VisGroupProperty::Name(s),
+                    p_visgroupid = key_value_numeric("visgroupid") => VisGroupProperty::VisGroupId,
+                    p_color      = Color::parser()                 => VisGroupProperty::Color,
+                    p_child      = vis_group.clone()               => VisGroupProperty::Child,
+                }
+            }
+
+            open_block(VisGroup::BLOCK_NAME)
+                .boxed()
+                .ignore_then(property_list.repeated().collect::<Vec<VisGroupProperty>>())
+                .then_ignore(close_block())
+                .map(|properties: Vec<VisGroupProperty>| {
+                    let mut visgroup = VisGroup::default();
+                    for prop in properties {
+                        match prop {
+                            VisGroupProperty::Name(val) => visgroup.name = val,
+                            VisGroupProperty::VisGroupId(val) => visgroup.visgroupid = val,
+                            VisGroupProperty::Color(val) => visgroup.color = val,
+                            VisGroupProperty::Child(val) => visgroup.children.push(val),
+                        }
+                    }
+                    visgroup
+                })
                 .boxed()
-                .ignore_then(
-                    quoted_string("name")
-                        .boxed()
-                        .ignore_then(any_quoted_string().boxed())
-                        .then_ignore(quoted_string("visgroupid").boxed())
-                        .then(number::<u32, I>().boxed())
-                        .then(Color::parser::<I>().boxed())
-                        .then(vis_group.repeated().collect().boxed()),
-                )
-                .then_ignore(close_block().boxed())
-                .map(|(((name, id), color), children)| VisGroup::new(name, id, color, children))
         })
     }
 }
@@ -181,6 +228,109 @@ mod tests {
         assert_eq!(parsed.children[0].color.r, 100);
     }
 
+    #[test]
+    fn test_visgroups_flatten() {
+        let input = lex(r#"
+            visgroups {
+                visgroup {
+                    "name" "Trees"
+                    "visgroupid" "1"
+                    "color" "0 255 0"
+                    visgroup {
+                        "name" "Pines"
+                        "visgroupid" "2"
+                        "color" "0 128 0"
+                    }
+                    visgroup {
+                        "name" "Oaks"
+                        "visgroupid" "3"
+                        "color" "0 64 0"
+                    }
+                }
+                visgroup {
+                    "name" "Lights"
+                    "visgroupid" "4"
+                    "color" "255 255 0"
+                }
+            }
+        "#);
+
+        let parsed = VisGroups::parse(input).unwrap();
+        assert_eq!(
+            parsed.flatten(),
+            vec![(1, "Trees"), (2, "Pines"), (3, "Oaks"), (4, "Lights")]
+        );
+    }
+
+    #[test]
+    fn test_visgroup_without_color() {
+        let input = lex(r#"
+            visgroup {
+                "name" "No color"
+                "visgroupid" "7"
+            }
+        "#);
+
+        let parsed = VisGroup::parse(input).unwrap();
+        assert_eq!(parsed.name, "No color");
+        assert_eq!(parsed.visgroupid, 7);
+        assert_eq!(parsed.color, Color::default());
+    }
+
+    #[test]
+    fn test_visgroup_keys_out_of_order() {
+        let input = lex(r#"
+            visgroup {
+                "color" "1 2 3"
+                visgroup {
+                    "visgroupid" "9"
+                    "name" "Child"
+                }
+                "visgroupid" "8"
+                "name" "Parent"
+            }
+        "#);
+
+        let parsed = VisGroup::parse(input).unwrap();
+        assert_eq!(parsed.name, "Parent");
+        assert_eq!(parsed.visgroupid, 8);
+        assert_eq!(parsed.color.b, 3);
+        assert_eq!(parsed.children[0].name, "Child");
+        assert_eq!(parsed.children[0].visgroupid, 9);
+    }
+
+    #[test]
+    fn test_deeply_nested_visgroup() {
+        let input = lex(r#"
+            visgroup {
+                "name" "Level0"
+                "visgroupid" "1"
+                "color" "1 1 1"
+                visgroup {
+                    "name" "Level1"
+                    "visgroupid" "2"
+                    "color" "2 2 2"
+                    visgroup {
+                        "name" "Level2"
+                        "visgroupid" "3"
+                        "color" "3 3 3"
+                    }
+                }
+                visgroup {
+                    "name" "Sibling"
+                    "visgroupid" "4"
+                    "color" "4 4 4"
+                }
+            }
+        "#);
+
+        let parsed = VisGroup::parse(input).unwrap();
+        assert_eq!(parsed.children.len(), 2);
+        assert_eq!(parsed.children[0].children[0].name, "Level2");
+        assert_eq!(parsed.children[0].children[0].visgroupid, 3);
+        assert_eq!(parsed.children[1].name, "Sibling");
+    }
+
     #[test]
     fn test_visgroups_block() {
         let input = lex(r#"
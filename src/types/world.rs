@@ -1,28 +1,31 @@
 use chumsky::{IterParser, Parser as ChumskyParser};
-use std::collections::HashMap;
+use std::borrow::Cow;
 
 use crate::{
     impl_block_properties_parser,
     parser::{
-        any_quoted_string, close_block, key_value, key_value_boolean, key_value_numeric,
-        open_block, InternalParser, TokenError, TokenSource,
+        any_quoted_string, close_block, key_value, key_value_boolean, key_value_float,
+        key_value_numeric, open_block, skip_unknown_block, InternalParser, TokenError,
+        TokenSource, VmfBlock,
+    },
+    types::{
+        owned_str, EditorData, Entity, Point3D, Properties, PropertiesExt, Solid, TextureAxis,
     },
-    types::{EditorData, Solid},
     Parser,
 };
 
 use super::Group;
 
 /// Represents the worldspawn entity in a VMF file
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct World<'src> {
     pub id: u32,
     pub mapversion: u32,
-    pub classname: &'src str,
-    pub detailmaterial: Option<&'src str>,
-    pub detailvbsp: Option<&'src str>,
+    pub classname: Cow<'src, str>,
+    pub detailmaterial: Option<Cow<'src, str>>,
+    pub detailvbsp: Option<Cow<'src, str>>,
     pub maxpropscreenwidth: Option<i32>,
-    pub skyname: Option<&'src str>,
+    pub skyname: Option<Cow<'src, str>>,
     pub sounds: Option<u32>,
     pub maxrange: Option<f32>,
 
@@ -37,12 +40,15 @@ pub struct World<'src> {
     // Geometry
     pub solids: Vec<Solid<'src>>,
 
+    // Entities nested in `hidden` blocks inside the world instead of at the top level
+    pub hidden_entities: Vec<Entity<'src>>,
+
     // Entity connections
-    pub targetname: Option<&'src str>,
-    pub target: Option<&'src str>,
+    pub targetname: Option<Cow<'src, str>>,
+    pub target: Option<Cow<'src, str>>,
 
     // Custom key-value pairs for world-specific properties
-    pub properties: HashMap<&'src str, &'src str>,
+    pub properties: Properties<'src>,
 
     // Editor data
     pub hidden: Option<bool>,
@@ -50,8 +56,110 @@ pub struct World<'src> {
     pub editor: Option<EditorData<'src>>,
 }
 
+/// A brush face with everything needed to draw it, see [`World::faces`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaceRef<'a> {
+    pub solid_id: u32,
+    pub side_id: u32,
+    pub material: &'a str,
+    pub uaxis: &'a TextureAxis,
+    pub vaxis: &'a TextureAxis,
+    /// Corners of the face polygon, in order around its edge.
+    pub vertices: Vec<Point3D>,
+}
+
+/// Detail prop settings of a map, see [`World::detail_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetailConfig<'a> {
+    /// Material the detail sprites are drawn with, the `detailmaterial` key.
+    pub material: Option<&'a str>,
+    /// The `.vbsp` file listing the detail types, the `detailvbsp` key.
+    pub vbsp: Option<&'a str>,
+}
+
+impl<'src> World<'src> {
+    /// Iterates over the faces of every world brush with their material, texture axes and
+    /// polygon.
+    ///
+    /// Polygons are reconstructed per solid with [`Solid::face_polygons`]. Sides that don't
+    /// form a face are skipped.
+    pub fn faces(&self) -> impl Iterator<Item = FaceRef<'_>> {
+        self.solids.iter().flat_map(|solid| {
+            solid
+                .sides
+                .iter()
+                .zip(solid.face_polygons())
+                .filter(|(_, polygon)| !polygon.is_empty())
+                .map(move |(side, vertices)| FaceRef {
+                    solid_id: solid.id,
+                    side_id: side.id,
+                    material: &side.material,
+                    uaxis: &side.uaxis,
+                    vaxis: &side.vaxis,
+                    vertices,
+                })
+        })
+    }
+
+    /// Returns the value of a custom key that has no dedicated field.
+    pub fn get_property(&self, key: &str) -> Option<&str> {
+        self.properties.get_str(key)
+    }
+
+    /// Same as [`get_property`](Self::get_property).
+    #[deprecated(note = "use `World::get_property`")]
+    pub fn get_custom(&self, key: &str) -> Option<&str> {
+        self.get_property(key)
+    }
+
+    /// Name of the 2D skybox, the `skyname` key.
+    pub fn skybox(&self) -> Option<&str> {
+        self.skyname.as_deref()
+    }
+
+    /// Replaces the 2D skybox, e.g. `world.set_skybox("sky_day01_01")`.
+    pub fn set_skybox(&mut self, name: impl Into<Cow<'src, str>>) {
+        self.skyname = Some(name.into());
+    }
+
+    /// Returns the `detailmaterial` and `detailvbsp` keys, `None` if neither is set.
+    pub fn detail_config(&self) -> Option<DetailConfig<'_>> {
+        let config = DetailConfig {
+            material: self.detailmaterial.as_deref(),
+            vbsp: self.detailvbsp.as_deref(),
+        };
+        (config.material.is_some() || config.vbsp.is_some()).then_some(config)
+    }
+
+    /// Converts this world into one that owns all of its data.
+    pub fn into_owned(self) -> World<'static> {
+        World {
+            classname: owned_str(self.classname),
+            detailmaterial: self.detailmaterial.map(owned_str),
+            detailvbsp: self.detailvbsp.map(owned_str),
+            skyname: self.skyname.map(owned_str),
+            solids: self.solids.into_iter().map(Solid::into_owned).collect(),
+            hidden_entities: self
+                .hidden_entities
+                .into_iter()
+                .map(Entity::into_owned)
+                .collect(),
+            targetname: self.targetname.map(owned_str),
+            target: self.target.map(owned_str),
+            properties: self
+                .properties
+                .into_iter()
+                .map(|(key, value)| (owned_str(key), owned_str(value)))
+                .collect(),
+            group: self.group.map(Group::into_owned),
+            editor: self.editor.map(EditorData::into_owned),
+            ..self
+        }
+    }
+}
+
 /// Internal [`World`] Properties to be used in a parser impl
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 enum WorldProperty<'src> {
     Id(u32),
     MapVersion(u32),
@@ -74,12 +182,46 @@ enum WorldProperty<'src> {
     Group(Group<'src>),
     Editor(EditorData<'src>),
     Solid(Solid<'src>),
+    HiddenBlock(Vec<HiddenItem<'src>>),
     Custom(&'src str, &'src str),
 }
 
+/// Something found inside a `hidden` block of the world.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum HiddenItem<'src> {
+    Solid(Solid<'src>),
+    Entity(Entity<'src>),
+    Other,
+}
+
+/// Parser for `hidden` blocks inside the world, which can hold solids and entities.
+/// Key-values and other blocks inside them are skipped.
+fn parse_hidden_block<'src, I>(
+) -> impl ChumskyParser<'src, I, Vec<HiddenItem<'src>>, TokenError<'src>>
+where
+    I: TokenSource<'src>,
+{
+    let item = Solid::parser()
+        .map(HiddenItem::Solid)
+        .or(Entity::parser().map(HiddenItem::Entity))
+        .or(any_quoted_string()
+            .then(any_quoted_string())
+            .map(|_| HiddenItem::Other))
+        .or(skip_unknown_block(&["solid", "entity"]).map(|_| HiddenItem::Other));
+
+    open_block("hidden")
+        .ignore_then(item.repeated().collect())
+        .then_ignore(close_block())
+}
+
 /// Public parser trait implementation
 impl<'src> Parser<'src> for World<'src> {}
 
+impl VmfBlock for World<'_> {
+    const BLOCK_NAME: &'static str = "world";
+}
+
 /// InternalParser implementation for World
 impl<'src> InternalParser<'src> for World<'src> {
     fn parser<I>() -> impl ChumskyParser<'src, I, Self, TokenError<'src>>
@@ -96,11 +238,11 @@ impl<'src> InternalParser<'src> for World<'src> {
                 p_maxpropscreenwidth    = key_value_numeric("maxpropscreenwidth")     => WorldProperty::MaxPropScreenWidth,
                 p_skyname               = key_value("skyname")                        => |s: &str| WorldProperty::Skyname(s),
                 p_sounds                = key_value_numeric("sounds")                 => WorldProperty::Sounds,
-                p_maxrange              = key_value_numeric("maxrange")               => WorldProperty::MaxRange,
-                p_maxoccludeearea       = key_value_numeric("maxoccludeearea")        => WorldProperty::MaxOccludeeArea,
-                p_minoccluderarea       = key_value_numeric("minoccluderarea")        => WorldProperty::MinOccluderArea,
-                p_maxoccludeearea_csgo  = key_value_numeric("maxoccludeearea_csgo")   => WorldProperty::MaxOccludeeAreaCsgo,
-                p_minoccluderarea_csgo  = key_value_numeric("minoccluderarea_csgo")   => WorldProperty::MinOccluderAreaCsgo,
+                p_maxrange              = key_value_float("maxrange")                 => WorldProperty::MaxRange,
+                p_maxoccludeearea       = key_value_float("maxoccludeearea")          => WorldProperty::MaxOccludeeArea,
+                p_minoccluderarea       = key_value_float("minoccluderarea")          => WorldProperty::MinOccluderArea,
+                p_maxoccludeearea_csgo  = key_value_float("maxoccludeearea_csgo")     => WorldProperty::MaxOccludeeAreaCsgo,
+                p_minoccluderarea_csgo  = key_value_float("minoccluderarea_csgo")     => WorldProperty::MinOccluderAreaCsgo,
                 p_difficulty_level      = key_value_numeric("difficulty_level")       => WorldProperty::DifficultyLevel,
                 p_hdr_level             = key_value_numeric("hdr_level")              => WorldProperty::HdrLevel,
                 p_targetname            = key_value("targetname")                     => |s: &str| WorldProperty::Targetname(s),
@@ -112,6 +254,7 @@ impl<'src> InternalParser<'src> for World<'src> {
 
         let editor_parser = EditorData::parser().map(WorldProperty::Editor);
         let solid_parser = Solid::parser().map(WorldProperty::Solid);
+        let hidden_parser = parse_hidden_block().map(WorldProperty::HiddenBlock);
         let custom_property = any_quoted_string()
             .then(any_quoted_string())
             .map(|(key, value): (&str, &str)| WorldProperty::Custom(key, value));
@@ -119,9 +262,10 @@ impl<'src> InternalParser<'src> for World<'src> {
         let any_property = known_properties
             .or(editor_parser)
             .or(solid_parser)
+            .or(hidden_parser)
             .or(custom_property);
 
-        open_block("world")
+        open_block(Self::BLOCK_NAME)
             .ignore_then(
                 any_property
                     .repeated()
@@ -134,13 +278,15 @@ impl<'src> InternalParser<'src> for World<'src> {
                     match prop {
                         WorldProperty::Id(val) => world.id = val,
                         WorldProperty::MapVersion(val) => world.mapversion = val,
-                        WorldProperty::Classname(val) => world.classname = val,
-                        WorldProperty::DetailMaterial(val) => world.detailmaterial = Some(val),
-                        WorldProperty::DetailVbsp(val) => world.detailvbsp = Some(val),
+                        WorldProperty::Classname(val) => world.classname = val.into(),
+                        WorldProperty::DetailMaterial(val) => {
+                            world.detailmaterial = Some(val.into())
+                        }
+                        WorldProperty::DetailVbsp(val) => world.detailvbsp = Some(val.into()),
                         WorldProperty::MaxPropScreenWidth(val) => {
                             world.maxpropscreenwidth = Some(val)
                         }
-                        WorldProperty::Skyname(val) => world.skyname = Some(val),
+                        WorldProperty::Skyname(val) => world.skyname = Some(val.into()),
                         WorldProperty::Sounds(val) => world.sounds = Some(val),
                         WorldProperty::MaxRange(val) => world.maxrange = Some(val),
                         WorldProperty::MaxOccludeeArea(val) => world.maxoccludeearea = Some(val),
@@ -153,14 +299,23 @@ impl<'src> InternalParser<'src> for World<'src> {
                         }
                         WorldProperty::DifficultyLevel(val) => world.difficulty_level = Some(val),
                         WorldProperty::HdrLevel(val) => world.hdr_level = Some(val),
-                        WorldProperty::Targetname(val) => world.targetname = Some(val),
-                        WorldProperty::Target(val) => world.target = Some(val),
+                        WorldProperty::Targetname(val) => world.targetname = Some(val.into()),
+                        WorldProperty::Target(val) => world.target = Some(val.into()),
                         WorldProperty::Hidden(val) => world.hidden = Some(val),
                         WorldProperty::Group(val) => world.group = Some(val),
                         WorldProperty::Editor(val) => world.editor = Some(val),
                         WorldProperty::Solid(val) => world.solids.push(val),
+                        WorldProperty::HiddenBlock(items) => {
+                            for item in items {
+                                match item {
+                                    HiddenItem::Solid(val) => world.solids.push(val),
+                                    HiddenItem::Entity(val) => world.hidden_entities.push(val),
+                                    HiddenItem::Other => {}
+                                }
+                            }
+                        }
                         WorldProperty::Custom(key, value) => {
-                            world.properties.insert(key, value);
+                            world.properties.insert(key.into(), value.into());
                         }
                     }
                 }
@@ -175,6 +330,83 @@ mod tests {
     use super::*;
     use crate::util::lex;
 
+    #[test]
+    fn test_world_set_skybox() {
+        use crate::writer::ToVmf;
+
+        let src = r#"world { "id" "1" "classname" "worldspawn" "skyname" "sky_day01_01" }"#;
+        let mut world = World::parse(lex(src)).unwrap();
+        assert_eq!(world.skybox(), Some("sky_day01_01"));
+
+        world.set_skybox(format!("sky_{}", "night01"));
+        assert_eq!(world.skybox(), Some("sky_night01"));
+
+        let out = world.to_vmf();
+        assert!(out.contains("\"skyname\" \"sky_night01\""), "{}", out);
+        assert!(!out.contains("sky_day01_01"));
+    }
+
+    #[test]
+    fn test_world_detail_config() {
+        let src = r#"world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            "detailmaterial" "detail/detailsprites"
+            "detailvbsp" "detail.vbsp"
+        }"#;
+        let world = World::parse(lex(src)).unwrap();
+        assert_eq!(
+            world.detail_config(),
+            Some(DetailConfig {
+                material: Some("detail/detailsprites"),
+                vbsp: Some("detail.vbsp"),
+            })
+        );
+
+        let src = r#"world { "id" "1" "classname" "worldspawn" }"#;
+        assert_eq!(World::parse(lex(src)).unwrap().detail_config(), None);
+    }
+
+    #[test]
+    fn test_world_hidden_entities() {
+        let input = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid { "id" "2" }
+            hidden
+            {
+                entity
+                {
+                    "id" "3"
+                    "classname" "logic_relay"
+                    "targetname" "relay_hidden"
+                    "origin" "0 0 0"
+                }
+                solid { "id" "4" }
+            }
+        }
+        "#;
+
+        let world = World::parse(lex(input)).unwrap();
+        assert_eq!(world.hidden_entities.len(), 1);
+        let entity = &world.hidden_entities[0];
+        assert_eq!(entity.id, 3);
+        assert_eq!(entity.classname, "logic_relay");
+        assert_eq!(entity.targetname.as_deref(), Some("relay_hidden"));
+        let solid_ids: Vec<_> = world.solids.iter().map(|s| s.id).collect();
+        assert_eq!(solid_ids, vec![2, 4]);
+
+        let out = crate::writer::ToVmf::to_vmf(&world);
+        let reparsed = World::parse(lex(&out)).unwrap();
+        assert_eq!(reparsed.hidden_entities, world.hidden_entities);
+
+        let world = world.into_owned();
+        assert_eq!(world.hidden_entities[0].id, 3);
+    }
+
     #[test]
     fn test_world_minimal() {
         let input = r#"
@@ -218,10 +450,13 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let world = result.unwrap();
-        assert_eq!(world.detailmaterial, Some("detail/detailsprites"));
-        assert_eq!(world.detailvbsp, Some("detail.vbsp"));
+        assert_eq!(
+            world.detailmaterial.as_deref(),
+            Some("detail/detailsprites")
+        );
+        assert_eq!(world.detailvbsp.as_deref(), Some("detail.vbsp"));
         assert_eq!(world.maxpropscreenwidth, Some(-1));
-        assert_eq!(world.skyname, Some("sky_day01_01"));
+        assert_eq!(world.skyname.as_deref(), Some("sky_day01_01"));
     }
 
     #[test]
@@ -347,9 +582,21 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let world = result.unwrap();
-        assert_eq!(world.properties.get("customkey1"), Some(&"customvalue1"));
-        assert_eq!(world.properties.get("customkey2"), Some(&"customvalue2"));
-        assert_eq!(world.properties.get("_light"), Some(&"255 255 255 200"));
+        assert_eq!(
+            world.properties.get("customkey1").map(|v| v.as_ref()),
+            Some("customvalue1")
+        );
+        assert_eq!(
+            world.properties.get("customkey2").map(|v| v.as_ref()),
+            Some("customvalue2")
+        );
+        assert_eq!(
+            world.properties.get("_light").map(|v| v.as_ref()),
+            Some("255 255 255 200")
+        );
+        assert_eq!(world.get_property("customkey2"), Some("customvalue2"));
+        let keys: Vec<_> = world.properties.keys().map(|key| key.as_ref()).collect();
+        assert_eq!(keys, ["customkey1", "customkey2", "_light"]);
     }
 
     #[test]
@@ -373,8 +620,11 @@ mod tests {
         assert_eq!(world.id, 1);
         assert_eq!(world.mapversion, 16);
         assert_eq!(world.classname, "worldspawn");
-        assert_eq!(world.skyname, Some("sky_day01_01"));
-        assert_eq!(world.detailmaterial, Some("detail/detailsprites"));
+        assert_eq!(world.skyname.as_deref(), Some("sky_day01_01"));
+        assert_eq!(
+            world.detailmaterial.as_deref(),
+            Some("detail/detailsprites")
+        );
     }
 
     #[test]
@@ -454,8 +704,8 @@ mod tests {
         assert!(result.is_ok(), "Parsing failed: {:?}", result.err());
 
         let world = result.unwrap();
-        assert_eq!(world.targetname, Some("world_spawn"));
-        assert_eq!(world.target, Some("some_target"));
+        assert_eq!(world.targetname.as_deref(), Some("world_spawn"));
+        assert_eq!(world.target.as_deref(), Some("some_target"));
     }
 
     #[test]
@@ -676,6 +926,49 @@ mod tests {
         assert_eq!(world.id, 1);
         assert_eq!(world.classname, "worldspawn");
         assert_eq!(world.solids.len(), 2);
-        assert_eq!(world.skyname, Some("sky_day01_01"));
+        assert_eq!(world.skyname.as_deref(), Some("sky_day01_01"));
+    }
+
+    #[test]
+    fn test_world_integer_floats() {
+        for value in ["5", "5.0"] {
+            let input = format!(
+                r#"world {{ "id" "1" "classname" "worldspawn" "maxrange" "{0}" "minoccluderarea" "{0}" }}"#,
+                value
+            );
+            let world = World::parse(lex(&input)).unwrap();
+            assert_eq!(world.maxrange, Some(5.0));
+            assert_eq!(world.minoccluderarea, Some(5.0));
+        }
+    }
+
+    #[test]
+    fn test_world_faces_of_cube() {
+        let input = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side { "id" "3" "plane" "(0 64 64) (64 64 64) (64 0 64)" "material" "DEV/TOP" }
+                side { "id" "4" "plane" "(0 0 0) (64 0 0) (64 64 0)" "material" "DEV/BOTTOM" }
+                side { "id" "5" "plane" "(0 64 64) (0 0 64) (0 0 0)" "material" "DEV/WALL" }
+                side { "id" "6" "plane" "(64 64 0) (64 0 0) (64 0 64)" "material" "DEV/WALL" }
+                side { "id" "7" "plane" "(64 64 64) (0 64 64) (0 64 0)" "material" "DEV/WALL" }
+                side { "id" "8" "plane" "(64 0 0) (0 0 0) (0 0 64)" "material" "DEV/WALL" }
+            }
+        }
+        "#;
+        let world = World::parse(lex(input)).unwrap();
+
+        let faces: Vec<_> = world.faces().collect();
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|face| face.vertices.len() == 4));
+        assert!(faces.iter().all(|face| face.solid_id == 2));
+        assert_eq!(faces[0].side_id, 3);
+        assert_eq!(faces[0].material, "DEV/TOP");
+        assert!(faces[0].vertices.iter().all(|vertex| vertex.z == 64.0));
     }
 }
@@ -1,17 +1,27 @@
-use chumsky::input::Stream;
+use chumsky::input::{Input as _, Stream};
+use std::borrow::Cow;
+use std::io::BufRead;
+use std::ops::Range;
+#[cfg(feature = "fs")]
 use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+use crate::document::{block_sources, ParsedVmf};
 use crate::error::VMFError;
-use crate::parser::lexer::TokenIter;
-use crate::parser::{skip_unknown_block, InternalParser};
+use crate::parser::lexer::{Token, TokenIter};
+use crate::parser::scan::{scan_blocks, ScanError};
+use crate::parser::{skip_unknown_block, InternalParser, TokenError, TokenSource};
 use crate::types::*;
 
-use chumsky::primitive::choice;
+use chumsky::error::Rich;
+use chumsky::primitive::{any, choice, end};
+use chumsky::span::SimpleSpan;
 use chumsky::IterParser;
 use chumsky::Parser as ChumskyParser;
 
 /// `VMFValue` holds types of all items from a VMF.
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum VMFValue<'src> {
     VersionInfo(VersionInfo),
     VisGroups(Box<VisGroups<'src>>),
@@ -22,8 +32,156 @@ pub enum VMFValue<'src> {
     Cordon(Box<Cordon>),
 }
 
+/// Predicate over entity classnames, used by [`ParseOptions::entity_filter`].
+pub type EntityFilter = Box<dyn Fn(&str) -> bool>;
+
+/// Options controlling how [`VMF::parse_with`] parses a file.
+///
+/// # Example
+/// ```ignore
+/// let options = ParseOptions {
+///     entity_filter: Some(Box::new(|classname| classname.starts_with("trigger_"))),
+///     ..Default::default()
+/// };
+/// let data = vmf.parse_with(&options)?;
+/// ```
+#[derive(Default)]
+pub struct ParseOptions {
+    /// Entities whose classname is rejected by this filter are parsed and discarded
+    /// immediately instead of being kept in the result.
+    pub entity_filter: Option<EntityFilter>,
+
+    /// Skip input that isn't part of a complete top-level block instead of failing.
+    /// Useful for files that end with stray bytes from an interrupted save. Stray input
+    /// between blocks is skipped too, the blocks after it are still parsed.
+    pub allow_trailing: bool,
+
+    /// Lowercase side materials and entity models. Source looks these paths up without
+    /// regard to case, but packing them for a case-sensitive file system (e.g. a Linux
+    /// dedicated server) needs one spelling.
+    pub normalize_materials: bool,
+
+    /// Keep the `//` comments in front of every top-level block, so they can be written
+    /// out again. Only used by [`VMF::parse_document_with`], see
+    /// [`ParsedVmf::leading_comments`].
+    pub keep_comments: bool,
+
+    /// Give every entity classname an integer symbol while building the document. Only
+    /// used by [`VMF::parse_document_with`], see [`ParsedVmf::classnames`].
+    pub intern_classnames: bool,
+
+    /// Most vertex rows a displacement may have, which also limits each of its per-vertex
+    /// blocks to this many rows squared values. A displacement past the limit fails the
+    /// parse. `None` uses [`DispInfo::MAX_ROWS`], the size of the largest displacement
+    /// Hammer makes. The parser never accepts more than that while reading rows, so larger
+    /// values have no effect.
+    pub max_disp_rows: Option<usize>,
+}
+
+impl std::fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("entity_filter", &self.entity_filter.as_ref().map(|_| ".."))
+            .field("allow_trailing", &self.allow_trailing)
+            .field("normalize_materials", &self.normalize_materials)
+            .field("keep_comments", &self.keep_comments)
+            .field("intern_classnames", &self.intern_classnames)
+            .field("max_disp_rows", &self.max_disp_rows)
+            .finish()
+    }
+}
+
+/// Time spent in each stage of [`VMF::parse_timed`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ParseTiming {
+    /// Time spent turning the source into tokens.
+    pub tokenize: Duration,
+    /// Time spent parsing the tokens into blocks.
+    pub parse: Duration,
+}
+
+impl ParseTiming {
+    /// Combined time of both stages.
+    pub fn total(&self) -> Duration {
+        self.tokenize + self.parse
+    }
+}
+
+/// A part of the source that [`VMF::parse_recovering`] had to skip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDiagnostic {
+    /// Name of the top-level block, or `None` for stray tokens between blocks.
+    pub block: Option<String>,
+    /// Byte range of the skipped source.
+    pub span: Range<usize>,
+    /// What went wrong.
+    pub message: String,
+}
+
+/// A recoverable problem found by [`VMF::parse_with_warnings`]. The file still parses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// Name of the block the problem is in, e.g. `solid` for a key inside a solid.
+    pub block: String,
+    /// Byte range of the offending source.
+    pub span: Range<usize>,
+    /// What looks wrong.
+    pub message: String,
+}
+
+/// Name and byte range of one top-level block, as listed by [`VMF::index`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedBlock {
+    /// Block keyword, e.g. `world` or `entity`.
+    pub name: String,
+    /// Byte range of the block in the source, from its name to the closing brace.
+    pub span: Range<usize>,
+}
+
+/// The top-level blocks of a VMF in file order, found without parsing their contents.
+///
+/// usage:
+/// ```ignore
+/// let index = vmf.index();
+/// let block = index.get(3).unwrap();
+/// let value = vmf.reparse_block(block.span.clone())?;
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockIndex {
+    blocks: Vec<IndexedBlock>,
+}
+
+impl BlockIndex {
+    /// Returns the block at position `index`.
+    pub fn get(&self, index: usize) -> Option<&IndexedBlock> {
+        self.blocks.get(index)
+    }
+
+    /// Number of indexed blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns `true` if no blocks were found.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Iterates over the blocks in file order.
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedBlock> {
+        self.blocks.iter()
+    }
+}
+
 /// VMF struct with raw file data.
 /// Use `parse()` to get parsed data that borrows from this instance.
+///
+/// A `VMF` can be loaded from disk with [`VMF::open`] (requires the default `fs` feature)
+/// or built from data that is already in memory:
+/// ```ignore
+/// let vmf: VMF = source.parse()?;
+/// let vmf = VMF::from(owned_string);
+/// ```
 #[allow(clippy::upper_case_acronyms)]
 pub struct VMF {
     data: String,
@@ -38,6 +196,7 @@ impl VMF {
     /// let data = vmf.parse()?;
     /// // Use data..
     /// ```
+    #[cfg(feature = "fs")]
     pub fn open(path: impl AsRef<Path>) -> Result<Self, VMFError> {
         let data = std::fs::read_to_string(path)?;
         Ok(VMF { data })
@@ -45,8 +204,199 @@ impl VMF {
 
     /// Parse the VMF file and return the parsed data.
     /// The returned data borrows from this VMF instance.
-    pub fn parse(&self) -> Result<Vec<VMFValue>, VMFError> {
-        parse_vmf_from_str(&self.data)
+    pub fn parse(&self) -> Result<Vec<VMFValue<'_>>, VMFError> {
+        parse_vmf_from_str(&self.data, &ParseOptions::default())
+    }
+
+    /// Parse the VMF file using the given [`ParseOptions`].
+    /// The returned data borrows from this VMF instance.
+    pub fn parse_with(&self, options: &ParseOptions) -> Result<Vec<VMFValue<'_>>, VMFError> {
+        parse_vmf_from_str(&self.data, options)
+    }
+
+    /// Parse the VMF file and measure how long tokenizing and parsing took.
+    ///
+    /// Unlike [`VMF::parse`], which lexes lazily while parsing, this tokenizes the whole
+    /// file up front so the two stages can be timed separately.
+    pub fn parse_timed(&self) -> Result<(Vec<VMFValue<'_>>, ParseTiming), VMFError> {
+        let start = Instant::now();
+        let tokens: Vec<_> = TokenIter::new(&self.data)
+            .spanned()
+            .map(|(token, span)| (token, SimpleSpan::from(span)))
+            .collect();
+        let tokenize = start.elapsed();
+
+        let start = Instant::now();
+        let eoi = SimpleSpan::from(self.data.len()..self.data.len());
+        let blocks = parse_tokens(
+            &self.data,
+            Stream::from_iter(tokens).map(eoi, |(token, span)| (token, span)),
+            &ParseOptions::default(),
+        )?;
+        let parse = start.elapsed();
+
+        Ok((blocks, ParseTiming { tokenize, parse }))
+    }
+
+    /// Parse the VMF file one top-level block at a time, skipping blocks that fail.
+    ///
+    /// Blocks are found by matching braces first, then each one is parsed on its own, so a
+    /// single malformed entity doesn't lose the rest of the map. Every skipped block or stray
+    /// piece of input is reported as a [`ParseDiagnostic`].
+    pub fn parse_recovering(&self) -> (Vec<VMFValue<'_>>, Vec<ParseDiagnostic>) {
+        let mut blocks = Vec::new();
+        let mut diagnostics = Vec::new();
+
+        for item in scan_blocks(&self.data) {
+            match item {
+                Ok(block) => match parse_single_block(block.name, &self.data, block.span.clone()) {
+                    Ok(value) => blocks.extend(value),
+                    Err(err) => diagnostics.push(ParseDiagnostic {
+                        block: Some(block.name.to_string()),
+                        span: block.span,
+                        message: err.to_string(),
+                    }),
+                },
+                Err(ScanError::Stray(span)) => diagnostics.push(ParseDiagnostic {
+                    block: None,
+                    span,
+                    message: "Unexpected input outside of a block".to_string(),
+                }),
+                Err(ScanError::Unterminated(block)) => diagnostics.push(ParseDiagnostic {
+                    block: Some(block.name.to_string()),
+                    span: block.span,
+                    message: "Block is missing its closing brace".to_string(),
+                }),
+            }
+        }
+
+        (blocks, diagnostics)
+    }
+
+    /// Parse the VMF file like [`VMF::parse`] and also report soft problems that don't
+    /// stop it from parsing.
+    ///
+    /// Reported are keys that appear more than once in a block, where the last value wins,
+    /// unknown top-level blocks, which are skipped, and malformed entries in `connections`
+    /// blocks, which are dropped from the entity's outputs. Keys that may repeat, like
+    /// `visgroupid` and the outputs in `connections`, are not reported as duplicates.
+    pub fn parse_with_warnings(&self) -> Result<(Vec<VMFValue<'_>>, Vec<ParseWarning>), VMFError> {
+        let blocks = self.parse()?;
+        Ok((blocks, collect_warnings(&self.data)))
+    }
+
+    /// Checks that the file is structurally valid without building any blocks.
+    ///
+    /// This is a single pass over the tokens that checks every top-level item is a named
+    /// block, braces are balanced and all input (including quoted strings) is terminated.
+    /// Values are not parsed, so a file that validates can still fail [`VMF::parse`], e.g.
+    /// on a malformed number. Returns the first problem found.
+    pub fn validate(&self) -> Result<(), VMFError> {
+        let error = |message: &str, span: Range<usize>| {
+            Err(error_at(&self.data, span.start, message.to_string()))
+        };
+
+        let mut depth = 0usize;
+        let mut tokens = TokenIter::new(&self.data).spanned();
+        while let Some((token, span)) = tokens.next() {
+            match token {
+                Token::Error => return error("Unrecognized input", span),
+                Token::Ident(_) if depth == 0 => match tokens.next() {
+                    Some((Token::LBracket, _)) => depth = 1,
+                    _ => return error("Expected '{' after block name", span),
+                },
+                Token::LBracket if depth > 0 => depth += 1,
+                Token::RBracket if depth > 0 => depth -= 1,
+                _ if depth == 0 => return error("Unexpected input outside of a block", span),
+                _ => {}
+            }
+        }
+
+        if depth > 0 {
+            return error("Block is missing its closing brace", self.data.len()..self.data.len());
+        }
+        Ok(())
+    }
+
+    /// Lists the name and byte range of every top-level block by matching braces.
+    ///
+    /// Block contents are not parsed, so this is much cheaper than [`VMF::parse`]. Use
+    /// [`VMF::reparse_block`] to parse single blocks on demand. Stray input and blocks
+    /// missing their closing brace are left out, [`VMF::parse_recovering`] reports them.
+    pub fn index(&self) -> BlockIndex {
+        let blocks = scan_blocks(&self.data)
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|block| IndexedBlock {
+                name: block.name.to_string(),
+                span: block.span,
+            })
+            .collect();
+        BlockIndex { blocks }
+    }
+
+    /// Parses the single top-level block at `span`, usually taken from [`VMF::index`].
+    ///
+    /// Returns `Ok(None)` for blocks this crate doesn't know, like [`VMF::parse`] skips them.
+    pub fn reparse_block(&self, span: Range<usize>) -> Result<Option<VMFValue<'_>>, VMFError> {
+        let src = self.data.get(span.clone()).ok_or_else(|| {
+            VMFError::ParseError(format!("Block span {:?} is outside of the file", span))
+        })?;
+        match TokenIter::new(src).next() {
+            Some(Ok(Token::Ident(name))) => parse_single_block(name, &self.data, span),
+            _ => Err(error_at(&self.data, span.start, "Expected a block name".to_string())),
+        }
+    }
+
+    /// Parse the VMF file into a [`ParsedVmf`] document.
+    /// The returned document borrows from this VMF instance.
+    ///
+    /// The document also keeps the source text of every block, so unedited blocks can be
+    /// written back unchanged (see [`ParsedVmf::edit`]).
+    pub fn parse_document(&self) -> Result<ParsedVmf<'_>, VMFError> {
+        self.parse_document_with(&ParseOptions::default())
+    }
+
+    /// Parse the VMF file into a [`ParsedVmf`] document using the given [`ParseOptions`].
+    ///
+    /// Block sources are only kept if no entities were filtered out.
+    pub fn parse_document_with(&self, options: &ParseOptions) -> Result<ParsedVmf<'_>, VMFError> {
+        let blocks = self.parse_with(options)?;
+
+        let mut starts = Vec::new();
+        let mut comments = Vec::new();
+        let mut prev_end = 0;
+        for item in scan_blocks(&self.data) {
+            let (span, known) = match item {
+                Ok(block) => (block.span, KNOWN_BLOCKS.contains(&block.name)),
+                Err(ScanError::Stray(span)) => (span, false),
+                Err(ScanError::Unterminated(block)) => (block.span, false),
+            };
+            if known {
+                // Only whitespace and comments lie between blocks, the comments belong to
+                // the block after them
+                let gap = &self.data[prev_end..span.start];
+                let start = gap.find("//").map_or(span.start, |offset| prev_end + offset);
+                starts.push(start);
+                if options.keep_comments {
+                    comments.push(
+                        self.data[start..span.start]
+                            .split(['\r', '\n'])
+                            .map(str::trim)
+                            .filter(|line| line.starts_with("//"))
+                            .collect(),
+                    );
+                }
+            }
+            prev_end = span.end;
+        }
+
+        let sources = block_sources(&self.data, &starts);
+        let mut doc = ParsedVmf::with_sources(blocks, sources, comments);
+        if options.intern_classnames {
+            doc.intern_classnames();
+        }
+        Ok(doc)
     }
 
     /// Get the raw file content as a string slice.
@@ -55,46 +405,952 @@ impl VMF {
     }
 }
 
+impl From<String> for VMF {
+    fn from(data: String) -> Self {
+        VMF { data }
+    }
+}
+
+impl FromStr for VMF {
+    type Err = VMFError;
+
+    /// Copies `src` into a new [`VMF`]. Parsing happens later in [`VMF::parse`].
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(VMF::from(src.to_string()))
+    }
+}
+
+/// Parses VMF source that is already in memory.
+///
+/// This needs no file system access, so it is the entry point for WASM and embedded
+/// targets (build with `default-features = false`). It never panics on malformed input:
+/// bytes the lexer doesn't understand, unbalanced braces and bad values are all reported
+/// as [`VMFError::ParseErrorAt`] with the line and column where parsing failed.
+///
+/// # Example
+/// Exposing the parser to a browser with `wasm-bindgen`:
+/// ```ignore
+/// use wasm_bindgen::prelude::*;
+///
+/// #[wasm_bindgen]
+/// pub fn count_entities(src: &str) -> Result<usize, JsError> {
+///     let blocks = mnk_vmf::parse_vmf(src)?;
+///     Ok(blocks
+///         .iter()
+///         .filter(|block| matches!(block, mnk_vmf::VMFValue::Entity(_)))
+///         .count())
+/// }
+/// ```
+pub fn parse_vmf(src: &str) -> Result<Vec<VMFValue<'_>>, VMFError> {
+    parse_vmf_from_str(src, &ParseOptions::default())
+}
+
+/// Parses geometry and entities copied out of Hammer, like text pasted into a map tool.
+///
+/// Copied objects are a bare sequence of `solid` and `entity` blocks, so unlike
+/// [`parse_vmf`] solids are accepted at the top level and no `world` or `versioninfo` is
+/// needed. The loose solids are collected into one `worldspawn` [`VMFValue::World`] placed
+/// where the first of them was, so the result can go straight into a [`ParsedVmf`]. A pasted
+/// `world` block takes that place instead, and the solids and hidden entities of any
+/// further worlds are merged into it, so the result never holds more than one world. All
+/// other blocks are returned like [`parse_vmf`] returns them.
+///
+/// # Example
+/// ```ignore
+/// let pasted = ParsedVmf::new(mnk_vmf::parse_clipboard(&clipboard_text)?);
+/// for solid in pasted.solids() {
+///     import_brush(solid);
+/// }
+/// ```
+pub fn parse_clipboard(src: &str) -> Result<Vec<VMFValue<'_>>, VMFError> {
+    enum Pasted<'src> {
+        Solid(Solid<'src>),
+        Block(Option<VMFValue<'src>>),
+    }
+
+    let pasted = choice((
+        Solid::parser().map(Pasted::Solid),
+        known_block(None).map(Pasted::Block),
+        skip_unknown_block(&PASTED_BLOCKS).map(|_| Pasted::Block(None)),
+    ))
+    .repeated()
+    .collect::<Vec<_>>()
+    .parse(spanned_tokens(src))
+    .into_result()
+    .map_err(|errors| parse_error_at(src, 0, errors))?;
+
+    let mut blocks = Vec::new();
+    let mut world = None;
+    for item in pasted {
+        match item {
+            Pasted::Block(Some(VMFValue::World(pasted))) => match world {
+                Some(index) => {
+                    if let VMFValue::World(world) = &mut blocks[index] {
+                        world.solids.extend(pasted.solids);
+                        world.hidden_entities.extend(pasted.hidden_entities);
+                    }
+                }
+                None => {
+                    world = Some(blocks.len());
+                    blocks.push(VMFValue::World(pasted));
+                }
+            },
+            Pasted::Block(block) => blocks.extend(block),
+            Pasted::Solid(solid) => {
+                let index = *world.get_or_insert_with(|| {
+                    blocks.push(VMFValue::World(Box::new(World {
+                        classname: "worldspawn".into(),
+                        ..Default::default()
+                    })));
+                    blocks.len() - 1
+                });
+                if let VMFValue::World(world) = &mut blocks[index] {
+                    world.solids.push(solid);
+                }
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// Parses VMF data from a reader one top-level block at a time.
+///
+/// Only the block being parsed is kept in memory, so the peak memory use is bounded by the
+/// largest block (usually `world`) instead of the whole file. This suits very large maps and
+/// 32-bit targets where loading everything into one buffer isn't an option. Each parsed
+/// block is handed to `on_block` and borrows from a buffer that is reused for the next one,
+/// so keep what you need with `into_owned` or by copying it out.
+///
+/// # Example
+/// ```ignore
+/// let reader = BufReader::new(File::open("mymap.vmf")?);
+/// let mut entities = 0;
+/// parse_vmf_reader(reader, |block| {
+///     if let VMFValue::Entity(_) = block {
+///         entities += 1;
+///     }
+/// })?;
+/// ```
+pub fn parse_vmf_reader<R: BufRead>(
+    mut reader: R,
+    mut on_block: impl FnMut(VMFValue<'_>),
+) -> Result<(), VMFError> {
+    let mut line = String::new();
+    let mut chunk = String::new();
+    let mut depth = 0usize;
+    let mut in_quote = false;
+    let mut in_comment = false;
+    let mut escaped = false;
+    // Where the current chunk starts in the whole input, to report errors at file positions
+    let mut chunk_start = (1, 1);
+
+    let mut flush = |chunk: &mut String| -> Result<(), VMFError> {
+        let blocks = parse_vmf_from_str(chunk, &ParseOptions::default()).map_err(|err| match err {
+            VMFError::ParseErrorAt {
+                line,
+                column,
+                message,
+            } => VMFError::ParseErrorAt {
+                line: line + chunk_start.0 - 1,
+                column: if line == 1 { column + chunk_start.1 - 1 } else { column },
+                message,
+            },
+            err => err,
+        })?;
+        blocks.into_iter().for_each(&mut on_block);
+        chunk_start = advance(chunk_start, chunk);
+        chunk.clear();
+        Ok(())
+    };
+
+    while reader.read_line(&mut line)? > 0 {
+        let mut start = 0;
+        for (i, c) in line.char_indices() {
+            match c {
+                // A lone `\r` ends the line too, read_line only splits on `\n`
+                '\r' | '\n' if in_comment => in_comment = false,
+                _ if in_comment => {}
+                _ if escaped => escaped = false,
+                '\\' if in_quote => escaped = true,
+                '"' => in_quote = !in_quote,
+                // The rest of the line is a comment, braces and quotes in it don't count
+                '/' if !in_quote && line[i..].starts_with("//") => in_comment = true,
+                '{' if !in_quote => depth += 1,
+                '}' if !in_quote => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        // A top-level block (or a stray brace, which fails to parse) ends here
+                        chunk.push_str(&line[start..=i]);
+                        flush(&mut chunk)?;
+                        start = i + 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        chunk.push_str(&line[start..]);
+        line.clear();
+    }
+
+    // Anything left over is an unterminated block or stray input
+    if !chunk.trim().is_empty() {
+        flush(&mut chunk)?;
+    }
+    Ok(())
+}
+
 /// Parse VMF data from a string slice.
 /// Uses a sequential parser that handles all top-level blocks in order.
-fn parse_vmf_from_str<'src>(src: &'src str) -> Result<Vec<VMFValue<'src>>, VMFError> {
-    let token_iter = TokenIter::new(src).map(|tok| tok.expect("valid token"));
-    let token_stream = Stream::from_iter(token_iter);
-
-    let any_block = choice((
-        VersionInfo::parser().map(VMFValue::VersionInfo),
-        VisGroups::parser().map(|v| VMFValue::VisGroups(Box::new(v))),
-        ViewSettings::parser().map(|v| VMFValue::ViewSettings(Box::new(v))),
-        World::parser().map(|v| VMFValue::World(Box::new(v))),
-        Entity::parser().map(|v| VMFValue::Entity(Box::new(v))),
-        Cameras::parser().map(|v| VMFValue::Cameras(Box::new(v))),
-        Cordon::parser().map(|v| VMFValue::Cordon(Box::new(v))),
-    ));
-
-    let any_block = any_block
-        .map(|v| Some(v))
-        .or(skip_unknown_block().map(|_| None));
+fn parse_vmf_from_str<'src>(
+    src: &'src str,
+    options: &ParseOptions,
+) -> Result<Vec<VMFValue<'src>>, VMFError> {
+    parse_tokens(src, spanned_tokens(src), options)
+}
+
+/// Lexes `src` into parser input whose spans are byte offsets into `src`.
+///
+/// Bytes the lexer can't make sense of become `Token::Error`, which no block parser accepts.
+fn spanned_tokens(src: &str) -> impl TokenSource<'_> {
+    let tokens = TokenIter::new(src)
+        .spanned()
+        .map(|(token, span)| (token, SimpleSpan::from(span)));
+    Stream::from_iter(tokens).map((src.len()..src.len()).into(), |(token, span)| (token, span))
+}
+
+/// Parses all top-level blocks from the lexed tokens of `src`.
+fn parse_tokens<'src, I>(
+    src: &str,
+    token_stream: I,
+    options: &ParseOptions,
+) -> Result<Vec<VMFValue<'src>>, VMFError>
+where
+    I: TokenSource<'src>,
+{
+    // Stray tokens are only skipped in lenient mode, otherwise they fail the parse. They
+    // are skipped one at a time, so a block right after them is parsed as usual.
+    let allow_trailing = options.allow_trailing;
+    let stray = any().filter(move |_| allow_trailing).map(|_| None);
+
+    let any_block = known_block(options.entity_filter.as_deref())
+        .or(skip_unknown_block(&KNOWN_BLOCKS).map(|_| None))
+        .or(stray);
 
     let all_blocks_parser = any_block.repeated().collect::<Vec<_>>();
 
-    all_blocks_parser
+    let mut blocks: Vec<_> = all_blocks_parser
         .parse(token_stream)
         .into_result()
         .map(|blocks| blocks.into_iter().flatten().collect())
-        .map_err(|errors| {
-            let error_msg = errors
-                .into_iter()
-                .map(|e| format!("{:?}", e.reason()))
-                .collect::<Vec<_>>()
-                .join("; ");
-            VMFError::ParseError(format!("Failed to parse VMF: {}", error_msg))
-        })
+        .map_err(|errors| parse_error_at(src, 0, errors))?;
+
+    if let Some(max_rows) = options.max_disp_rows {
+        blocks
+            .iter()
+            .try_for_each(|block| check_disp_rows(block, max_rows))?;
+    }
+    if options.normalize_materials {
+        blocks.iter_mut().for_each(normalize_materials);
+    }
+    Ok(blocks)
+}
+
+/// Fails if a displacement in `block` has a per-vertex block with more values than
+/// `max_rows` rows of `max_rows` vertices hold.
+fn check_disp_rows(block: &VMFValue, max_rows: usize) -> Result<(), VMFError> {
+    let solids: Box<dyn Iterator<Item = &Solid>> = match block {
+        VMFValue::World(world) => Box::new(world.solids.iter().chain(
+            world.hidden_entities.iter().flat_map(|entity| entity.solids.iter()),
+        )),
+        VMFValue::Entity(entity) => Box::new(entity.solids.iter()),
+        _ => return Ok(()),
+    };
+
+    let max_values = max_rows.saturating_mul(max_rows);
+    for side in solids.flat_map(|solid| solid.sides.iter()) {
+        let Some(dispinfo) = &side.dispinfo else {
+            continue;
+        };
+        let blocks = [
+            ("normals", dispinfo.normals.len()),
+            ("distances", dispinfo.distances.len()),
+            ("offsets", dispinfo.offsets.len()),
+            ("offset_normals", dispinfo.offset_normals.len()),
+            ("alphas", dispinfo.alphas.len()),
+        ];
+        if let Some((name, len)) = blocks.into_iter().find(|(_, len)| *len > max_values) {
+            return Err(VMFError::ParseError(format!(
+                "displacement on side {} has {} {} values, at most {} rows ({} values) are allowed",
+                side.id, len, name, max_rows, max_values
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercases the material of every side and the model of every entity in `block`,
+/// including the hidden entities of a world.
+fn normalize_materials(block: &mut VMFValue) {
+    fn lowercase(value: &mut Cow<str>) {
+        // Only allocate for values that actually change
+        if value.chars().any(char::is_uppercase) {
+            *value = Cow::Owned(value.to_lowercase());
+        }
+    }
+
+    fn normalize_solids(solids: &mut [Solid]) {
+        for side in solids.iter_mut().flat_map(|solid| solid.sides.iter_mut()) {
+            lowercase(&mut side.material);
+        }
+    }
+
+    fn normalize_entity(entity: &mut Entity) {
+        if let Some(model) = &mut entity.model {
+            lowercase(model);
+        }
+        normalize_solids(&mut entity.solids);
+    }
+
+    match block {
+        VMFValue::World(world) => {
+            normalize_solids(&mut world.solids);
+            world.hidden_entities.iter_mut().for_each(normalize_entity);
+        }
+        VMFValue::Entity(entity) => normalize_entity(entity),
+        _ => {}
+    }
+}
+
+/// Finds the problems reported by [`VMF::parse_with_warnings`] in a single pass over the
+/// tokens of `src`.
+fn collect_warnings(src: &str) -> Vec<ParseWarning> {
+    struct Frame<'src> {
+        name: &'src str,
+        keys: Vec<&'src str>,
+    }
+
+    let mut warnings = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut block_name = None;
+    let mut pending_key: Option<(&str, Range<usize>)> = None;
+
+    for (token, span) in TokenIter::new(src).spanned() {
+        match token {
+            Token::Ident(name) => {
+                if frames.is_empty() && !KNOWN_BLOCKS.contains(&name) {
+                    warnings.push(ParseWarning {
+                        block: name.to_string(),
+                        span,
+                        message: format!("unknown block `{}` is skipped", name),
+                    });
+                }
+                block_name = Some(name);
+            }
+            Token::LBracket => {
+                let name = block_name.take().unwrap_or_default();
+                if frames.last().is_some_and(|frame| is_connections(frame.name)) {
+                    warnings.push(ParseWarning {
+                        block: "connections".to_string(),
+                        span: span.clone(),
+                        message: format!("block `{}` in connections is skipped", name),
+                    });
+                }
+                frames.push(Frame {
+                    name,
+                    keys: Vec::new(),
+                });
+                pending_key = None;
+            }
+            Token::RBracket => {
+                frames.pop();
+                pending_key = None;
+            }
+            Token::QuotedText(text) => match (pending_key.take(), frames.last_mut()) {
+                (None, _) => pending_key = Some((text, span)),
+                (Some((key, key_span)), Some(frame)) => {
+                    let connection = is_connections(frame.name);
+                    if connection
+                        && let Err(err) = EntityOutput::parse_output_string(key, text)
+                    {
+                        warnings.push(ParseWarning {
+                            block: frame.name.to_string(),
+                            span: key_span.start..span.end,
+                            message: format!("output `{}` is skipped: {}", key, err),
+                        });
+                    }
+                    let repeatable = connection || key.eq_ignore_ascii_case("visgroupid");
+                    // Only these blocks match their keys case-insensitively, elsewhere
+                    // `Origin` and `origin` are separate keys
+                    let ignore_case = matches!(frame.name, "editor" | "viewsettings");
+                    let same_key = |k: &&str| {
+                        if ignore_case {
+                            k.eq_ignore_ascii_case(key)
+                        } else {
+                            *k == key
+                        }
+                    };
+                    if !repeatable && frame.keys.iter().any(same_key) {
+                        warnings.push(ParseWarning {
+                            block: frame.name.to_string(),
+                            span: key_span.start..span.end,
+                            message: format!("duplicate key `{}`, the last value is used", key),
+                        });
+                    }
+                    frame.keys.push(key);
+                }
+                (Some(_), None) => {}
+            },
+            _ => {}
+        }
+    }
+
+    warnings
+}
+
+fn is_connections(block: &str) -> bool {
+    block.eq_ignore_ascii_case("connections")
+}
+
+/// Names of the top-level blocks [`known_block`] understands.
+const KNOWN_BLOCKS: [&str; 7] = [
+    "versioninfo",
+    "visgroups",
+    "viewsettings",
+    "world",
+    "entity",
+    "cameras",
+    "cordon",
+];
+
+/// Blocks [`parse_clipboard`] understands at the top level, [`KNOWN_BLOCKS`] and loose
+/// solids.
+const PASTED_BLOCKS: [&str; 8] = [
+    "solid",
+    "versioninfo",
+    "visgroups",
+    "viewsettings",
+    "world",
+    "entity",
+    "cameras",
+    "cordon",
+];
+
+/// Parser for any of the top-level blocks in [`KNOWN_BLOCKS`].
+/// Yields `None` for entities rejected by `entity_filter`.
+fn known_block<'src, 'a, I>(
+    entity_filter: Option<&'a dyn Fn(&str) -> bool>,
+) -> impl ChumskyParser<'src, I, Option<VMFValue<'src>>, TokenError<'src>> + 'a
+where
+    I: TokenSource<'src>,
+    'src: 'a,
+{
+    choice((
+        VersionInfo::parser().map(|v| Some(VMFValue::VersionInfo(v))),
+        VisGroups::parser().map(|v| Some(VMFValue::VisGroups(Box::new(v)))),
+        ViewSettings::parser().map(|v| Some(VMFValue::ViewSettings(Box::new(v)))),
+        World::parser().map(|v| Some(VMFValue::World(Box::new(v)))),
+        Entity::parser().map(move |v| match entity_filter {
+            Some(filter) if !filter(&v.classname) => None,
+            _ => Some(VMFValue::Entity(Box::new(v))),
+        }),
+        Cameras::parser().map(|v| Some(VMFValue::Cameras(Box::new(v)))),
+        Cordon::parser().map(|v| Some(VMFValue::Cordon(Box::new(v)))),
+    ))
+}
+
+/// Parses the single top-level block `name` at `span` of `src`, for
+/// [`VMF::parse_recovering`] and [`VMF::reparse_block`].
+///
+/// Known blocks are parsed strictly, so a malformed one is reported instead of being
+/// skipped like an unknown block. Error positions are relative to the whole of `src`.
+fn parse_single_block<'src>(
+    name: &str,
+    src: &'src str,
+    span: Range<usize>,
+) -> Result<Option<VMFValue<'src>>, VMFError> {
+    if !KNOWN_BLOCKS.contains(&name) {
+        return Ok(None);
+    }
+
+    known_block(None)
+        .then_ignore(end())
+        .parse(spanned_tokens(&src[span.clone()]))
+        .into_result()
+        .map_err(|errors| parse_error_at(src, span.start, errors))
+}
+
+/// Converts parser errors over [`spanned_tokens`] of the part of `src` starting at byte
+/// `start` into a [`VMFError::ParseErrorAt`] at the first error.
+fn parse_error_at(src: &str, start: usize, errors: Vec<Rich<'_, Token<'_>>>) -> VMFError {
+    let offset = errors.first().map_or(src.len(), |error| start + error.span().start);
+    let message = errors
+        .iter()
+        .map(|e| format!("{:?}", e.reason()))
+        .collect::<Vec<_>>()
+        .join("; ");
+    error_at(src, offset, format!("Failed to parse VMF: {}", message))
+}
+
+/// Builds a [`VMFError::ParseErrorAt`] pointing at byte `offset` of `src`.
+pub(crate) fn error_at(src: &str, offset: usize, message: String) -> VMFError {
+    let (line, column) = advance((1, 1), src.get(..offset).unwrap_or(src));
+    VMFError::ParseErrorAt {
+        line,
+        column,
+        message,
+    }
+}
+
+/// Moves the 1-based `(line, column)` position past `text`. `\n`, `\r\n` and a lone `\r`
+/// all end a line.
+fn advance((mut line, mut column): (usize, usize), text: &str) -> (usize, usize) {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                line += 1;
+                column = 1;
+            }
+            _ => column += 1,
+        }
+    }
+    (line, column)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_from_str() {
+        // Goes through the in-memory path only, so this also runs without the `fs` feature.
+        let vmf: VMF = include_str!("../test.vmf").parse().unwrap();
+        let data = vmf.parse().expect("Failed to parse VMF");
+
+        verify_parsed_data(&data);
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        let src = r#"
+        solid
+        {
+            "id" "10"
+            side
+            {
+                "id" "11"
+                "plane" "(0 0 64) (64 0 64) (64 -64 64)"
+                "material" "DEV/DEV_BLENDMEASURE"
+            }
+            editor { "color" "0 180 0" "visgroupshown" "1" "visgroupautoshown" "1" }
+        }
+        entity
+        {
+            "id" "20"
+            "classname" "light"
+            "origin" "32 -32 96"
+            "_light" "255 255 255 200"
+        }
+        solid
+        {
+            "id" "12"
+            side
+            {
+                "id" "13"
+                "plane" "(0 0 0) (64 0 0) (64 64 0)"
+                "material" "TOOLS/TOOLSNODRAW"
+            }
+        }
+        "#;
+
+        let blocks = parse_clipboard(src).unwrap();
+        assert_eq!(blocks.len(), 2);
+        let VMFValue::World(world) = &blocks[0] else {
+            panic!("expected the pasted solids in a world, got {:?}", blocks[0]);
+        };
+        assert_eq!(world.classname, "worldspawn");
+        let solids: Vec<_> = world.solids.iter().map(|solid| solid.id).collect();
+        assert_eq!(solids, [10, 12]);
+        let VMFValue::Entity(entity) = &blocks[1] else {
+            panic!("expected an entity, got {:?}", blocks[1]);
+        };
+        assert_eq!(entity.id, 20);
+        assert_eq!(entity.get_property("_light"), Some("255 255 255 200"));
+
+        // A bare solid isn't a valid top-level block of a whole file
+        assert!(parse_vmf(src).unwrap().iter().all(|b| !matches!(b, VMFValue::World(_))));
+        assert!(parse_clipboard("solid { \"id\" \"1\"").is_err());
+        assert!(parse_clipboard("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_clipboard_with_world() {
+        let src = r#"
+        solid { "id" "10" side { "id" "11" } }
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            "skyname" "sky_day01_01"
+            solid { "id" "2" side { "id" "3" } }
+        }
+        solid { "id" "12" side { "id" "13" } }
+        world { "id" "5" "classname" "worldspawn" solid { "id" "6" side { "id" "7" } } }
+        "#;
+
+        let doc = ParsedVmf::new(parse_clipboard(src).unwrap());
+        assert_eq!(doc.blocks.len(), 1);
+        let world = doc.world().unwrap();
+        let solids: Vec<_> = world.solids.iter().map(|solid| solid.id).collect();
+        assert_eq!(solids, [10, 2, 12, 6]);
+        // Only the geometry of a world that isn't first is kept
+        assert_eq!(world.skybox(), None);
+
+        let blocks = parse_clipboard(&src[src.find("world").unwrap()..]).unwrap();
+        let VMFValue::World(world) = &blocks[0] else {
+            panic!("expected the pasted world, got {:?}", blocks[0]);
+        };
+        assert_eq!(world.id, 1);
+        let solids: Vec<_> = world.solids.iter().map(|solid| solid.id).collect();
+        assert_eq!(solids, [2, 12, 6]);
+    }
+
+    #[test]
+    fn test_parse_vmf_malformed_input_returns_err() {
+        let inputs = [
+            "world\n{\n\t\"id\" \"1\"",
+            "world { \"id\" \"unterminated }",
+            "versioninfo { \"editorversion\" \"400\" } }",
+            "\u{0}\u{1}\u{7f} @@@ #",
+            "entity { \"id\" \"1\" } extra",
+            "}{",
+        ];
+
+        for input in inputs {
+            assert!(
+                parse_vmf(input).is_err(),
+                "{:?} should fail to parse",
+                input
+            );
+        }
+        assert!(parse_vmf("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let position = |src: &str| match parse_vmf(src).unwrap_err() {
+            VMFError::ParseErrorAt { line, column, .. } => (line, column),
+            err => panic!("expected a positioned error, got {:?}", err),
+        };
+
+        // Unexpected end of input, after the last character
+        assert_eq!(position("world\n{\n\t\"id\" \"1\""), (3, 10));
+        // A stray token on the next line
+        assert_eq!(position("entity { \"id\" \"1\" }\n  extra"), (2, 8));
+        // CRLF and lone CR both end a line
+        assert_eq!(position("entity\r\n{\r\n}\r\n}"), (4, 1));
+        assert_eq!(position("entity\r{\r}\r}"), (4, 1));
+
+        let err = parse_vmf("}").unwrap_err();
+        assert!(err.to_string().starts_with("1:1: Failed to parse VMF"));
+    }
+
+    #[test]
+    fn test_parse_vmf_reader_error_position() {
+        let src = "world\n{\n\t\"id\" \"1\"\n}\nentity { \"id\" \"2\" } }\n";
+        let expected = parse_vmf(src).unwrap_err().to_string();
+        assert!(expected.starts_with("5:21:"), "{}", expected);
+
+        let reader = std::io::BufReader::new(src.as_bytes());
+        let err = parse_vmf_reader(reader, |_| {}).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_parse_vmf_reader() {
+        let src = include_str!("../test.vmf");
+        let reader = std::io::BufReader::new(src.as_bytes());
+
+        let expected = parse_vmf(src).unwrap();
+        let mut count = 0;
+        parse_vmf_reader(reader, |block| {
+            assert_eq!(block, expected[count]);
+            count += 1;
+        })
+        .unwrap();
+        assert_eq!(count, expected.len());
+
+        // Blocks sharing a line, braces inside values and unknown blocks
+        let src = concat!(
+            "entity { \"id\" \"1\" \"classname\" \"game_text\" \"message\" \"}{\" } custom { \"a\" \"b\" }\n",
+            "entity\n{\n\t\"id\" \"2\"\n\t\"classname\" \"light\"\n}\n",
+        );
+        let mut ids = Vec::new();
+        parse_vmf_reader(src.as_bytes(), |block| {
+            if let VMFValue::Entity(entity) = block {
+                ids.push(entity.id);
+            }
+        })
+        .unwrap();
+        assert_eq!(ids, vec![1, 2]);
+
+        for input in ["world\n{\n\t\"id\" \"1\"\n", "entity { \"id\" \"1\" } }", "extra"] {
+            assert!(
+                parse_vmf_reader(input.as_bytes(), |_| {}).is_err(),
+                "{:?} should fail to parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_timed() {
+        let vmf: VMF = include_str!("../test.vmf").parse().unwrap();
+        let (data, timing) = vmf.parse_timed().expect("Failed to parse VMF");
+
+        assert_eq!(data, vmf.parse().unwrap());
+        assert!(timing.tokenize > Duration::ZERO);
+        assert!(timing.parse > Duration::ZERO);
+        assert_eq!(timing.total(), timing.tokenize + timing.parse);
+    }
+
+    #[test]
+    fn test_parse_recovering() {
+        let src = r#"
+        entity
+        {
+            "id" "2"
+            "classname" "info_player_start"
+        }
+        entity
+        {
+            "id" "3"
+            "classname" "light"
+            origin "0 0 64"
+        }
+        }
+        entity
+        {
+            "id" "4"
+            "classname" "prop_static"
+        }
+        "#;
+        let vmf: VMF = src.parse().unwrap();
+        assert!(vmf.parse().is_err());
+
+        let (blocks, diagnostics) = vmf.parse_recovering();
+        let ids: Vec<_> = blocks
+            .iter()
+            .filter_map(|value| match value {
+                VMFValue::Entity(e) => Some(e.id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec![2, 4]);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].block.as_deref(), Some("entity"));
+        assert!(src[diagnostics[0].span.clone()].contains("origin \"0 0 64\""));
+        assert!(diagnostics[0].message.starts_with("11:13:"), "{}", diagnostics[0].message);
+        assert!(matches!(
+            vmf.reparse_block(diagnostics[0].span.clone()),
+            Err(VMFError::ParseErrorAt { line: 11, column: 13, .. })
+        ));
+        assert_eq!(diagnostics[1].block, None);
+        assert_eq!(&src[diagnostics[1].span.clone()], "}");
+    }
+
+    #[test]
+    fn test_validate() {
+        let src = include_str!("../test.vmf");
+        let vmf: VMF = src.parse().unwrap();
+        assert!(vmf.validate().is_ok());
+
+        // Cut at a line break so only the closing braces are missing
+        let cut = src[..src.len() / 2].rfind('\n').unwrap();
+        let truncated: VMF = src[..cut].parse().unwrap();
+        let err = truncated.validate().unwrap_err();
+        assert!(
+            err.to_string().contains("missing its closing brace"),
+            "{}",
+            err
+        );
+
+        let inputs = [
+            "entity { \"id\" \"1\" } }",
+            "entity \"id\" \"1\"",
+            "{ }",
+            "entity { \"id\" \"unterminated }",
+            "entity { @ }",
+        ];
+        for input in inputs {
+            let vmf: VMF = input.parse().unwrap();
+            assert!(vmf.validate().is_err(), "{:?} should not validate", input);
+        }
+
+        let stray: VMF = "world\n{\n}\n  }".parse().unwrap();
+        assert!(matches!(
+            stray.validate(),
+            Err(VMFError::ParseErrorAt { line: 4, column: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_index() {
+        let vmf: VMF = include_str!("../test.vmf").parse().unwrap();
+        let index = vmf.index();
+
+        let names: Vec<_> = index.iter().map(|block| block.name.as_str()).collect();
+        let mut expected = vec!["versioninfo", "visgroups", "viewsettings", "world"];
+        expected.extend(["entity"; 11]);
+        expected.extend(["cameras", "cordon"]);
+        assert_eq!(names, expected);
+
+        let blocks = vmf.parse().unwrap();
+        assert_eq!(index.len(), blocks.len());
+        for (i, block) in blocks.iter().enumerate() {
+            let span = index.get(i).unwrap().span.clone();
+            assert_eq!(vmf.reparse_block(span).unwrap().as_ref(), Some(block));
+        }
+
+        assert!(index.get(index.len()).is_none());
+        assert!(vmf.reparse_block(0..vmf.as_str().len() + 1).is_err());
+        let world = index.get(3).unwrap().span.clone();
+        assert!(vmf.reparse_block(world.start + "world".len()..world.end).is_err());
+    }
+
+    #[test]
+    fn test_cr_line_endings() {
+        use crate::writer::{SerializeOptions, ToVmf};
+
+        let lf = include_str!("../test.vmf").replace("\r\n", "\n");
+        let lf = format!("// header\n{}", lf.replacen("world", "// the map\nworld", 1));
+        let cr = lf.replace('\n', "\r");
+
+        let tokens: Vec<_> = TokenIter::new(&cr).collect();
+        assert_eq!(tokens, TokenIter::new(&lf).collect::<Vec<_>>());
+
+        let expected = parse_vmf(&lf).unwrap();
+        assert_eq!(parse_vmf(&cr).unwrap(), expected);
+
+        // Streamed blocks borrow from the reader, compare them as text
+        let sorted = SerializeOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+        let mut streamed = Vec::new();
+        parse_vmf_reader(cr.as_bytes(), |block| streamed.push(block.to_vmf_with(&sorted)))
+            .unwrap();
+        let expected_text: Vec<_> = expected.iter().map(|b| b.to_vmf_with(&sorted)).collect();
+        assert_eq!(streamed, expected_text);
+
+        let vmf: VMF = cr.parse().unwrap();
+        let options = ParseOptions {
+            keep_comments: true,
+            ..Default::default()
+        };
+        let doc = vmf.parse_document_with(&options).unwrap();
+        assert_eq!(doc.leading_comments(0), ["// header"]);
+        assert_eq!(doc.leading_comments(3), ["// the map"]);
+
+        // Written back out with `\n` line endings, the map parses the same again
+        assert_eq!(parse_vmf(&doc.to_vmf()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_braces_inside_quoted_values() {
+        let vmf: VMF = r#"
+        entity
+        {
+            "id" "1"
+            "classname" "game_text"
+            "message" "score}"
+            "targetname" "{hud"
+        }
+        entity
+        {
+            "id" "2"
+            "classname" "light"
+        }
+        "#
+        .parse()
+        .unwrap();
+
+        assert!(vmf.validate().is_ok());
+        assert_eq!(vmf.index().len(), 2);
+        let (_, diagnostics) = vmf.parse_recovering();
+        assert!(diagnostics.is_empty());
+
+        let doc = vmf.parse_document().unwrap();
+        let entities: Vec<_> = doc.entities().collect();
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].properties["message"], "score}");
+        assert_eq!(entities[0].targetname.as_deref(), Some("{hud"));
+        assert!(doc.block_source(0).unwrap().contains("\"score}\""));
+    }
+
+    #[test]
+    fn test_parse_with_warnings() {
+        let vmf: VMF = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            "id" "2"
+        }
+        entity
+        {
+            "id" "3"
+            "classname" "logic_relay"
+            "Note" "a"
+            "note" "b"
+            connections
+            {
+                "OnTrigger" "a,Enable,,0,-1"
+                "OnTrigger" "b,Enable,,0,-1"
+            }
+            editor
+            {
+                "visgroupid" "1"
+                "visgroupid" "2"
+                "groupid" "5"
+                "GroupId" "6"
+            }
+        }
+        custom { "a" "b" }
+        "#
+        .parse()
+        .unwrap();
+
+        let (blocks, warnings) = vmf.parse_with_warnings().unwrap();
+        assert_eq!(blocks.len(), 2);
+        match &blocks[0] {
+            VMFValue::World(world) => assert_eq!(world.id, 2),
+            other => panic!("expected the world, got {:?}", other),
+        }
+
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert_eq!(warnings[0].block, "world");
+        assert!(warnings[0].message.contains("duplicate key `id`"));
+        assert_eq!(&vmf.as_str()[warnings[0].span.clone()], r#""id" "2""#);
+        assert_eq!(warnings[1].block, "editor");
+        assert!(warnings[1].message.contains("duplicate key `GroupId`"));
+        assert_eq!(warnings[2].block, "custom");
+
+        let clean: VMF = include_str!("../test.vmf").parse().unwrap();
+        assert_eq!(clean.parse_with_warnings().unwrap().1, []);
+    }
+
+    #[test]
+    fn test_parse_recovering_matches_parse() {
+        let vmf: VMF = include_str!("../test.vmf").parse().unwrap();
+        let (blocks, diagnostics) = vmf.parse_recovering();
+        assert!(diagnostics.is_empty());
+        assert_eq!(blocks, vmf.parse().unwrap());
+    }
+
+    #[cfg(feature = "fs")]
     #[test]
     fn full_parser_test() {
         let vmf = VMF::open("test.vmf").expect("Failed to open VMF");
@@ -134,6 +1390,173 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_parse_with_entity_filter() {
+        let vmf = VMF::open("test.vmf").expect("Failed to open VMF");
+        let options = ParseOptions {
+            entity_filter: Some(Box::new(|classname| classname == "light")),
+            ..Default::default()
+        };
+        let data = vmf.parse_with(&options).expect("Failed to parse VMF");
+
+        let classnames: Vec<_> = data
+            .iter()
+            .filter_map(|value| match value {
+                VMFValue::Entity(e) => Some(&*e.classname),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(classnames, vec!["light"; 4]);
+        assert!(data.iter().any(|value| matches!(value, VMFValue::World(_))));
+    }
+
+    #[test]
+    fn test_parse_with_trailing_garbage() {
+        let mut data = include_str!("../test.vmf").to_string();
+        let expected = VMF { data: data.clone() }.parse().unwrap().len();
+        data.push_str("\n}}\u{1} \"unterminated ) garbage");
+        let vmf = VMF { data };
+
+        assert!(vmf.parse().is_err());
+
+        let options = ParseOptions {
+            allow_trailing: true,
+            ..Default::default()
+        };
+        let blocks = vmf
+            .parse_with(&options)
+            .expect("Trailing content should be ignored");
+        assert_eq!(blocks.len(), expected);
+    }
+
+    #[test]
+    fn test_parse_with_max_disp_rows() {
+        // A power 2 displacement, 5 rows of 5 distances
+        let rows: String = (0..5).map(|i| format!(r#""row{}" "0 0 0 0 0" "#, i)).collect();
+        let src = format!(
+            r#"world
+            {{
+                "id" "1"
+                "classname" "worldspawn"
+                solid {{ "id" "2" side {{ "id" "3" dispinfo {{ "power" "2" distances {{ {} }} }} }} }}
+            }}"#,
+            rows
+        );
+        let vmf: VMF = src.parse().unwrap();
+        let with_limit = |max_disp_rows| ParseOptions {
+            max_disp_rows,
+            ..Default::default()
+        };
+
+        assert!(vmf.parse().is_ok());
+        assert!(vmf.parse_with(&with_limit(Some(5))).is_ok());
+        let err = vmf.parse_with(&with_limit(Some(4))).unwrap_err();
+        assert!(err.to_string().contains("side 3 has 25 distances values"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_known_blocks() {
+        let world = |body: &str| {
+            format!(
+                r#"world {{ "id" "1" "classname" "worldspawn" {} }}
+                entity {{ "id" "9" "classname" "info_target" }}"#,
+                body
+            )
+        };
+        let inputs = [
+            world(r#"solid { "id" "2" side { "id" "3" "plane" "(0 0 0) (0 64" } }"#),
+            world(r#"solid { "id" "2" side { "id" "3" dispinfo { "power" "x" } } }"#),
+            world(r#"hidden { solid { "id" "two" } }"#),
+            world(r#"hidden { entity { "id" "4" connections { "OnTrigger" } } }"#),
+        ];
+        for input in inputs {
+            let vmf: VMF = input.parse().unwrap();
+            assert!(vmf.parse().is_err(), "{} should not parse", input);
+        }
+
+        // Inside an unknown block, known names are skipped along with it
+        let vmf: VMF = format!(r#"{} custom {{ world {{ "id" "x" }} }}"#, world(""))
+            .parse()
+            .unwrap();
+        assert_eq!(vmf.parse().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_with_garbage_between_blocks() {
+        let vmf: VMF = r#"
+        world { "id" "1" "classname" "worldspawn" }
+        "stray" } @@ junk {
+        entity { "id" "2" "classname" "light" }
+        "#
+        .parse()
+        .unwrap();
+        assert!(vmf.parse().is_err());
+
+        let options = ParseOptions {
+            allow_trailing: true,
+            ..Default::default()
+        };
+        let blocks = vmf.parse_with(&options).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(&blocks[0], VMFValue::World(world) if world.id == 1));
+        assert!(matches!(&blocks[1], VMFValue::Entity(entity) if entity.id == 2));
+    }
+
+    #[test]
+    fn test_parse_with_normalize_materials() {
+        let vmf: VMF = r#"
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side { "id" "3" "material" "DEV/Dev_Measure" }
+            }
+        }
+        entity
+        {
+            "id" "4"
+            "classname" "prop_static"
+            "model" "Models/Props/Crate.mdl"
+        }
+        "#
+        .parse()
+        .unwrap();
+
+        let material_and_model = |blocks: Vec<VMFValue>| {
+            let doc = ParsedVmf::new(blocks);
+            let material = doc.sides().next().unwrap().1.material.to_string();
+            let model = doc
+                .entities()
+                .next()
+                .unwrap()
+                .model
+                .as_deref()
+                .map(str::to_string);
+            (material, model)
+        };
+
+        let options = ParseOptions {
+            normalize_materials: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            material_and_model(vmf.parse_with(&options).unwrap()),
+            (
+                "dev/dev_measure".to_string(),
+                Some("models/props/crate.mdl".to_string())
+            )
+        );
+        assert_eq!(
+            material_and_model(vmf.parse().unwrap()).0,
+            "DEV/Dev_Measure"
+        );
+    }
+
+    #[cfg(feature = "fs")]
     #[test]
     fn test_large_real_map() {
         let path = Path::new("Gm_RunDownTown.vmf");
@@ -0,0 +1,561 @@
+use std::fmt;
+
+use super::{ToVmf, VmfWriter};
+use crate::{
+    document::ParsedVmf,
+    types::{
+        Camera, Cameras, Color, Cordon, DispInfo, EditorData, Entity, EntityOutput, Group,
+        Point3D, Properties, Side, Solid, TextureAxis, VersionInfo, ViewSettings, VisGroup,
+        VisGroups, World,
+    },
+    VMFValue, VmfBlock,
+};
+
+fn color(color: Color) -> String {
+    format!("{} {} {}", color.r, color.g, color.b)
+}
+
+fn plane(w: &VmfWriter<'_>, (p1, p2, p3): (Point3D, Point3D, Point3D)) -> String {
+    format!("({}) ({}) ({})", w.point(p1), w.point(p2), w.point(p3))
+}
+
+fn texture_axis(w: &VmfWriter<'_>, axis: &TextureAxis) -> String {
+    format!(
+        "[{} {} {} {}] {}",
+        w.float(axis.x),
+        w.float(axis.y),
+        w.float(axis.z),
+        w.float(axis.shift),
+        w.float(axis.scale)
+    )
+}
+
+/// Writes custom keyvalues in source order, or sorted by key if
+/// [`sort_keys`](super::SerializeOptions::sort_keys) is set.
+fn properties(w: &mut VmfWriter<'_>, properties: &Properties<'_>) -> fmt::Result {
+    let mut properties: Vec<_> = properties.iter().collect();
+    if w.options.sort_keys {
+        properties.sort_unstable();
+    }
+    properties
+        .into_iter()
+        .try_for_each(|(key, value)| w.key_value(key, value))
+}
+
+impl ToVmf for ParsedVmf<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        for (index, block) in self.blocks.iter().enumerate() {
+            match self.block_source(index).filter(|_| w.options.preserve_source) {
+                Some(source) => w.raw(source)?,
+                None => {
+                    for comment in self.leading_comments(index) {
+                        w.comment(comment)?;
+                    }
+                    block.write_to(w)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ToVmf for VMFValue<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        match self {
+            VMFValue::VersionInfo(v) => v.write_to(w),
+            VMFValue::VisGroups(v) => v.write_to(w),
+            VMFValue::ViewSettings(v) => v.write_to(w),
+            VMFValue::World(v) => v.write_to(w),
+            VMFValue::Entity(v) => v.write_to(w),
+            VMFValue::Cameras(v) => v.write_to(w),
+            VMFValue::Cordon(v) => v.write_to(w),
+        }
+    }
+}
+
+impl ToVmf for VersionInfo {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(VersionInfo::BLOCK_NAME)?;
+        w.key_value("editorversion", self.editor_version)?;
+        w.key_value("editorbuild", self.editor_build)?;
+        w.key_value("mapversion", self.map_version)?;
+        w.key_value("formatversion", self.format_version)?;
+        w.key_value("prefab", self.prefab)?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for VisGroups<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(VisGroups::BLOCK_NAME)?;
+        self.0.iter().try_for_each(|group| group.write_to(w))?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for VisGroup<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(VisGroup::BLOCK_NAME)?;
+        w.key_value("name", self.name)?;
+        w.key_value("visgroupid", self.visgroupid)?;
+        w.key_value("color", color(self.color))?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.write_to(w))?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for ViewSettings {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(ViewSettings::BLOCK_NAME)?;
+        w.key_bool("bSnapToGrid", self.snap_to_grid)?;
+        w.key_bool("bShowGrid", self.show_grid)?;
+        w.key_bool("bShowLogicalGrid", self.show_logical_grid)?;
+        w.key_value("nGridSpacing", self.grid_spacing)?;
+        w.key_bool("bShow3DGrid", self.show_3d_grid)?;
+
+        // Older Hammer builds don't know about these, so they are only written when set
+        let optional = [
+            ("bHideObjects", self.hide_objects),
+            ("bHideWalls", self.hide_walls),
+            ("bHideStripes", self.hide_stripes),
+            ("bHideNeighbors", self.hide_neighbors),
+            ("bHideDetail", self.hide_detail),
+            ("bShowBrushes", self.show_brushes),
+            ("bShowEntities", self.show_entities),
+            ("bShowLightRadius", self.show_light_radius),
+            ("bShowLightingPreview", self.show_lighting_preview),
+            ("bShowWireframe", self.show_wireframe),
+        ];
+        for (key, value) in optional {
+            if value {
+                w.key_bool(key, value)?;
+            }
+        }
+        w.end_block()
+    }
+}
+
+impl ToVmf for World<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(World::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        w.key_value("mapversion", self.mapversion)?;
+        w.key_value("classname", &self.classname)?;
+        w.key_value_opt("detailmaterial", self.detailmaterial.as_deref())?;
+        w.key_value_opt("detailvbsp", self.detailvbsp.as_deref())?;
+        w.key_value_opt("maxpropscreenwidth", self.maxpropscreenwidth)?;
+        w.key_value_opt("skyname", self.skyname.as_deref())?;
+        w.key_value_opt("sounds", self.sounds)?;
+        w.key_value_opt("maxrange", self.maxrange.map(|v| w.float(v)))?;
+        w.key_value_opt("maxoccludeearea", self.maxoccludeearea.map(|v| w.float(v)))?;
+        w.key_value_opt("minoccluderarea", self.minoccluderarea.map(|v| w.float(v)))?;
+        w.key_value_opt(
+            "maxoccludeearea_csgo",
+            self.maxoccludeearea_csgo.map(|v| w.float(v)),
+        )?;
+        w.key_value_opt(
+            "minoccluderarea_csgo",
+            self.minoccluderarea_csgo.map(|v| w.float(v)),
+        )?;
+        w.key_value_opt("difficulty_level", self.difficulty_level)?;
+        w.key_value_opt("hdr_level", self.hdr_level)?;
+        w.key_value_opt("targetname", self.targetname.as_deref())?;
+        w.key_value_opt("target", self.target.as_deref())?;
+        properties(w, &self.properties)?;
+        if let Some(hidden) = self.hidden {
+            w.key_bool("hidden", hidden)?;
+        }
+
+        self.solids.iter().try_for_each(|solid| solid.write_to(w))?;
+        if !self.hidden_entities.is_empty() {
+            w.begin_block("hidden")?;
+            self.hidden_entities
+                .iter()
+                .try_for_each(|entity| entity.write_to(w))?;
+            w.end_block()?;
+        }
+        if let Some(group) = &self.group {
+            group.write_to(w)?;
+        }
+        if let Some(editor) = &self.editor {
+            editor.write_to(w)?;
+        }
+        w.end_block()
+    }
+}
+
+impl ToVmf for Entity<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Entity::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        w.key_value("classname", &self.classname)?;
+        w.key_value_opt("angles", self.angles.map(|p| w.point(p)))?;
+        w.key_value_opt("targetname", self.targetname.as_deref())?;
+        w.key_value_opt("parentname", self.parentname.as_deref())?;
+        w.key_value_opt("target", self.target.as_deref())?;
+        w.key_value_opt("model", self.model.as_deref())?;
+        w.key_value_opt("skin", self.skin)?;
+        w.key_value_opt("spawnflags", self.spawnflags)?;
+        w.key_value_opt("rendermode", self.rendermode)?;
+        w.key_value_opt("renderamt", self.renderamt)?;
+        w.key_value_opt("rendercolor", self.rendercolor.map(color))?;
+        w.key_value_opt("renderfx", self.renderfx)?;
+        w.key_value_opt("disablex360", self.disablex360.map(u8::from))?;
+        w.key_value_opt("disableshadows", self.disableshadows.map(u8::from))?;
+        w.key_value_opt(
+            "disablereceiveshadows",
+            self.disablereceiveshadows.map(u8::from),
+        )?;
+        w.key_value_opt("startdisabled", self.startdisabled.map(u8::from))?;
+        w.key_value_opt("fademindist", self.fademindist.map(|v| w.float(v)))?;
+        w.key_value_opt("fademaxdist", self.fademaxdist.map(|v| w.float(v)))?;
+        w.key_value_opt("fadescale", self.fadescale.map(|v| w.float(v)))?;
+        properties(w, &self.properties)?;
+        w.key_value_opt("origin", self.origin.map(|p| w.point(p)))?;
+
+        if !self.outputs.is_empty() {
+            w.begin_block("connections")?;
+            self.outputs
+                .iter()
+                .try_for_each(|output| output.write_to(w))?;
+            w.end_block()?;
+        }
+        self.solids.iter().try_for_each(|solid| solid.write_to(w))?;
+        if let Some(editor) = &self.editor {
+            editor.write_to(w)?;
+        }
+        w.end_block()
+    }
+}
+
+/// Outputs are written as a single line of the `connections` block.
+impl ToVmf for EntityOutput<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        let value = format!(
+            "{},{},{},{},{}",
+            self.target,
+            self.input,
+            self.parameter,
+            w.float(self.delay),
+            self.times_to_fire
+        );
+        w.key_value(&self.output_name, value)
+    }
+}
+
+impl ToVmf for Solid<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Solid::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        self.sides.iter().try_for_each(|side| side.write_to(w))?;
+        if let Some(editor) = &self.editor {
+            editor.write_to(w)?;
+        }
+        w.end_block()
+    }
+}
+
+impl ToVmf for Side<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Side::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        w.key_value("plane", plane(w, self.plane))?;
+        w.key_value("material", &self.material)?;
+        w.key_value("uaxis", texture_axis(w, &self.uaxis))?;
+        w.key_value("vaxis", texture_axis(w, &self.vaxis))?;
+        w.key_value("rotation", w.float(self.rotation))?;
+        w.key_value("lightmapscale", self.lightmapscale)?;
+        w.key_value("smoothing_groups", self.smoothing_groups)?;
+        if let Some(dispinfo) = &self.dispinfo {
+            dispinfo.write_to(w)?;
+        }
+        w.end_block()
+    }
+}
+
+/// Writes a block of `"rowN"` keys holding `per_row` values each. Empty blocks are left out.
+fn rows<T>(
+    w: &mut VmfWriter<'_>,
+    name: &str,
+    values: &[T],
+    per_row: usize,
+    format: impl Fn(&VmfWriter<'_>, &T) -> String,
+) -> fmt::Result {
+    if values.is_empty() {
+        return Ok(());
+    }
+    w.begin_block(name)?;
+    for (i, row) in values.chunks(per_row.max(1)).enumerate() {
+        let row: Vec<_> = row.iter().map(|value| format(w, value)).collect();
+        w.key_value(&format!("row{}", i), row.join(" "))?;
+    }
+    w.end_block()
+}
+
+/// Rows follow the displacement grid, so a power 3 displacement gets 9 rows of 9 vertices.
+impl ToVmf for DispInfo {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        // An unsupported power has no grid to follow, its values are written as one row
+        let size = self.grid_size().unwrap_or(usize::MAX);
+        let point = |w: &VmfWriter<'_>, p: &Point3D| w.point(*p);
+        let float = |w: &VmfWriter<'_>, v: &f32| w.float(*v);
+
+        w.begin_block(DispInfo::BLOCK_NAME)?;
+        w.key_value("power", self.power)?;
+        w.key_value("startposition", format!("[{}]", w.point(self.start_position)))?;
+        w.key_value("flags", self.flags)?;
+        w.key_value("elevation", w.float(self.elevation))?;
+        w.key_bool("subdiv", self.subdiv)?;
+        rows(w, "normals", &self.normals, size, point)?;
+        rows(w, "distances", &self.distances, size, float)?;
+        rows(w, "offsets", &self.offsets, size, point)?;
+        rows(w, "offset_normals", &self.offset_normals, size, point)?;
+        rows(w, "alphas", &self.alphas, size, float)?;
+        // Two triangles per grid cell
+        let tags_per_row = size.saturating_sub(1).saturating_mul(2);
+        rows(w, "triangle_tags", &self.triangle_tags, tags_per_row, |_, v| v.to_string())?;
+        if !self.allowed_verts.is_empty() {
+            w.begin_block("allowed_verts")?;
+            let verts: Vec<_> = self.allowed_verts.iter().map(i32::to_string).collect();
+            w.key_value(&verts.len().to_string(), verts.join(" "))?;
+            w.end_block()?;
+        }
+        w.end_block()
+    }
+}
+
+/// Written as a `"color" "r g b"` line, the way [`EditorData`] and visgroups store it.
+impl ToVmf for Color {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.key_value("color", color(*self))
+    }
+}
+
+impl ToVmf for EditorData<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(EditorData::BLOCK_NAME)?;
+        w.key_value("color", color(self.color))?;
+        for id in &self.visgroupids {
+            w.key_value("visgroupid", id)?;
+        }
+        w.key_value_opt("groupid", self.groupid)?;
+        w.key_bool("visgroupshown", self.visgroupshown)?;
+        w.key_bool("visgroupautoshown", self.visgroupautoshown)?;
+        w.key_value_opt("comments", self.comments.as_deref())?;
+        w.key_value_opt("logicalpos", self.logicalpos.as_deref())?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for Group<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Group::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        if let Some(editor) = &self.editor {
+            editor.write_to(w)?;
+        }
+        self.groups.iter().try_for_each(|group| group.write_to(w))?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for Cameras<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Cameras::BLOCK_NAME)?;
+        w.key_value("activecamera", self.activecamera)?;
+        self.cameras
+            .iter()
+            .try_for_each(|camera| camera.write_to(w))?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for Camera<'_> {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Camera::BLOCK_NAME)?;
+        w.key_value("id", self.id)?;
+        w.key_value("classname", self.classname)?;
+        w.key_value("origin", w.point(self.origin))?;
+        w.key_value("angles", w.point(self.angles))?;
+        w.key_value("targetname", self.targetname)?;
+        w.key_value_opt("spawnflags", self.spawnflags)?;
+        w.key_value_opt("wait", self.wait.map(|v| w.float(v)))?;
+        w.key_value_opt("acceleration", self.acceleration.map(|v| w.float(v)))?;
+        w.key_value_opt("deceleration", self.deceleration.map(|v| w.float(v)))?;
+        w.key_value_opt("speed", self.speed.map(|v| w.float(v)))?;
+        w.key_value_opt("fov", self.fov.map(|v| w.float(v)))?;
+        w.key_value_opt("fov_rate", self.fov_rate.map(|v| w.float(v)))?;
+        w.key_value_opt(
+            "use_screen_aspect_ratio",
+            self.use_screen_aspect_ratio.map(u8::from),
+        )?;
+        w.key_value_opt("interp_time", self.interp_time.map(|v| w.float(v)))?;
+        w.end_block()
+    }
+}
+
+impl ToVmf for Cordon {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        w.begin_block(Cordon::BLOCK_NAME)?;
+        w.key_value("mins", format!("({})", w.point(self.mins)))?;
+        w.key_value("maxs", format!("({})", w.point(self.maxs)))?;
+        w.key_bool("active", self.active)?;
+        w.end_block()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::lex, writer::SerializeOptions, Parser};
+
+    #[test]
+    fn test_write_side() {
+        let src = r#"side
+{
+	"id" "1"
+	"plane" "(-320 -320 0) (-320 320 0) (320 320 0)"
+	"material" "DEV/DEV_MEASUREGENERIC01B"
+	"uaxis" "[1 0 0 0] 0.25"
+	"vaxis" "[0 -1 0 0] 0.25"
+	"rotation" "0"
+	"lightmapscale" "16"
+	"smoothing_groups" "0"
+}
+"#;
+        let side = Side::parse(lex(src)).unwrap();
+        assert_eq!(side.to_vmf(), src);
+    }
+
+    #[test]
+    fn test_write_entity_with_connections() {
+        let src = r#"entity
+{
+	"id" "243"
+	"classname" "func_button"
+	"origin" "0 0 64.5"
+	connections
+	{
+		"OnIn" "motor*,TurnOn,,0,-1"
+		"OnOut" "motor*,TurnOff,,0.5,1"
+	}
+	editor
+	{
+		"color" "220 30 220"
+		"visgroupshown" "1"
+		"visgroupautoshown" "1"
+		"logicalpos" "[0 1500]"
+	}
+}
+"#;
+        let entity = Entity::parse(lex(src)).unwrap();
+        assert_eq!(entity.to_vmf(), src);
+    }
+
+    #[test]
+    fn test_write_dispinfo() {
+        let dispinfo = DispInfo {
+            power: 2,
+            start_position: Point3D::new(0.0, 0.0, 64.0),
+            normals: vec![Point3D::new(0.0, 0.0, 1.0); 25],
+            distances: (0..25).map(|i| i as f32 * 0.5).collect(),
+            alphas: vec![255.0; 25],
+            triangle_tags: vec![9; 32],
+            allowed_verts: vec![-1; 10],
+            ..Default::default()
+        };
+
+        let written = dispinfo.to_vmf();
+        assert!(written.contains("\t\"row4\" \"10 10.5 11 11.5 12\"\n"), "{}", written);
+        assert!(written.contains("\t\"row3\" \"9 9 9 9 9 9 9 9\"\n"), "{}", written);
+        assert!(!written.contains("row5"));
+        assert!(!written.contains("offsets"));
+        assert!(written.contains("\"10\" \"-1 -1 -1 -1 -1 -1 -1 -1 -1 -1\""));
+        assert_eq!(DispInfo::parse(lex(&written)).unwrap(), dispinfo);
+
+        let side = Side {
+            id: 1,
+            dispinfo: Some(dispinfo),
+            ..Default::default()
+        };
+        assert_eq!(Side::parse(lex(&side.to_vmf())).unwrap(), side);
+    }
+
+    #[test]
+    fn test_write_color() {
+        let color = Color { r: 0, g: 128, b: 255 };
+        assert_eq!(color.to_vmf(), "\"color\" \"0 128 255\"\n");
+        assert_eq!(Color::parse(lex(&color.to_vmf())).unwrap(), color);
+    }
+
+    #[test]
+    fn test_roundtrip_large_map() {
+        let Ok(src) = std::fs::read_to_string("Gm_RunDownTown.vmf") else {
+            eprintln!("Skipping large map round trip - file not found");
+            return;
+        };
+        let doc = ParsedVmf::new(crate::parse_vmf(&src).unwrap());
+        let written = doc.to_vmf();
+        assert_eq!(crate::parse_vmf(&written).unwrap(), doc.blocks);
+    }
+
+    #[test]
+    fn test_write_sorted_keys() {
+        let mut entity = Entity {
+            id: 1,
+            classname: "light".into(),
+            ..Default::default()
+        };
+        for key in ["style", "_light", "pattern", "_quadratic_attn", "_constant_attn"] {
+            entity.properties.insert(key.into(), "0".into());
+        }
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+
+        let written = entity.to_vmf_with(&options);
+        let keys: Vec<_> = written
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('"')?.split('"').next())
+            .skip(2)
+            .collect();
+        assert_eq!(
+            keys,
+            ["_constant_attn", "_light", "_quadratic_attn", "pattern", "style"]
+        );
+        assert_eq!(entity.to_vmf_with(&options), written);
+    }
+
+    #[test]
+    fn test_write_keeps_property_order() {
+        let src = concat!(
+            "entity\n{\n\t\"id\" \"1\"\n\t\"classname\" \"light\"\n",
+            "\t\"_lightHDR\" \"-1 -1 -1 1\"\n\t\"_light\" \"255 255 255 200\"\n}\n",
+        );
+        let entity = Entity::parse(lex(src)).unwrap();
+        let keys: Vec<_> = entity.properties.keys().map(|key| key.as_ref()).collect();
+        assert_eq!(keys, ["_lightHDR", "_light"]);
+        assert_eq!(entity.get_property("_light"), Some("255 255 255 200"));
+        assert_eq!(entity.to_vmf(), src);
+
+        let reparsed = Entity::parse(lex(&entity.to_vmf())).unwrap().into_owned();
+        let pairs: Vec<_> = reparsed.properties.into_iter().collect();
+        assert_eq!(
+            pairs,
+            [
+                ("_lightHDR".into(), "-1 -1 -1 1".into()),
+                ("_light".into(), "255 255 255 200".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_cordon() {
+        let src = "cordon\n{\n\t\"mins\" \"(-1024 -1024 -1024)\"\n\t\"maxs\" \"(1024 1024 1024)\"\n\t\"active\" \"0\"\n}\n";
+        let cordon = Cordon::parse(lex(src)).unwrap();
+        assert_eq!(cordon.to_vmf(), src);
+    }
+}
@@ -0,0 +1,170 @@
+use std::{fmt, iter::Peekable};
+
+use super::{ToVmf, VmfWriter};
+use crate::{document::ParsedVmf, Token, TokenIter};
+
+/// A node of a generic KeyValues tree, see [`to_keyvalues`].
+///
+/// Unlike the typed blocks, a node only knows its key and either a text value or child
+/// nodes, so it can be handed to tools that understand Valve's KeyValues format but not
+/// VMF.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KvNode {
+    pub key: String,
+    pub value: KvValue,
+}
+
+/// The value of a [`KvNode`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum KvValue {
+    /// A `"key" "value"` pair.
+    Text(String),
+    /// A nested block.
+    Block(Vec<KvNode>),
+}
+
+impl KvNode {
+    /// Returns the text value, `None` for a block.
+    pub fn text(&self) -> Option<&str> {
+        match &self.value {
+            KvValue::Text(text) => Some(text),
+            KvValue::Block(_) => None,
+        }
+    }
+
+    /// Returns the child nodes, empty for a text value.
+    pub fn children(&self) -> &[KvNode] {
+        match &self.value {
+            KvValue::Text(_) => &[],
+            KvValue::Block(children) => children,
+        }
+    }
+
+    /// Returns the first child with the given key. Keys are matched case-insensitively,
+    /// like KeyValues does.
+    pub fn child(&self, key: &str) -> Option<&KvNode> {
+        self.children()
+            .iter()
+            .find(|child| child.key.eq_ignore_ascii_case(key))
+    }
+}
+
+/// Writes the node in KeyValues text format, which uses the same syntax as VMF.
+///
+/// A block with an empty key, like the root returned by [`to_keyvalues`], writes only its
+/// children.
+impl ToVmf for KvNode {
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+        match &self.value {
+            KvValue::Text(text) => w.key_value(&self.key, text),
+            KvValue::Block(children) if self.key.is_empty() => {
+                children.iter().try_for_each(|child| child.write_to(w))
+            }
+            KvValue::Block(children) => {
+                w.begin_block(&self.key)?;
+                for child in children {
+                    child.write_to(w)?;
+                }
+                w.end_block()
+            }
+        }
+    }
+}
+
+/// Converts the document into a generic KeyValues tree mirroring the VMF structure.
+///
+/// The root has an empty key and one child per top-level block (`versioninfo`, `world`,
+/// `entity`, ...). Values are written the way [`ToVmf`] writes them, so numbers, points and
+/// planes end up as their VMF text.
+///
+/// usage:
+/// ```ignore
+/// let tree = to_keyvalues(&doc);
+/// let world = tree.child("world").unwrap();
+/// println!("{:?}", world.child("classname").and_then(KvNode::text));
+/// ```
+pub fn to_keyvalues(doc: &ParsedVmf) -> KvNode {
+    let text = doc.to_vmf();
+    let mut tokens = TokenIter::new(&text).filter_map(Result::ok).peekable();
+    KvNode {
+        key: String::new(),
+        value: KvValue::Block(read_nodes(&mut tokens)),
+    }
+}
+
+/// Reads nodes until the closing brace of the current block or the end of input.
+fn read_nodes<'a>(tokens: &mut Peekable<impl Iterator<Item = Token<'a>>>) -> Vec<KvNode> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let key = match token {
+            Token::Ident(key) | Token::QuotedText(key) => key.to_string(),
+            Token::RBracket => break,
+            _ => continue,
+        };
+        let value = match tokens.peek() {
+            Some(Token::LBracket) => {
+                tokens.next();
+                KvValue::Block(read_nodes(tokens))
+            }
+            Some(&Token::QuotedText(text)) => {
+                tokens.next();
+                KvValue::Text(text.to_string())
+            }
+            _ => continue,
+        };
+        nodes.push(KvNode { key, value });
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VMF;
+
+    #[test]
+    fn test_to_keyvalues() {
+        let vmf: VMF = r#"
+        versioninfo
+        {
+            "editorversion" "400"
+            "editorbuild" "6157"
+            "mapversion" "16"
+            "formatversion" "100"
+            "prefab" "0"
+        }
+        world
+        {
+            "id" "1"
+            "classname" "worldspawn"
+            solid
+            {
+                "id" "2"
+                side
+                {
+                    "id" "3"
+                    "plane" "(0 0 0) (0 1 0) (1 1 0)"
+                    "material" "TOOLS/TOOLSNODRAW"
+                }
+            }
+        }
+        "#
+        .parse()
+        .unwrap();
+        let doc = vmf.parse_document().unwrap();
+
+        let tree = to_keyvalues(&doc);
+        assert_eq!(tree.key, "");
+        assert_eq!(tree.children().len(), 2);
+
+        let world = tree.child("world").unwrap();
+        assert_eq!(world.child("classname").and_then(KvNode::text), Some("worldspawn"));
+        let side = world.child("solid").and_then(|solid| solid.child("side")).unwrap();
+        assert_eq!(side.child("material").and_then(KvNode::text), Some("TOOLS/TOOLSNODRAW"));
+        assert_eq!(side.child("PLANE").and_then(KvNode::text), Some("(0 0 0) (0 1 0) (1 1 0)"));
+        assert!(side.child("missing").is_none());
+
+        // The tree writes back out as the same text
+        assert_eq!(tree.to_vmf(), doc.to_vmf());
+    }
+}
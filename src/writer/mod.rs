@@ -0,0 +1,273 @@
+mod blocks;
+mod keyvalues;
+mod subset;
+
+use std::fmt::{self, Display, Write};
+
+use crate::types::Point3D;
+
+pub use keyvalues::{to_keyvalues, KvNode, KvValue};
+pub use subset::write_vmf_subset;
+
+/// A type that can be written back out in VMF format.
+///
+/// The output uses the same layout Hammer writes: `"key" "value"` pairs, one per line,
+/// nested blocks and tab indentation. Parsing the output again yields an equivalent value.
+///
+/// usage:
+/// ```ignore
+/// use mnk_vmf::writer::ToVmf;
+///
+/// let mut out = String::new();
+/// world.write_vmf(&mut out)?;
+/// ```
+pub trait ToVmf {
+    /// Writes `self` through `w`, which keeps track of the current indentation.
+    fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result;
+
+    /// Writes `self` into `out`, starting at the top level.
+    fn write_vmf(&self, out: &mut impl Write) -> fmt::Result {
+        self.write_to(&mut VmfWriter::new(out))
+    }
+
+    /// Writes `self` into `out` using `options`, starting at the top level.
+    fn write_vmf_with(&self, out: &mut impl Write, options: &SerializeOptions) -> fmt::Result {
+        self.write_to(&mut VmfWriter::with_options(out, options.clone()))
+    }
+
+    /// Writes `self` into a new `String`.
+    fn to_vmf(&self) -> String {
+        self.to_vmf_with(&SerializeOptions::default())
+    }
+
+    /// Writes `self` into a new `String` using `options`.
+    fn to_vmf_with(&self, options: &SerializeOptions) -> String {
+        let mut out = String::new();
+        // Writing into a String can't fail
+        let _ = self.write_vmf_with(&mut out, options);
+        out
+    }
+}
+
+/// How floating point values are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// The shortest representation that reads back as the same value, like Hammer writes
+    /// them: `64`, `0.25`, `-0.0078125`.
+    #[default]
+    Shortest,
+    /// A fixed number of decimals: `Fixed(6)` writes `64.000000`.
+    Fixed(usize),
+}
+
+/// How nested lines are indented.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// One tab per level, like Hammer writes.
+    #[default]
+    Tab,
+    /// The given number of spaces per level.
+    Spaces(usize),
+}
+
+/// Settings for writing VMF output.
+///
+/// usage:
+/// ```ignore
+/// let options = SerializeOptions {
+///     float_format: FloatFormat::Fixed(6),
+///     ..Default::default()
+/// };
+/// let text = world.to_vmf_with(&options);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SerializeOptions {
+    pub float_format: FloatFormat,
+    pub indent: Indent,
+
+    /// Copy blocks of a [`ParsedVmf`](crate::ParsedVmf) that weren't edited straight from
+    /// the source instead of rendering them again, so changing one value only changes that
+    /// block in the output. See [`ParsedVmf::edit`](crate::ParsedVmf::edit).
+    pub preserve_source: bool,
+
+    /// Write the custom keyvalues of worlds and entities sorted by key instead of in the
+    /// order they were parsed or inserted, so maps built from different sources list them
+    /// the same way.
+    pub sort_keys: bool,
+}
+
+/// Low-level VMF output, used by [`ToVmf`] implementations.
+pub struct VmfWriter<'a> {
+    out: &'a mut dyn Write,
+    depth: usize,
+    options: SerializeOptions,
+}
+
+impl<'a> VmfWriter<'a> {
+    /// Creates a writer that appends to `out`, starting at the top level.
+    pub fn new(out: &'a mut dyn Write) -> Self {
+        Self::with_options(out, SerializeOptions::default())
+    }
+
+    /// Creates a writer that appends to `out` and formats values according to `options`.
+    pub fn with_options(out: &'a mut dyn Write, options: SerializeOptions) -> Self {
+        Self {
+            out,
+            depth: 0,
+            options,
+        }
+    }
+
+    fn indent(&mut self) -> fmt::Result {
+        for _ in 0..self.depth {
+            match self.options.indent {
+                Indent::Tab => self.out.write_char('\t')?,
+                Indent::Spaces(width) => write!(self.out, "{:width$}", "")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the block `name` and its opening brace, and indents what follows.
+    pub fn begin_block(&mut self, name: &str) -> fmt::Result {
+        self.indent()?;
+        writeln!(self.out, "{}", name)?;
+        self.indent()?;
+        writeln!(self.out, "{{")?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Closes the innermost block opened with [`begin_block`](Self::begin_block).
+    pub fn end_block(&mut self) -> fmt::Result {
+        self.depth = self.depth.saturating_sub(1);
+        self.indent()?;
+        writeln!(self.out, "}}")
+    }
+
+    /// Writes a whole `// ...` comment line at the current indentation.
+    pub fn comment(&mut self, comment: &str) -> fmt::Result {
+        self.indent()?;
+        writeln!(self.out, "{}", comment)
+    }
+
+    /// Writes `text` as is, without indenting it.
+    pub fn raw(&mut self, text: &str) -> fmt::Result {
+        self.out.write_str(text)
+    }
+
+    /// Writes a `"key" "value"` line.
+    pub fn key_value(&mut self, key: &str, value: impl Display) -> fmt::Result {
+        self.indent()?;
+        writeln!(self.out, "\"{}\" \"{}\"", key, value)
+    }
+
+    /// Writes a `"key" "value"` line if `value` is set.
+    pub fn key_value_opt(&mut self, key: &str, value: Option<impl Display>) -> fmt::Result {
+        match value {
+            Some(value) => self.key_value(key, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a boolean as `"0"` or `"1"`.
+    pub fn key_bool(&mut self, key: &str, value: bool) -> fmt::Result {
+        self.key_value(key, u8::from(value))
+    }
+
+    /// Formats a float according to the [`FloatFormat`] of this writer. By default this is
+    /// the way Hammer does it, using as few digits as possible.
+    pub fn float(&self, value: f32) -> String {
+        match self.options.float_format {
+            FloatFormat::Shortest => value.to_string(),
+            FloatFormat::Fixed(decimals) => format!("{:.*}", decimals, value),
+        }
+    }
+
+    /// Formats a point as `x y z`.
+    pub fn point(&self, point: Point3D) -> String {
+        format!(
+            "{} {} {}",
+            self.float(point.x),
+            self.float(point.y),
+            self.float(point.z)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sample;
+
+    impl ToVmf for Sample {
+        fn write_to(&self, w: &mut VmfWriter<'_>) -> fmt::Result {
+            w.begin_block("outer")?;
+            w.key_value("id", 1)?;
+            w.begin_block("inner")?;
+            w.key_bool("flag", true)?;
+            w.key_value_opt("missing", None::<&str>)?;
+            w.key_value("origin", w.point(Point3D::new(0.5, -0.0, 64.0)))?;
+            w.end_block()?;
+            w.end_block()
+        }
+    }
+
+    #[test]
+    fn test_writer_layout() {
+        assert_eq!(
+            Sample.to_vmf(),
+            "outer\n{\n\t\"id\" \"1\"\n\tinner\n\t{\n\t\t\"flag\" \"1\"\n\t\t\"origin\" \"0.5 -0 64\"\n\t}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_indent() {
+        let tabs = SerializeOptions {
+            indent: Indent::Tab,
+            ..Default::default()
+        };
+        assert_eq!(Sample.to_vmf_with(&tabs), Sample.to_vmf());
+
+        let spaces = SerializeOptions {
+            indent: Indent::Spaces(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            Sample.to_vmf_with(&spaces),
+            "outer\n{\n  \"id\" \"1\"\n  inner\n  {\n    \"flag\" \"1\"\n    \"origin\" \"0.5 -0 64\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_float_format_shortest() {
+        let mut out = String::new();
+        let w = VmfWriter::new(&mut out);
+        assert_eq!(w.float(64.0), "64");
+        assert_eq!(w.float(0.25), "0.25");
+        assert_eq!(w.float(-0.0078125), "-0.0078125");
+        assert_eq!(w.float(0.1), "0.1");
+    }
+
+    #[test]
+    fn test_float_format_fixed() {
+        let options = SerializeOptions {
+            float_format: FloatFormat::Fixed(6),
+            ..Default::default()
+        };
+        let mut out = String::new();
+        let w = VmfWriter::with_options(&mut out, options.clone());
+        assert_eq!(w.float(64.0), "64.000000");
+        assert_eq!(w.float(0.25), "0.250000");
+        assert_eq!(w.float(-0.0078125), "-0.007812");
+        assert_eq!(
+            w.point(Point3D::new(1.0, 0.5, -2.0)),
+            "1.000000 0.500000 -2.000000"
+        );
+
+        assert!(Sample
+            .to_vmf_with(&options)
+            .contains("\"origin\" \"0.500000 -0.000000 64.000000\""));
+    }
+}
@@ -0,0 +1,109 @@
+use super::ToVmf;
+use crate::{
+    document::{block_max_id, canonical_rank},
+    types::{VersionInfo, World},
+    VMFValue,
+};
+
+/// Writes a selection of top-level blocks as a standalone VMF.
+///
+/// Hammer needs a `versioninfo` and a `world` block to load a file, so they are
+/// synthesized if the selection lacks them: a Hammer 4.0 header and an empty `worldspawn`
+/// with the next free id, or id 0 if the selection already uses `u32::MAX`. The blocks
+/// are written in the conventional Hammer order, see
+/// [`ParsedVmf::reorder_canonical`](crate::ParsedVmf::reorder_canonical).
+///
+/// usage:
+/// ```ignore
+/// let selected: Vec<_> = doc.blocks.iter().filter(|block| is_selected(block)).collect();
+/// std::fs::write("selection.vmf", write_vmf_subset(&selected))?;
+/// ```
+pub fn write_vmf_subset(blocks: &[&VMFValue]) -> String {
+    let has = |matches: fn(&VMFValue) -> bool| blocks.iter().any(|block| matches(block));
+
+    let version_info = (!has(|block| matches!(block, VMFValue::VersionInfo(_))))
+        .then(|| VMFValue::VersionInfo(VersionInfo::new(400, 6157, 1, 100, 0)));
+    let world = (!has(|block| matches!(block, VMFValue::World(_)))).then(|| {
+        let max_id = blocks.iter().map(|block| block_max_id(block)).fold(0, u32::max);
+        VMFValue::World(Box::new(World {
+            id: max_id.checked_add(1).unwrap_or(0),
+            mapversion: 1,
+            classname: "worldspawn".into(),
+            ..Default::default()
+        }))
+    });
+
+    let mut all: Vec<&VMFValue> = blocks.to_vec();
+    all.extend(version_info.iter().chain(&world));
+    all.sort_by_key(|block| canonical_rank(block));
+
+    let mut out = String::new();
+    for block in all {
+        // Writing into a String can't fail
+        let _ = block.write_vmf(&mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VMF;
+
+    #[test]
+    fn test_write_vmf_subset() {
+        let vmf: VMF = include_str!("../../test.vmf").parse().unwrap();
+        let doc = vmf.parse_document().unwrap();
+        let selected: Vec<_> = doc
+            .blocks
+            .iter()
+            .filter(|block| matches!(block, VMFValue::Entity(_)))
+            .take(2)
+            .collect();
+
+        let written = write_vmf_subset(&selected);
+        let subset: VMF = written.parse().unwrap();
+        let subset = subset.parse_document().unwrap();
+
+        assert!(subset.blocks_in_canonical_order());
+        assert!(subset.version_info().is_some());
+        let world = subset.world().unwrap();
+        assert_eq!(world.classname, "worldspawn");
+        assert!(world.solids.is_empty());
+
+        let entities: Vec<_> = subset.entities().collect();
+        let expected: Vec<_> = doc.entities().take(2).collect();
+        assert_eq!(entities, expected);
+        assert!(entities.iter().all(|entity| entity.id != world.id));
+
+        // A selection that has its own header and world keeps them
+        let full: Vec<_> = doc.blocks.iter().collect();
+        let written = write_vmf_subset(&full);
+        let reparsed: VMF = written.parse().unwrap();
+        assert_eq!(reparsed.parse().unwrap(), doc.blocks);
+    }
+
+    #[test]
+    fn test_write_vmf_subset_world_id() {
+        let vmf: VMF = r#"
+        entity
+        {
+            "id" "7"
+            "classname" "func_brush"
+            solid { "id" "12" side { "id" "30" } }
+        }
+        entity { "id" "4294967295" "classname" "info_target" }
+        "#
+        .parse()
+        .unwrap();
+        let blocks = vmf.parse().unwrap();
+
+        let world_id = |selected: &[&VMFValue]| {
+            let written = write_vmf_subset(selected);
+            let subset: VMF = written.parse().unwrap();
+            subset.parse_document().unwrap().world().unwrap().id
+        };
+        assert_eq!(world_id(&[&blocks[0]]), 31);
+        assert_eq!(world_id(&[&blocks[0], &blocks[1]]), 0);
+    }
+}
@@ -0,0 +1,41 @@
+//! Checks that the public API can be used from the crate root, the way the docs show it.
+
+use mnk_vmf::{
+    parse_vmf, EntityFilter, ParseDiagnostic, ParseOptions, ParseTiming, ParsedVmf, Parser,
+    VMFError, VMFValue, VmfBlock, VMF,
+};
+
+#[test]
+fn test_root_imports() {
+    let vmf: VMF = r#"world { "id" "1" "classname" "worldspawn" }"#.parse().unwrap();
+    let blocks: Vec<VMFValue> = vmf.parse().unwrap();
+    assert!(matches!(&blocks[..], [VMFValue::World(_)]));
+
+    let doc: ParsedVmf = vmf.parse_document().unwrap();
+    assert_eq!(doc.worlds().count(), 1);
+
+    let err: VMFError = parse_vmf("}").unwrap_err();
+    assert!(matches!(err, VMFError::ParseErrorAt { line: 1, column: 1, .. }));
+
+    let filter: EntityFilter = Box::new(|classname| classname == "light");
+    let options = ParseOptions {
+        entity_filter: Some(filter),
+        ..Default::default()
+    };
+    assert_eq!(vmf.parse_with(&options).unwrap().len(), 1);
+
+    let (_, timing): (_, ParseTiming) = vmf.parse_timed().unwrap();
+    assert!(timing.total() >= timing.parse);
+    let (_, diagnostics): (_, Vec<ParseDiagnostic>) = vmf.parse_recovering();
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_parser_trait_from_root() {
+    use mnk_vmf::types::World;
+
+    let src = r#"world { "id" "1" "classname" "worldspawn" }"#;
+    let world = World::parse(mnk_vmf::util::lex(src)).unwrap();
+    assert_eq!(world.id, 1);
+    assert_eq!(World::BLOCK_NAME, "world");
+}
@@ -0,0 +1,44 @@
+//! Top-level blocks may come in any order, e.g. in files merged by external tools.
+
+use mnk_vmf::{VMFValue, VMF};
+
+#[test]
+fn test_entity_before_versioninfo() {
+    let src = r#"
+entity
+{
+	"id" "2"
+	"classname" "info_player_start"
+	"origin" "0 0 0"
+}
+versioninfo
+{
+	"editorversion" "400"
+	"editorbuild" "8000"
+	"mapversion" "1"
+	"formatversion" "100"
+	"prefab" "0"
+}
+world
+{
+	"id" "1"
+	"classname" "worldspawn"
+}
+"#;
+    let vmf: VMF = src.parse().unwrap();
+
+    let blocks = vmf.parse().unwrap();
+    assert!(matches!(
+        &blocks[..],
+        [
+            VMFValue::Entity(_),
+            VMFValue::VersionInfo(_),
+            VMFValue::World(_)
+        ]
+    ));
+
+    let doc = vmf.parse_document().unwrap();
+    assert!(!doc.blocks_in_canonical_order());
+    assert_eq!(doc.version_info().unwrap().editor_build, 8000);
+    assert_eq!(doc.entities().next().unwrap().classname, "info_player_start");
+}